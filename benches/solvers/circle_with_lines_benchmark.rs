@@ -18,7 +18,7 @@ use super::{Benchmark, BenchmarkFactory};
 pub fn circle(n: usize) -> Vec<Vector2<f64>> {
     let mut points = Vec::new();
     for i in 0..n {
-        let x = ((i + 1) / 2) as f64 * 0.8;
+        let x = i.div_ceil(2) as f64 * 0.8;
         let y = (i / 2) as f64 * 0.8;
         points.push(Vector2::new(x, y));
     }
@@ -77,8 +77,8 @@ impl BenchmarkFactory for CirclesWithLinesBenchmarkFactory {
                 ))))
                 .unwrap();
 
-            let angle = (&reference_points[i + 1] - &reference_points[i])
-                .angle(&(&reference_points[(i + n - 1) % n] - &reference_points[i]));
+            let angle = (reference_points[i + 1] - reference_points[i])
+                .angle(&(reference_points[(i + n - 1) % n] - reference_points[i]));
             sketch
                 .borrow_mut()
                 .add_constraint(ConstraintCell::AngleBetweenPoints(Rc::new(RefCell::new(
@@ -107,10 +107,10 @@ pub struct CirclesWithLinesBenchmark {
 impl Benchmark for CirclesWithLinesBenchmark {
     fn check(&self, eps: f64) -> bool {
         let reference_points = circle(self.point_references.len());
-        for i in 0..self.point_references.len() {
-            let point = self.point_references[i].borrow();
-            let true_x = reference_points[i].x;
-            let true_y = reference_points[i].y;
+        for (point, reference) in self.point_references.iter().zip(reference_points.iter()) {
+            let point = point.borrow();
+            let true_x = reference.x;
+            let true_y = reference.y;
             if (point.x() - true_x).abs() > eps || (point.y() - true_y).abs() > eps {
                 return false;
             }
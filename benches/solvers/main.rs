@@ -4,7 +4,11 @@ use std::{cell::RefCell, rc::Rc};
 
 use isotope::sketch::Sketch;
 use isotope::solvers::bfgs_solver::BFGSSolver;
+use isotope::solvers::dogleg_solver::DoglegSolver;
+use isotope::solvers::gauss_newton_solver::GaussNewtonSolver;
 use isotope::solvers::gradient_based_solver::GradientBasedSolver;
+use isotope::solvers::lbfgs_solver::LBFGSSolver;
+use isotope::solvers::levenberg_marquardt::LevenbergMarquardtSolver;
 use isotope::solvers::Solver;
 
 use crate::circle_with_lines_benchmark::CirclesWithLinesBenchmarkFactory;
@@ -53,6 +57,13 @@ fn test_benchmark1() -> Vec<(String, Vec<BenchmarkResults>)> {
     let solvers: Vec<(&str, Box<dyn Solver>)> = vec![
         ("GradientBasedSolver", Box::new(GradientBasedSolver::new())),
         ("BFGSSolver", Box::new(BFGSSolver::new())),
+        ("LBFGSSolver", Box::new(LBFGSSolver::new())),
+        ("GaussNewtonSolver", Box::new(GaussNewtonSolver::new())),
+        (
+            "LevenbergMarquardtSolver",
+            Box::new(LevenbergMarquardtSolver::new()),
+        ),
+        ("DoglegSolver", Box::new(DoglegSolver::new())),
     ];
 
     let mut all_results = vec![];
@@ -115,7 +115,7 @@ impl Benchmark for StairsWithLinesBenchmark {
     fn check(&self, eps: f64) -> bool {
         for i in 0..self.point_references.len() - 1 {
             let point = self.point_references[i].as_ref().borrow();
-            let true_x = ((i + 1) / 2) as f64 * 0.8;
+            let true_x = i.div_ceil(2) as f64 * 0.8;
             let true_y = (i / 2) as f64 * 0.8;
             if (point.x() - true_x).abs() > eps || (point.y() - true_y).abs() > eps {
                 return false;
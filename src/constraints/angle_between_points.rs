@@ -1,5 +1,6 @@
 use std::{cell::RefCell, rc::Rc};
 
+use nalgebra::{DVector, DVectorView};
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "tsify")]
@@ -103,13 +104,13 @@ impl ConstraintLike for AngleBetweenPoints {
         ]
     }
 
-    fn loss_value(&self) -> f64 {
+    fn residuals(&self) -> DVector<f64> {
         let theta = self.current_angle();
 
-        0.5 * (theta - self.desired_angle) * (theta - self.desired_angle)
+        DVector::from_row_slice(&[theta - self.desired_angle])
     }
 
-    fn update_gradient(&mut self) {
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
         let point1 = self.point1.borrow().data();
         let point2 = self.point2.borrow().data();
         let middle_point = self.middle_point.borrow().data();
@@ -149,18 +150,24 @@ impl ConstraintLike for AngleBetweenPoints {
             return;
         }
 
-        let grad_loss = theta - self.desired_angle;
+        let grad_from_theta = weights[0];
 
-        let grad_from_d1 = grad_loss
+        let grad_from_d1 = grad_from_theta
             * grad_theta_from_cos_theta
             * grad_cos_theta_from_dot_product
             * grad_dot_product_from_d1
-            + grad_loss * grad_theta_from_cos_theta * grad_cos_theta_from_norm1 * grad_norm_from_d1;
-        let grad_from_d2 = grad_loss
+            + grad_from_theta
+                * grad_theta_from_cos_theta
+                * grad_cos_theta_from_norm1
+                * grad_norm_from_d1;
+        let grad_from_d2 = grad_from_theta
             * grad_theta_from_cos_theta
             * grad_cos_theta_from_dot_product
             * grad_dot_product_from_d2
-            + grad_loss * grad_theta_from_cos_theta * grad_cos_theta_from_norm2 * grad_norm_to_d2;
+            + grad_from_theta
+                * grad_theta_from_cos_theta
+                * grad_cos_theta_from_norm2
+                * grad_norm_to_d2;
 
         self.point1
             .borrow_mut()
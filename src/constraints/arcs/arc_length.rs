@@ -0,0 +1,216 @@
+use std::{cell::RefCell, f64::consts::TAU, rc::Rc};
+
+use nalgebra::{DVector, DVectorView};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::ConstraintLike,
+    primitives::{arc::Arc, PrimitiveCell},
+};
+
+// This is a sketch constraint that fixes the length of an arc, i.e. its radius times the angle it
+// sweeps from the start to the end angle in its own direction. An arc covers less than a full
+// circle, so lengths of 2pi times the radius or more cannot be reached.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct ArcLength {
+    arc: Rc<RefCell<Arc>>,
+
+    desired_length: f64,
+}
+
+impl ArcLength {
+    pub fn new(arc: Rc<RefCell<Arc>>, desired_length: f64) -> Self {
+        assert!(desired_length.is_finite());
+        Self {
+            arc,
+            desired_length,
+        }
+    }
+
+    pub fn arc(&self) -> Rc<RefCell<Arc>> {
+        self.arc.clone()
+    }
+
+    pub fn set_arc(&mut self, arc: Rc<RefCell<Arc>>) {
+        self.arc = arc;
+    }
+
+    pub fn desired_length(&self) -> f64 {
+        self.desired_length
+    }
+
+    pub fn set_desired_length(&mut self, desired_length: f64) {
+        self.desired_length = desired_length;
+    }
+
+    pub fn current_length(&self) -> f64 {
+        let arc = self.arc.borrow();
+        arc.radius() * arc.sweep_angle()
+    }
+
+    // The sweep angle wraps from almost 2pi to 0 when the end of an almost full arc passes its
+    // start. Near a desired sweep close to 2pi, the wrapped sweep is counted as a full turn more, so
+    // the residual stays continuous there and the arc does not collapse.
+    fn unwrapped_sweep_angle(&self) -> f64 {
+        let arc = self.arc.borrow();
+        let sweep_angle = arc.sweep_angle();
+        let desired_sweep_angle = self.desired_length / arc.radius();
+        if (sweep_angle + TAU - desired_sweep_angle).abs()
+            < (sweep_angle - desired_sweep_angle).abs()
+        {
+            sweep_angle + TAU
+        } else {
+            sweep_angle
+        }
+    }
+}
+
+impl ConstraintLike for ArcLength {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![PrimitiveCell::Arc(self.arc.clone())]
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let err = self.arc.borrow().radius() * self.unwrapped_sweep_angle() - self.desired_length;
+        DVector::from_row_slice(&[err])
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let radius = self.arc.borrow().radius();
+        let sweep_angle = self.unwrapped_sweep_angle();
+
+        let grad_from_err = weights[0];
+        let grad_length_from_radius = sweep_angle;
+        let grad_length_from_sweep_angle = radius;
+
+        let grad_radius = self.arc.borrow().radius_gradient();
+        let grad_start_angle = self.arc.borrow().start_angle_gradient();
+        let grad_end_angle = self.arc.borrow().end_angle_gradient();
+
+        // A counter clockwise arc sweeps from the start to the end angle, a clockwise one the other way
+        let grad_sweep_angle = if self.arc.borrow().clockwise() {
+            grad_start_angle - grad_end_angle
+        } else {
+            grad_end_angle - grad_start_angle
+        };
+
+        self.arc.borrow_mut().add_to_gradient(
+            (grad_from_err
+                * (grad_length_from_radius * grad_radius
+                    + grad_length_from_sweep_angle * grad_sweep_angle))
+                .as_view(),
+        );
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::ArcLength(self.clone())
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, f64::consts::TAU, rc::Rc};
+
+    use crate::{
+        constraints::{
+            arcs::arc_length::ArcLength,
+            radius::{fixed_radius::FixedRadius, RadiusPrimitive},
+            ConstraintCell, ConstraintLike,
+        },
+        primitives::{arc::Arc, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    #[test]
+    fn test_arc_length() {
+        // Both arcs start with a sweep of one radian
+        for (clockwise, start_angle, end_angle) in [(false, 0.0, 1.0), (true, 1.0, 0.0)] {
+            let mut sketch = Sketch::new();
+
+            let center = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+            let arc = Rc::new(RefCell::new(Arc::new(
+                center.clone(),
+                1.0,
+                clockwise,
+                start_angle,
+                end_angle,
+            )));
+            sketch
+                .add_primitive(PrimitiveCell::Point2(center.clone()))
+                .unwrap();
+            sketch
+                .add_primitive(PrimitiveCell::Arc(arc.clone()))
+                .unwrap();
+
+            let constr1 = Rc::new(RefCell::new(ArcLength::new(arc.clone(), 3.0)));
+            sketch
+                .add_constraint(ConstraintCell::ArcLength(constr1.clone()))
+                .unwrap();
+
+            sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+            let solver = BFGSSolver::new();
+            solver.solve(&mut sketch).unwrap();
+
+            println!("arc: {:?}", arc.as_ref().borrow());
+            println!("length: {:?}", constr1.borrow().current_length());
+
+            assert!(constr1.borrow().loss_value() < 1e-10);
+            assert!((constr1.borrow().current_length() - 3.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_arc_length_almost_full_circle() {
+        let mut sketch = Sketch::new();
+
+        // The end of the arc has just passed its start, so it sweeps only 0.02 radians
+        let center = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let arc = Rc::new(RefCell::new(Arc::new(
+            center.clone(),
+            1.0,
+            false,
+            0.0,
+            0.02,
+        )));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(center.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Arc(arc.clone()))
+            .unwrap();
+
+        let desired_length = TAU - 0.05;
+        let constr1 = Rc::new(RefCell::new(ArcLength::new(arc.clone(), desired_length)));
+        sketch
+            .add_constraint(ConstraintCell::ArcLength(constr1.clone()))
+            .unwrap();
+        sketch
+            .add_constraint(ConstraintCell::FixedRadius(Rc::new(RefCell::new(
+                FixedRadius::new(RadiusPrimitive::Arc(arc.clone()), 1.0),
+            ))))
+            .unwrap();
+
+        // The residual does not jump by a full circle at the seam
+        assert!((constr1.borrow().residuals()[0] - 0.07).abs() < 1e-12);
+        arc.borrow_mut().set_end_angle(-0.02);
+        assert!((constr1.borrow().residuals()[0] - 0.03).abs() < 1e-12);
+        arc.borrow_mut().set_end_angle(0.02);
+
+        sketch.check_gradients(1e-7, constr1.clone(), 1e-5);
+        let solver = BFGSSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("arc: {:?}", arc.as_ref().borrow());
+        println!("length: {:?}", constr1.borrow().current_length());
+
+        assert!(constr1.borrow().loss_value() < 1e-10);
+        assert!((constr1.borrow().current_length() - desired_length).abs() < 1e-5);
+    }
+}
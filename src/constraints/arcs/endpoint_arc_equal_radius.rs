@@ -0,0 +1,166 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::{DVector, DVectorView};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::ConstraintLike,
+    primitives::{endpoint_arc::EndpointArc, PrimitiveCell},
+};
+
+// This is a sketch constraint that keeps the start and end point of an endpoint arc at the same
+// distance from its center. The sketch adds it automatically together with the arc.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct EndpointArcEqualRadius {
+    arc: Rc<RefCell<EndpointArc>>,
+}
+
+impl EndpointArcEqualRadius {
+    pub fn new(arc: Rc<RefCell<EndpointArc>>) -> Self {
+        Self { arc }
+    }
+
+    pub fn arc(&self) -> Rc<RefCell<EndpointArc>> {
+        self.arc.clone()
+    }
+
+    pub fn set_arc(&mut self, arc: Rc<RefCell<EndpointArc>>) {
+        self.arc = arc;
+    }
+}
+
+impl ConstraintLike for EndpointArcEqualRadius {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![PrimitiveCell::EndpointArc(self.arc.clone())]
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let start = self.arc.borrow().start_point();
+        let end = self.arc.borrow().end_point();
+        let center = self.arc.borrow().center().borrow().data();
+
+        let difference = (start - center).norm() - (end - center).norm();
+        DVector::from_row_slice(&[difference])
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let start = self.arc.borrow().start_point();
+        let end = self.arc.borrow().end_point();
+        let center = self.arc.borrow().center().borrow().data();
+
+        let d1 = start - center;
+        let d2 = end - center;
+        if d1.norm() < 1e-6 || d2.norm() < 1e-6 {
+            return;
+        }
+
+        let grad_from_difference = weights[0];
+        let grad_difference_from_d1 = d1.transpose() / d1.norm();
+        let grad_difference_from_d2 = -d2.transpose() / d2.norm();
+
+        let grad_start = self.arc.borrow().start_gradient();
+        let grad_end = self.arc.borrow().end_gradient();
+        let grad_center = self.arc.borrow().center_gradient();
+
+        self.arc.borrow_mut().add_to_gradient(
+            (grad_from_difference
+                * (grad_difference_from_d1 * (grad_start - grad_center)
+                    + grad_difference_from_d2 * (grad_end - grad_center)))
+                .as_view(),
+        );
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::EndpointArcEqualRadius(self.clone())
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::{
+            arcs::endpoint_arc_equal_radius::EndpointArcEqualRadius, ConstraintCell, ConstraintLike,
+        },
+        primitives::{endpoint_arc::EndpointArc, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    #[test]
+    fn test_endpoint_arc_equal_radius() {
+        let mut sketch = Sketch::new();
+
+        let start = Rc::new(RefCell::new(Point2::new(1.0, 0.0)));
+        let end = Rc::new(RefCell::new(Point2::new(0.0, 2.0)));
+        let center = Rc::new(RefCell::new(Point2::new(0.1, -0.2)));
+        for point in [&start, &end, &center] {
+            sketch
+                .add_primitive(PrimitiveCell::Point2(point.clone()))
+                .unwrap();
+        }
+        let arc = Rc::new(RefCell::new(EndpointArc::new(
+            start.clone(),
+            end.clone(),
+            center.clone(),
+            false,
+        )));
+        sketch
+            .add_primitive(PrimitiveCell::EndpointArc(arc.clone()))
+            .unwrap();
+
+        // The equal radius constraint has been added together with the arc
+        assert_eq!(sketch.get_num_constraints(), 1);
+        let constr1 = match sketch.constraints()[0].clone() {
+            ConstraintCell::EndpointArcEqualRadius(c) => c,
+            _ => panic!("expected the equal radius constraint of the arc"),
+        };
+        assert!(Rc::ptr_eq(&constr1.borrow().arc(), &arc));
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+        let solver = BFGSSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("arc: {:?}", arc.as_ref().borrow());
+        assert!(constr1.borrow().loss_value() < 1e-10);
+
+        // Deleting the arc also removes its constraint
+        let id = sketch
+            .get_primitive_id(&PrimitiveCell::EndpointArc(arc.clone()))
+            .unwrap();
+        sketch.delete_primitive(id).unwrap();
+        assert_eq!(sketch.get_num_constraints(), 0);
+    }
+
+    #[test]
+    fn test_endpoint_arc_equal_radius_standalone() {
+        let mut sketch = Sketch::new();
+
+        let start = Rc::new(RefCell::new(Point2::new(1.0, 0.0)));
+        let end = Rc::new(RefCell::new(Point2::new(-3.0, 0.5)));
+        let center = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        for point in [&start, &end, &center] {
+            sketch
+                .add_primitive(PrimitiveCell::Point2(point.clone()))
+                .unwrap();
+        }
+        let arc = Rc::new(RefCell::new(EndpointArc::new(
+            start.clone(),
+            end.clone(),
+            center.clone(),
+            true,
+        )));
+        let constr1 = Rc::new(RefCell::new(EndpointArcEqualRadius::new(arc.clone())));
+        assert!(constr1.borrow().loss_value() > 1.0);
+        arc.borrow_mut()
+            .set_end(Rc::new(RefCell::new(Point2::new(0.0, -1.0))));
+        assert!(constr1.borrow().loss_value() < 1e-12);
+    }
+}
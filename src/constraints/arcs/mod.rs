@@ -0,0 +1,2 @@
+pub mod arc_length;
+pub mod endpoint_arc_equal_radius;
@@ -0,0 +1,276 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::{DVector, DVectorView, SMatrix, Vector2};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::{
+        bounds::{bound_violation, check_bounds},
+        ConstraintLike,
+    },
+    error::ISOTopeError,
+    primitives::{line::Line, PrimitiveCell},
+};
+
+// This is a sketch constraint that keeps the signed angle from the first to the second line, in the
+// range (-pi, pi], above a minimum and/or below a maximum angle. Leaving out one of the bounds makes
+// it one-sided.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+#[serde(try_from = "UncheckedAngleBetweenLinesBounds")]
+pub struct AngleBetweenLinesBounds {
+    line1: Rc<RefCell<Line>>,
+    line2: Rc<RefCell<Line>>,
+
+    min_angle: Option<f64>,
+    max_angle: Option<f64>,
+}
+
+impl AngleBetweenLinesBounds {
+    pub fn new(
+        line1: Rc<RefCell<Line>>,
+        line2: Rc<RefCell<Line>>,
+        min_angle: Option<f64>,
+        max_angle: Option<f64>,
+    ) -> Result<Self, ISOTopeError> {
+        check_bounds(min_angle, max_angle)?;
+        Ok(Self {
+            line1,
+            line2,
+            min_angle,
+            max_angle,
+        })
+    }
+
+    pub fn line1(&self) -> Rc<RefCell<Line>> {
+        self.line1.clone()
+    }
+
+    pub fn set_line1(&mut self, line1: Rc<RefCell<Line>>) {
+        self.line1 = line1;
+    }
+
+    pub fn line2(&self) -> Rc<RefCell<Line>> {
+        self.line2.clone()
+    }
+
+    pub fn set_line2(&mut self, line2: Rc<RefCell<Line>>) {
+        self.line2 = line2;
+    }
+
+    pub fn min_angle(&self) -> Option<f64> {
+        self.min_angle
+    }
+
+    pub fn max_angle(&self) -> Option<f64> {
+        self.max_angle
+    }
+
+    pub fn set_bounds(
+        &mut self,
+        min_angle: Option<f64>,
+        max_angle: Option<f64>,
+    ) -> Result<(), ISOTopeError> {
+        check_bounds(min_angle, max_angle)?;
+        self.min_angle = min_angle;
+        self.max_angle = max_angle;
+        Ok(())
+    }
+
+    pub fn current_angle(&self) -> f64 {
+        let (u1, u2) = self.directions();
+        let cross_product = u1.x * u2.y - u1.y * u2.x;
+        let dot_product = u1.dot(&u2);
+        cross_product.atan2(dot_product)
+    }
+
+    fn directions(&self) -> (Vector2<f64>, Vector2<f64>) {
+        let start1 = self.line1.borrow().start().borrow().data();
+        let end1 = self.line1.borrow().end().borrow().data();
+        let start2 = self.line2.borrow().start().borrow().data();
+        let end2 = self.line2.borrow().end().borrow().data();
+        (end1 - start1, end2 - start2)
+    }
+}
+
+// The fields of AngleBetweenLinesBounds as they are deserialized, before the bounds are checked
+#[derive(Deserialize)]
+struct UncheckedAngleBetweenLinesBounds {
+    line1: Rc<RefCell<Line>>,
+    line2: Rc<RefCell<Line>>,
+
+    min_angle: Option<f64>,
+    max_angle: Option<f64>,
+}
+
+impl TryFrom<UncheckedAngleBetweenLinesBounds> for AngleBetweenLinesBounds {
+    type Error = ISOTopeError;
+
+    fn try_from(unchecked: UncheckedAngleBetweenLinesBounds) -> Result<Self, Self::Error> {
+        Self::new(
+            unchecked.line1,
+            unchecked.line2,
+            unchecked.min_angle,
+            unchecked.max_angle,
+        )
+    }
+}
+
+impl ConstraintLike for AngleBetweenLinesBounds {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![
+            PrimitiveCell::Line(self.line1.clone()),
+            PrimitiveCell::Line(self.line2.clone()),
+        ]
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let (u1, u2) = self.directions();
+        if u1.norm() < 1e-6 || u2.norm() < 1e-6 {
+            return DVector::zeros(1);
+        }
+
+        let violation = bound_violation(self.current_angle(), self.min_angle, self.max_angle);
+        DVector::from_row_slice(&[violation])
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let (u1, u2) = self.directions();
+        let norm1_squared = u1.norm_squared();
+        let norm2_squared = u2.norm_squared();
+        if norm1_squared < 1e-12 || norm2_squared < 1e-12 {
+            return;
+        }
+
+        // Inside of the bounds the violation stays zero
+        let violation = bound_violation(self.current_angle(), self.min_angle, self.max_angle);
+        if violation == 0.0 {
+            return;
+        }
+
+        let grad_from_violation = weights[0];
+        let grad_theta_from_u1 =
+            SMatrix::<f64, 1, 2>::from_row_slice(&[u1.y, -u1.x]) / norm1_squared;
+        let grad_theta_from_u2 =
+            SMatrix::<f64, 1, 2>::from_row_slice(&[-u2.y, u2.x]) / norm2_squared;
+
+        let grad_start1 = self.line1.borrow().start_gradient();
+        let grad_end1 = self.line1.borrow().end_gradient();
+        let grad_start2 = self.line2.borrow().start_gradient();
+        let grad_end2 = self.line2.borrow().end_gradient();
+
+        self.line1.borrow_mut().add_to_gradient(
+            (grad_from_violation * grad_theta_from_u1 * (grad_end1 - grad_start1)).as_view(),
+        );
+        self.line2.borrow_mut().add_to_gradient(
+            (grad_from_violation * grad_theta_from_u2 * (grad_end2 - grad_start2)).as_view(),
+        );
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::AngleBetweenLinesBounds(self.clone())
+    }
+
+    fn is_inequality(&self) -> bool {
+        true
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::{
+            bounds::angle_between_lines_bounds::AngleBetweenLinesBounds, ConstraintCell,
+            ConstraintLike,
+        },
+        error::ISOTopeError,
+        primitives::{line::Line, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{levenberg_marquardt::LevenbergMarquardtSolver, Solver},
+    };
+
+    #[test]
+    fn test_angle_between_lines_bounds() {
+        let mut sketch = Sketch::new();
+
+        let line1_start = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let line1_end = Rc::new(RefCell::new(Point2::new(2.0, 0.0)));
+        let line1 = Rc::new(RefCell::new(Line::new(
+            line1_start.clone(),
+            line1_end.clone(),
+        )));
+        let line2_start = Rc::new(RefCell::new(Point2::new(0.0, 1.0)));
+        let line2_end = Rc::new(RefCell::new(Point2::new(2.0, 1.1)));
+        let line2 = Rc::new(RefCell::new(Line::new(
+            line2_start.clone(),
+            line2_end.clone(),
+        )));
+        for point in [&line1_start, &line1_end, &line2_start, &line2_end] {
+            sketch
+                .add_primitive(PrimitiveCell::Point2(point.clone()))
+                .unwrap();
+        }
+        sketch
+            .add_primitive(PrimitiveCell::Line(line1.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Line(line2.clone()))
+            .unwrap();
+
+        // The angle has to be between 10 and 80 degrees
+        let min_angle = 10.0_f64.to_radians();
+        let max_angle = 80.0_f64.to_radians();
+        let constr1 = Rc::new(RefCell::new(
+            AngleBetweenLinesBounds::new(
+                line1.clone(),
+                line2.clone(),
+                Some(min_angle),
+                Some(max_angle),
+            )
+            .unwrap(),
+        ));
+        sketch
+            .add_constraint(ConstraintCell::AngleBetweenLinesBounds(constr1.clone()))
+            .unwrap();
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+        let solver = LevenbergMarquardtSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("line1: {:?}", line1.as_ref().borrow());
+        println!("line2: {:?}", line2.as_ref().borrow());
+        println!(
+            "current angle: {}",
+            constr1.borrow().current_angle().to_degrees()
+        );
+
+        let angle = constr1.borrow().current_angle();
+        assert!(constr1.borrow().loss_value() < 1e-12);
+        assert!(angle >= min_angle - 1e-6 && angle <= max_angle + 1e-6);
+    }
+
+    #[test]
+    fn test_invalid_angle_between_lines_bounds() {
+        let origin = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let x = Rc::new(RefCell::new(Point2::new(1.0, 0.0)));
+        let y = Rc::new(RefCell::new(Point2::new(0.0, 1.0)));
+        let line1 = Rc::new(RefCell::new(Line::new(origin.clone(), x)));
+        let line2 = Rc::new(RefCell::new(Line::new(origin, y)));
+        assert!(matches!(
+            AngleBetweenLinesBounds::new(line1.clone(), line2.clone(), Some(1.0), Some(0.5)),
+            Err(ISOTopeError::InvalidBounds)
+        ));
+
+        let constr1 = AngleBetweenLinesBounds::new(line1, line2, Some(0.5), Some(1.0)).unwrap();
+        let mut json = serde_json::to_value(&constr1).unwrap();
+        json["max_angle"] = serde_json::json!(0.2);
+        assert!(serde_json::from_value::<AngleBetweenLinesBounds>(json).is_err());
+    }
+}
@@ -0,0 +1,320 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::{DVector, DVectorView, SMatrix};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::{
+        bounds::{bound_violation, check_bounds},
+        ConstraintLike,
+    },
+    error::ISOTopeError,
+    primitives::{point2::Point2, PrimitiveCell},
+};
+
+// This is a sketch constraint that keeps the euclidean distance between two points above a minimum
+// and/or below a maximum distance. Leaving out one of the bounds makes it one-sided.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+#[serde(try_from = "UncheckedDistanceBounds")]
+pub struct DistanceBounds {
+    point1: Rc<RefCell<Point2>>,
+    point2: Rc<RefCell<Point2>>,
+
+    min_distance: Option<f64>,
+    max_distance: Option<f64>,
+}
+
+impl DistanceBounds {
+    pub fn new(
+        point1: Rc<RefCell<Point2>>,
+        point2: Rc<RefCell<Point2>>,
+        min_distance: Option<f64>,
+        max_distance: Option<f64>,
+    ) -> Result<Self, ISOTopeError> {
+        check_bounds(min_distance, max_distance)?;
+        Ok(Self {
+            point1,
+            point2,
+            min_distance,
+            max_distance,
+        })
+    }
+
+    pub fn point1(&self) -> Rc<RefCell<Point2>> {
+        self.point1.clone()
+    }
+
+    pub fn set_point1(&mut self, point1: Rc<RefCell<Point2>>) {
+        self.point1 = point1;
+    }
+
+    pub fn point2(&self) -> Rc<RefCell<Point2>> {
+        self.point2.clone()
+    }
+
+    pub fn set_point2(&mut self, point2: Rc<RefCell<Point2>>) {
+        self.point2 = point2;
+    }
+
+    pub fn min_distance(&self) -> Option<f64> {
+        self.min_distance
+    }
+
+    pub fn max_distance(&self) -> Option<f64> {
+        self.max_distance
+    }
+
+    pub fn set_bounds(
+        &mut self,
+        min_distance: Option<f64>,
+        max_distance: Option<f64>,
+    ) -> Result<(), ISOTopeError> {
+        check_bounds(min_distance, max_distance)?;
+        self.min_distance = min_distance;
+        self.max_distance = max_distance;
+        Ok(())
+    }
+
+    pub fn current_distance(&self) -> f64 {
+        let point1 = self.point1.borrow().data();
+        let point2 = self.point2.borrow().data();
+        (point2 - point1).norm()
+    }
+}
+
+// The fields of DistanceBounds as they are deserialized, before the bounds are checked
+#[derive(Deserialize)]
+struct UncheckedDistanceBounds {
+    point1: Rc<RefCell<Point2>>,
+    point2: Rc<RefCell<Point2>>,
+
+    min_distance: Option<f64>,
+    max_distance: Option<f64>,
+}
+
+impl TryFrom<UncheckedDistanceBounds> for DistanceBounds {
+    type Error = ISOTopeError;
+
+    fn try_from(unchecked: UncheckedDistanceBounds) -> Result<Self, Self::Error> {
+        Self::new(
+            unchecked.point1,
+            unchecked.point2,
+            unchecked.min_distance,
+            unchecked.max_distance,
+        )
+    }
+}
+
+impl ConstraintLike for DistanceBounds {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![
+            PrimitiveCell::Point2(self.point1.clone()),
+            PrimitiveCell::Point2(self.point2.clone()),
+        ]
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let violation = bound_violation(
+            self.current_distance(),
+            self.min_distance,
+            self.max_distance,
+        );
+        DVector::from_row_slice(&[violation])
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let point1 = self.point1.borrow().data();
+        let point2 = self.point2.borrow().data();
+
+        let d = point2 - point1;
+        let distance = d.norm();
+
+        // Inside of the bounds the violation stays zero
+        let violation = bound_violation(distance, self.min_distance, self.max_distance);
+        if violation == 0.0 {
+            return;
+        }
+
+        // The direction between coincident points is undefined, but a minimum distance still has
+        // to push them apart, so they are separated along the x axis
+        let grad_from_violation = weights[0];
+        let grad_distance_from_d = if distance < 1e-6 {
+            SMatrix::<f64, 1, 2>::from_row_slice(&[1.0, 0.0])
+        } else {
+            d.transpose() / distance
+        };
+
+        let grad_point1 = self.point1.borrow().point_gradient();
+        let grad_point2 = self.point2.borrow().point_gradient();
+
+        self.point1
+            .borrow_mut()
+            .add_to_gradient((-grad_from_violation * grad_distance_from_d * grad_point1).as_view());
+        self.point2
+            .borrow_mut()
+            .add_to_gradient((grad_from_violation * grad_distance_from_d * grad_point2).as_view());
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::DistanceBounds(self.clone())
+    }
+
+    fn is_inequality(&self) -> bool {
+        true
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::{bounds::distance_bounds::DistanceBounds, ConstraintCell, ConstraintLike},
+        error::ISOTopeError,
+        primitives::{point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, SolveStatus, Solver},
+    };
+
+    #[test]
+    fn test_distance_bounds() {
+        // The first pair is too close, the second one too far apart and the third one already fine
+        for (x, min_distance, max_distance) in [
+            (1.0, Some(5.0), None),
+            (8.0, None, Some(3.0)),
+            (2.0, Some(1.0), Some(3.0)),
+        ] {
+            let mut sketch = Sketch::new();
+
+            let point1 = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+            let point2 = Rc::new(RefCell::new(Point2::new(x, 0.5)));
+            sketch
+                .add_primitive(PrimitiveCell::Point2(point1.clone()))
+                .unwrap();
+            sketch
+                .add_primitive(PrimitiveCell::Point2(point2.clone()))
+                .unwrap();
+
+            let constr1 = Rc::new(RefCell::new(
+                DistanceBounds::new(point1.clone(), point2.clone(), min_distance, max_distance)
+                    .unwrap(),
+            ));
+            sketch
+                .add_constraint(ConstraintCell::DistanceBounds(constr1.clone()))
+                .unwrap();
+
+            sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+            let solver = BFGSSolver::new();
+            solver.solve(&mut sketch).unwrap();
+
+            println!("point1: {:?}", point1.as_ref().borrow());
+            println!("point2: {:?}", point2.as_ref().borrow());
+
+            let distance = constr1.borrow().current_distance();
+            assert!(constr1.borrow().loss_value() < 1e-12);
+            assert!(distance >= min_distance.unwrap_or(f64::MIN) - 1e-6);
+            assert!(distance <= max_distance.unwrap_or(f64::MAX) + 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_coincident_points_with_min_distance() {
+        let mut sketch = Sketch::new();
+
+        let point1 = Rc::new(RefCell::new(Point2::new(1.0, 2.0)));
+        let point2 = Rc::new(RefCell::new(Point2::new(1.0, 2.0)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(point1.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Point2(point2.clone()))
+            .unwrap();
+
+        let constr1 = Rc::new(RefCell::new(
+            DistanceBounds::new(point1.clone(), point2.clone(), Some(1.0), None).unwrap(),
+        ));
+        sketch
+            .add_constraint(ConstraintCell::DistanceBounds(constr1.clone()))
+            .unwrap();
+
+        let report = BFGSSolver::new().solve(&mut sketch).unwrap();
+        assert_eq!(report.status, SolveStatus::Converged);
+        assert!(constr1.borrow().current_distance() >= 1.0 - 1e-6);
+    }
+
+    #[test]
+    fn test_infeasible_distance_bounds() {
+        let mut sketch = Sketch::new();
+
+        let point1 = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let point2 = Rc::new(RefCell::new(Point2::new(1.0, 0.0)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(point1.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Point2(point2.clone()))
+            .unwrap();
+
+        // The points cannot be at least 5 and at most 2 apart at the same time
+        let constr1 = Rc::new(RefCell::new(
+            DistanceBounds::new(point1.clone(), point2.clone(), Some(5.0), None).unwrap(),
+        ));
+        let constr2 = Rc::new(RefCell::new(
+            DistanceBounds::new(point1.clone(), point2.clone(), None, Some(2.0)).unwrap(),
+        ));
+        sketch
+            .add_constraint(ConstraintCell::DistanceBounds(constr1.clone()))
+            .unwrap();
+        sketch
+            .add_constraint(ConstraintCell::DistanceBounds(constr2.clone()))
+            .unwrap();
+
+        // The report still describes where the solver ended up
+        let solver = BFGSSolver::new();
+        let report = solver.solve(&mut sketch).unwrap();
+        assert_eq!(report.status, SolveStatus::Infeasible);
+        assert_eq!(report.violated_inequalities, 2);
+        assert!(report.iterations > 0);
+        assert!(report.final_loss > 0.0);
+    }
+
+    #[test]
+    fn test_invalid_distance_bounds() {
+        let point1 = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let point2 = Rc::new(RefCell::new(Point2::new(1.0, 0.0)));
+        for (min_distance, max_distance) in [
+            (None, None),
+            (Some(3.0), Some(2.0)),
+            (Some(f64::NAN), None),
+            (None, Some(f64::INFINITY)),
+        ] {
+            assert!(matches!(
+                DistanceBounds::new(point1.clone(), point2.clone(), min_distance, max_distance),
+                Err(ISOTopeError::InvalidBounds)
+            ));
+        }
+
+        // Invalid bounds leave the constraint unchanged
+        let mut constr1 =
+            DistanceBounds::new(point1.clone(), point2.clone(), Some(1.0), Some(2.0)).unwrap();
+        assert!(matches!(
+            constr1.set_bounds(Some(3.0), Some(2.0)),
+            Err(ISOTopeError::InvalidBounds)
+        ));
+        assert_eq!(constr1.min_distance(), Some(1.0));
+        assert_eq!(constr1.max_distance(), Some(2.0));
+
+        // Deserializing checks the bounds just like constructing
+        let mut json = serde_json::to_value(&constr1).unwrap();
+        let deserialized: DistanceBounds = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(deserialized, constr1);
+        json["min_distance"] = serde_json::json!(3.0);
+        assert!(serde_json::from_value::<DistanceBounds>(json).is_err());
+    }
+}
@@ -0,0 +1,37 @@
+use crate::error::ISOTopeError;
+
+pub mod angle_between_lines_bounds;
+pub mod distance_bounds;
+pub mod radius_bounds;
+
+// Checks that the bounds of an inequality constraint are valid, at least one of them has to be set.
+// This is done both when constructing and when deserializing the constraints.
+pub(crate) fn check_bounds(min: Option<f64>, max: Option<f64>) -> Result<(), ISOTopeError> {
+    let valid = (min.is_some() || max.is_some())
+        && min.is_none_or(f64::is_finite)
+        && max.is_none_or(f64::is_finite)
+        && match (min, max) {
+            (Some(min), Some(max)) => min <= max,
+            _ => true,
+        };
+    if valid {
+        Ok(())
+    } else {
+        Err(ISOTopeError::InvalidBounds)
+    }
+}
+
+// The signed amount by which a value lies outside of the interval [min, max], zero inside of it
+pub(crate) fn bound_violation(value: f64, min: Option<f64>, max: Option<f64>) -> f64 {
+    if let Some(min) = min {
+        if value < min {
+            return value - min;
+        }
+    }
+    if let Some(max) = max {
+        if value > max {
+            return value - max;
+        }
+    }
+    0.0
+}
@@ -0,0 +1,184 @@
+use nalgebra::{DVector, DVectorView};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::{
+        bounds::{bound_violation, check_bounds},
+        radius::RadiusPrimitive,
+        ConstraintLike,
+    },
+    error::ISOTopeError,
+    primitives::PrimitiveCell,
+};
+
+// This is a sketch constraint that keeps the radius of a circle or an arc above a minimum and/or
+// below a maximum radius. Leaving out one of the bounds makes it one-sided.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+#[serde(try_from = "UncheckedRadiusBounds")]
+pub struct RadiusBounds {
+    primitive: RadiusPrimitive,
+
+    min_radius: Option<f64>,
+    max_radius: Option<f64>,
+}
+
+impl RadiusBounds {
+    pub fn new(
+        primitive: RadiusPrimitive,
+        min_radius: Option<f64>,
+        max_radius: Option<f64>,
+    ) -> Result<Self, ISOTopeError> {
+        check_bounds(min_radius, max_radius)?;
+        Ok(Self {
+            primitive,
+            min_radius,
+            max_radius,
+        })
+    }
+
+    pub fn primitive(&self) -> RadiusPrimitive {
+        self.primitive.clone()
+    }
+
+    pub fn set_primitive(&mut self, primitive: RadiusPrimitive) {
+        self.primitive = primitive;
+    }
+
+    pub fn min_radius(&self) -> Option<f64> {
+        self.min_radius
+    }
+
+    pub fn max_radius(&self) -> Option<f64> {
+        self.max_radius
+    }
+
+    pub fn set_bounds(
+        &mut self,
+        min_radius: Option<f64>,
+        max_radius: Option<f64>,
+    ) -> Result<(), ISOTopeError> {
+        check_bounds(min_radius, max_radius)?;
+        self.min_radius = min_radius;
+        self.max_radius = max_radius;
+        Ok(())
+    }
+}
+
+// The fields of RadiusBounds as they are deserialized, before the bounds are checked
+#[derive(Deserialize)]
+struct UncheckedRadiusBounds {
+    primitive: RadiusPrimitive,
+
+    min_radius: Option<f64>,
+    max_radius: Option<f64>,
+}
+
+impl TryFrom<UncheckedRadiusBounds> for RadiusBounds {
+    type Error = ISOTopeError;
+
+    fn try_from(unchecked: UncheckedRadiusBounds) -> Result<Self, Self::Error> {
+        Self::new(
+            unchecked.primitive,
+            unchecked.min_radius,
+            unchecked.max_radius,
+        )
+    }
+}
+
+impl ConstraintLike for RadiusBounds {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![self.primitive.to_primitive_cell()]
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let violation = bound_violation(self.primitive.radius(), self.min_radius, self.max_radius);
+        DVector::from_row_slice(&[violation])
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        // Inside of the bounds the violation stays zero
+        let violation = bound_violation(self.primitive.radius(), self.min_radius, self.max_radius);
+        if violation == 0.0 {
+            return;
+        }
+
+        let grad_from_violation = weights[0];
+        self.primitive.add_to_radius_gradient(grad_from_violation);
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::RadiusBounds(self.clone())
+    }
+
+    fn is_inequality(&self) -> bool {
+        true
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::{
+            bounds::radius_bounds::RadiusBounds, radius::RadiusPrimitive, ConstraintCell,
+            ConstraintLike,
+        },
+        error::ISOTopeError,
+        primitives::{arc::Arc, circle::Circle, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{gauss_newton_solver::GaussNewtonSolver, Solver},
+    };
+
+    #[test]
+    fn test_radius_bounds() {
+        let mut sketch = Sketch::new();
+
+        let center = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let arc = Rc::new(RefCell::new(Arc::new(center.clone(), 0.5, false, 0.0, 1.0)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(center.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Arc(arc.clone()))
+            .unwrap();
+
+        // A tool with a radius of 2 has to fit into the arc
+        let constr1 = Rc::new(RefCell::new(
+            RadiusBounds::new(RadiusPrimitive::Arc(arc.clone()), Some(2.0), None).unwrap(),
+        ));
+        sketch
+            .add_constraint(ConstraintCell::RadiusBounds(constr1.clone()))
+            .unwrap();
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+        let solver = GaussNewtonSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("arc: {:?}", arc.as_ref().borrow());
+
+        assert!(constr1.borrow().loss_value() < 1e-12);
+        assert!(arc.as_ref().borrow().radius() >= 2.0 - 1e-6);
+    }
+
+    #[test]
+    fn test_invalid_radius_bounds() {
+        let center = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let circle = RadiusPrimitive::Circle(Rc::new(RefCell::new(Circle::new(center, 1.0))));
+        assert!(matches!(
+            RadiusBounds::new(circle.clone(), Some(2.0), Some(1.0)),
+            Err(ISOTopeError::InvalidBounds)
+        ));
+
+        let constr1 = RadiusBounds::new(circle, None, Some(2.0)).unwrap();
+        let mut json = serde_json::to_value(&constr1).unwrap();
+        json["max_radius"] = serde_json::Value::Null;
+        assert!(serde_json::from_value::<RadiusBounds>(json).is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use std::{cell::RefCell, rc::Rc};
 
-use nalgebra::SMatrix;
+use nalgebra::{DVector, DVectorView, SMatrix};
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "tsify")]
@@ -50,21 +50,16 @@ impl ConstraintLike for ArcEndPointCoincident {
         ]
     }
 
-    fn loss_value(&self) -> f64 {
+    fn residuals(&self) -> DVector<f64> {
         let arc_end = self.arc.borrow().end_point();
         let point = self.point.borrow().data();
         let dx = arc_end.x - point.x;
         let dy = arc_end.y - point.y;
-        0.5 * (dx * dx + dy * dy)
+        DVector::from_row_slice(&[dx, dy])
     }
 
-    fn update_gradient(&mut self) {
-        let arc_end = self.arc.borrow().end_point();
-        let point = self.point.borrow().data();
-        let dx = arc_end.x - point.x;
-        let dy = arc_end.y - point.y;
-
-        let gradient_constraint = SMatrix::<f64, 1, 2>::from_row_slice(&[dx, dy]);
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let gradient_constraint = SMatrix::<f64, 1, 2>::from_row_slice(&[weights[0], weights[1]]);
 
         let grad_arc = self.arc.borrow().end_point_gradient();
         let grad_point = self.point.borrow().point_gradient();
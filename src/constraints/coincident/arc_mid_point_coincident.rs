@@ -0,0 +1,136 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::{DVector, DVectorView, SMatrix};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::ConstraintLike,
+    primitives::{arc::Arc, point2::Point2, PrimitiveCell},
+};
+
+// This is a sketch constraint that makes the angular mid point of an arc coincident with a point.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct ArcMidPointCoincident {
+    arc: Rc<RefCell<Arc>>,
+    point: Rc<RefCell<Point2>>,
+}
+
+impl ArcMidPointCoincident {
+    pub fn new(arc: Rc<RefCell<Arc>>, point: Rc<RefCell<Point2>>) -> Self {
+        Self { arc, point }
+    }
+
+    pub fn arc(&self) -> Rc<RefCell<Arc>> {
+        self.arc.clone()
+    }
+
+    pub fn set_arc(&mut self, arc: Rc<RefCell<Arc>>) {
+        self.arc = arc;
+    }
+
+    pub fn point(&self) -> Rc<RefCell<Point2>> {
+        self.point.clone()
+    }
+
+    pub fn set_point(&mut self, point: Rc<RefCell<Point2>>) {
+        self.point = point;
+    }
+}
+
+impl ConstraintLike for ArcMidPointCoincident {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![
+            PrimitiveCell::Arc(self.arc.clone()),
+            PrimitiveCell::Point2(self.point.clone()),
+        ]
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let arc_mid = self.arc.borrow().mid_point();
+        let point = self.point.borrow().data();
+        DVector::from_row_slice(&[arc_mid.x - point.x, arc_mid.y - point.y])
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let gradient_constraint = SMatrix::<f64, 1, 2>::from_row_slice(&[weights[0], weights[1]]);
+
+        let grad_arc = self.arc.borrow().mid_point_gradient();
+        let grad_point = self.point.borrow().point_gradient();
+
+        self.arc
+            .borrow_mut()
+            .add_to_gradient((gradient_constraint * grad_arc).as_view());
+        self.point
+            .borrow_mut()
+            .add_to_gradient((-gradient_constraint * grad_point).as_view());
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::ArcMidPointCoincident(self.clone())
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::{
+            coincident::arc_mid_point_coincident::ArcMidPointCoincident, ConstraintCell,
+            ConstraintLike,
+        },
+        primitives::{arc::Arc, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    #[test]
+    fn test_arc_mid_point_coincident() {
+        for clockwise in [false, true] {
+            let mut sketch = Sketch::new();
+
+            let center = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+            let arc = Rc::new(RefCell::new(Arc::new(
+                center.clone(),
+                1.0,
+                clockwise,
+                0.3,
+                2.0,
+            )));
+            let point = Rc::new(RefCell::new(Point2::new(2.0, 3.0)));
+            sketch
+                .add_primitive(PrimitiveCell::Point2(center.clone()))
+                .unwrap();
+            sketch
+                .add_primitive(PrimitiveCell::Arc(arc.clone()))
+                .unwrap();
+            sketch
+                .add_primitive(PrimitiveCell::Point2(point.clone()))
+                .unwrap();
+
+            let constr1 = Rc::new(RefCell::new(ArcMidPointCoincident::new(
+                arc.clone(),
+                point.clone(),
+            )));
+            sketch
+                .add_constraint(ConstraintCell::ArcMidPointCoincident(constr1.clone()))
+                .unwrap();
+
+            sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+            let solver = BFGSSolver::new();
+            solver.solve(&mut sketch).unwrap();
+
+            println!("arc: {:?}", arc.as_ref().borrow());
+            println!("arc mid point: {:?}", arc.as_ref().borrow().mid_point());
+            println!("point: {:?}", point.as_ref().borrow());
+
+            assert!(constr1.borrow().loss_value() < 1e-10);
+        }
+    }
+}
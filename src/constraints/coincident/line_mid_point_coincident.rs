@@ -0,0 +1,141 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::{DVector, DVectorView, SMatrix};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::ConstraintLike,
+    primitives::{line::Line, point2::Point2, PrimitiveCell},
+};
+
+// This is a sketch constraint that makes the mid point of a line coincident with a point.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct LineMidPointCoincident {
+    line: Rc<RefCell<Line>>,
+    point: Rc<RefCell<Point2>>,
+}
+
+impl LineMidPointCoincident {
+    pub fn new(line: Rc<RefCell<Line>>, point: Rc<RefCell<Point2>>) -> Self {
+        Self { line, point }
+    }
+
+    pub fn line(&self) -> Rc<RefCell<Line>> {
+        self.line.clone()
+    }
+
+    pub fn set_line(&mut self, line: Rc<RefCell<Line>>) {
+        self.line = line;
+    }
+
+    pub fn point(&self) -> Rc<RefCell<Point2>> {
+        self.point.clone()
+    }
+
+    pub fn set_point(&mut self, point: Rc<RefCell<Point2>>) {
+        self.point = point;
+    }
+}
+
+impl ConstraintLike for LineMidPointCoincident {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![
+            PrimitiveCell::Line(self.line.clone()),
+            PrimitiveCell::Point2(self.point.clone()),
+        ]
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let start = self.line.borrow().start().borrow().data();
+        let end = self.line.borrow().end().borrow().data();
+        let point = self.point.borrow().data();
+
+        let d = 0.5 * (start + end) - point;
+        DVector::from_row_slice(&[d.x, d.y])
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let gradient_constraint = SMatrix::<f64, 1, 2>::from_row_slice(&[weights[0], weights[1]]);
+
+        let grad_mid_point =
+            0.5 * (self.line.borrow().start_gradient() + self.line.borrow().end_gradient());
+        let grad_point = self.point.borrow().point_gradient();
+
+        self.line
+            .borrow_mut()
+            .add_to_gradient((gradient_constraint * grad_mid_point).as_view());
+        self.point
+            .borrow_mut()
+            .add_to_gradient((-gradient_constraint * grad_point).as_view());
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::LineMidPointCoincident(self.clone())
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::{
+            coincident::line_mid_point_coincident::LineMidPointCoincident, ConstraintCell,
+            ConstraintLike,
+        },
+        primitives::{line::Line, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    #[test]
+    fn test_line_mid_point_coincident() {
+        let mut sketch = Sketch::new();
+
+        let line_start = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let line_end = Rc::new(RefCell::new(Point2::new(2.0, 1.0)));
+        let line = Rc::new(RefCell::new(Line::new(
+            line_start.clone(),
+            line_end.clone(),
+        )));
+        let point = Rc::new(RefCell::new(Point2::new(3.0, 4.0)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(line_start.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Point2(line_end.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Line(line.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Point2(point.clone()))
+            .unwrap();
+
+        let constr1 = Rc::new(RefCell::new(LineMidPointCoincident::new(
+            line.clone(),
+            point.clone(),
+        )));
+        sketch
+            .add_constraint(ConstraintCell::LineMidPointCoincident(constr1.clone()))
+            .unwrap();
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+        let solver = BFGSSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("line: {:?}", line.as_ref().borrow());
+        println!("point: {:?}", point.as_ref().borrow());
+
+        let mid_point =
+            0.5 * (line_start.as_ref().borrow().data() + line_end.as_ref().borrow().data());
+        assert!((mid_point - point.as_ref().borrow().data()).norm() < 1e-6);
+        assert!(constr1.borrow().loss_value() < 1e-10);
+    }
+}
@@ -1,2 +1,8 @@
 pub mod arc_end_point_coincident;
+pub mod arc_mid_point_coincident;
 pub mod arc_start_point_coincident;
+pub mod line_mid_point_coincident;
+pub mod point_on_arc;
+pub mod point_on_circle;
+pub mod point_on_line;
+pub mod point_on_line_segment;
@@ -0,0 +1,188 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::{DVector, DVectorView, SMatrix};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::ConstraintLike,
+    primitives::{arc::Arc, point2::Point2, PrimitiveCell},
+};
+
+// This is a sketch constraint that makes a point lie on an arc. If the point is outside of the
+// angular range of the arc, it is pulled towards the closest end point of the arc instead.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct PointOnArc {
+    point: Rc<RefCell<Point2>>,
+    arc: Rc<RefCell<Arc>>,
+}
+
+impl PointOnArc {
+    pub fn new(point: Rc<RefCell<Point2>>, arc: Rc<RefCell<Arc>>) -> Self {
+        Self { point, arc }
+    }
+
+    pub fn point(&self) -> Rc<RefCell<Point2>> {
+        self.point.clone()
+    }
+
+    pub fn set_point(&mut self, point: Rc<RefCell<Point2>>) {
+        self.point = point;
+    }
+
+    pub fn arc(&self) -> Rc<RefCell<Arc>> {
+        self.arc.clone()
+    }
+
+    pub fn set_arc(&mut self, arc: Rc<RefCell<Arc>>) {
+        self.arc = arc;
+    }
+
+    fn within_arc(&self) -> bool {
+        let point = self.point.borrow().data();
+        let center = self.arc.borrow().center().borrow().data();
+        let d = point - center;
+        self.arc.borrow().contains_angle(d.y.atan2(d.x))
+    }
+
+    fn start_is_closer(&self) -> bool {
+        let point = self.point.borrow().data();
+        let arc = self.arc.borrow();
+        (point - arc.start_point()).norm_squared() <= (point - arc.end_point()).norm_squared()
+    }
+}
+
+impl ConstraintLike for PointOnArc {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![
+            PrimitiveCell::Point2(self.point.clone()),
+            PrimitiveCell::Arc(self.arc.clone()),
+        ]
+    }
+
+    // Outside of the arc, the residuals are the offset from the closest end point. On the arc, they
+    // are the distance from the circle of the arc and zero, so there are always two of them.
+    fn residuals(&self) -> DVector<f64> {
+        let point = self.point.borrow().data();
+        let center = self.arc.borrow().center().borrow().data();
+        let radius = self.arc.borrow().radius();
+
+        if !self.within_arc() {
+            let closest = if self.start_is_closer() {
+                self.arc.borrow().start_point()
+            } else {
+                self.arc.borrow().end_point()
+            };
+            let d = point - closest;
+            return DVector::from_row_slice(&[d.x, d.y]);
+        }
+
+        let difference = (point - center).norm() - radius;
+        DVector::from_row_slice(&[difference, 0.0])
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let point = self.point.borrow().data();
+        let center = self.arc.borrow().center().borrow().data();
+
+        let grad_point = self.point.borrow().point_gradient();
+
+        if !self.within_arc() {
+            // Outside of the arc, this is a coincidence with the closest end point
+            let grad_closest = if self.start_is_closer() {
+                self.arc.borrow().start_point_gradient()
+            } else {
+                self.arc.borrow().end_point_gradient()
+            };
+            let grad_from_d = SMatrix::<f64, 1, 2>::from_row_slice(&[weights[0], weights[1]]);
+            self.point
+                .borrow_mut()
+                .add_to_gradient((grad_from_d * grad_point).as_view());
+            self.arc
+                .borrow_mut()
+                .add_to_gradient((-grad_from_d * grad_closest).as_view());
+            return;
+        }
+
+        let d = point - center;
+        let distance = d.norm();
+        if distance < 1e-6 {
+            return;
+        }
+
+        let grad_from_difference = weights[0];
+        let grad_distance_from_d = d.transpose() / distance;
+
+        let grad_center = self.arc.borrow().center_gradient();
+        let grad_radius = self.arc.borrow().radius_gradient();
+
+        self.point
+            .borrow_mut()
+            .add_to_gradient((grad_from_difference * grad_distance_from_d * grad_point).as_view());
+        self.arc.borrow_mut().add_to_gradient(
+            (grad_from_difference * (-grad_distance_from_d * grad_center - grad_radius)).as_view(),
+        );
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::PointOnArc(self.clone())
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::{coincident::point_on_arc::PointOnArc, ConstraintCell, ConstraintLike},
+        primitives::{arc::Arc, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    #[test]
+    fn test_point_on_arc() {
+        // One point lies within the angular range of the arc, the other one does not
+        for (x, y) in [(1.0, 3.0), (-2.0, -0.5)] {
+            let mut sketch = Sketch::new();
+
+            let center = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+            let arc = Rc::new(RefCell::new(Arc::new(
+                center.clone(),
+                1.0,
+                false,
+                0.0,
+                std::f64::consts::PI / 2.0,
+            )));
+            let point = Rc::new(RefCell::new(Point2::new(x, y)));
+            sketch
+                .add_primitive(PrimitiveCell::Point2(center.clone()))
+                .unwrap();
+            sketch
+                .add_primitive(PrimitiveCell::Arc(arc.clone()))
+                .unwrap();
+            sketch
+                .add_primitive(PrimitiveCell::Point2(point.clone()))
+                .unwrap();
+
+            let constr1 = Rc::new(RefCell::new(PointOnArc::new(point.clone(), arc.clone())));
+            sketch
+                .add_constraint(ConstraintCell::PointOnArc(constr1.clone()))
+                .unwrap();
+
+            sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+            let solver = BFGSSolver::new();
+            solver.solve(&mut sketch).unwrap();
+
+            println!("arc: {:?}", arc.as_ref().borrow());
+            println!("point: {:?}", point.as_ref().borrow());
+
+            assert!(constr1.as_ref().borrow().loss_value() < 1e-8);
+        }
+    }
+}
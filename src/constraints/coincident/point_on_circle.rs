@@ -0,0 +1,138 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::{DVector, DVectorView};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::ConstraintLike,
+    primitives::{circle::Circle, point2::Point2, PrimitiveCell},
+};
+
+// This is a sketch constraint that makes a point lie on a circle.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct PointOnCircle {
+    point: Rc<RefCell<Point2>>,
+    circle: Rc<RefCell<Circle>>,
+}
+
+impl PointOnCircle {
+    pub fn new(point: Rc<RefCell<Point2>>, circle: Rc<RefCell<Circle>>) -> Self {
+        Self { point, circle }
+    }
+
+    pub fn point(&self) -> Rc<RefCell<Point2>> {
+        self.point.clone()
+    }
+
+    pub fn set_point(&mut self, point: Rc<RefCell<Point2>>) {
+        self.point = point;
+    }
+
+    pub fn circle(&self) -> Rc<RefCell<Circle>> {
+        self.circle.clone()
+    }
+
+    pub fn set_circle(&mut self, circle: Rc<RefCell<Circle>>) {
+        self.circle = circle;
+    }
+}
+
+impl ConstraintLike for PointOnCircle {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![
+            PrimitiveCell::Point2(self.point.clone()),
+            PrimitiveCell::Circle(self.circle.clone()),
+        ]
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let point = self.point.borrow().data();
+        let center = self.circle.borrow().center().borrow().data();
+        let radius = self.circle.borrow().radius();
+
+        let difference = (point - center).norm() - radius;
+        DVector::from_row_slice(&[difference])
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let point = self.point.borrow().data();
+        let center = self.circle.borrow().center().borrow().data();
+
+        let d = point - center;
+        let distance = d.norm();
+        if distance < 1e-6 {
+            return;
+        }
+
+        let grad_from_difference = weights[0];
+        let grad_distance_from_d = d.transpose() / distance;
+
+        let grad_point = self.point.borrow().point_gradient();
+        let grad_center = self.circle.borrow().center_gradient();
+        let grad_radius = self.circle.borrow().radius_gradient();
+
+        self.point
+            .borrow_mut()
+            .add_to_gradient((grad_from_difference * grad_distance_from_d * grad_point).as_view());
+        self.circle.borrow_mut().add_to_gradient(
+            (grad_from_difference * (-grad_distance_from_d * grad_center - grad_radius)).as_view(),
+        );
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::PointOnCircle(self.clone())
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::{coincident::point_on_circle::PointOnCircle, ConstraintCell, ConstraintLike},
+        primitives::{circle::Circle, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    #[test]
+    fn test_point_on_circle() {
+        let mut sketch = Sketch::new();
+
+        let center = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let circle = Rc::new(RefCell::new(Circle::new(center.clone(), 1.0)));
+        let point = Rc::new(RefCell::new(Point2::new(3.0, 4.0)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(center.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Circle(circle.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Point2(point.clone()))
+            .unwrap();
+
+        let constr1 = Rc::new(RefCell::new(PointOnCircle::new(
+            point.clone(),
+            circle.clone(),
+        )));
+        sketch
+            .add_constraint(ConstraintCell::PointOnCircle(constr1.clone()))
+            .unwrap();
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+        let solver = BFGSSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("circle: {:?}", circle.as_ref().borrow());
+        println!("point: {:?}", point.as_ref().borrow());
+
+        assert!(constr1.as_ref().borrow().loss_value() < 1e-8);
+    }
+}
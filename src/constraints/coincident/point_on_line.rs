@@ -0,0 +1,264 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::{DVector, DVectorView, SMatrix, Vector2};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::{lines::LinePrimitive, ConstraintLike},
+    primitives::{point2::Point2, PrimitiveCell},
+};
+
+// This is a sketch constraint that makes a point lie on the infinite extension of a line, on an
+// infinite line or on a ray. A ray does not extend behind its origin, so there the residual is the
+// distance to the origin.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct PointOnLine {
+    point: Rc<RefCell<Point2>>,
+    line: LinePrimitive,
+}
+
+impl PointOnLine {
+    pub fn new(point: Rc<RefCell<Point2>>, line: impl Into<LinePrimitive>) -> Self {
+        Self {
+            point,
+            line: line.into(),
+        }
+    }
+
+    pub fn point(&self) -> Rc<RefCell<Point2>> {
+        self.point.clone()
+    }
+
+    pub fn set_point(&mut self, point: Rc<RefCell<Point2>>) {
+        self.point = point;
+    }
+
+    pub fn line(&self) -> LinePrimitive {
+        self.line.clone()
+    }
+
+    pub fn set_line(&mut self, line: impl Into<LinePrimitive>) {
+        self.line = line.into();
+    }
+
+    // Whether the offset w from the anchor points behind the origin of a ray with direction u
+    fn is_behind_ray(&self, u: Vector2<f64>, w: Vector2<f64>) -> bool {
+        matches!(self.line, LinePrimitive::Ray(_)) && u.dot(&w) < 0.0
+    }
+}
+
+// The side of the line a point is on, points on the line count as the left side
+fn side(cross_product: f64) -> f64 {
+    if cross_product >= 0.0 {
+        1.0
+    } else {
+        -1.0
+    }
+}
+
+impl ConstraintLike for PointOnLine {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![
+            PrimitiveCell::Point2(self.point.clone()),
+            self.line.to_primitive_cell(),
+        ]
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let point = self.point.borrow().data();
+        let anchor = self.line.anchor();
+
+        let u = self.line.direction();
+        let w = point - anchor;
+        let length = u.norm();
+        if length < 1e-6 {
+            return DVector::zeros(1);
+        }
+
+        let cross_product = u.x * w.y - u.y * w.x;
+        if self.is_behind_ray(u, w) {
+            // Keep the sign of the side, so the residual is continuous at the origin
+            return DVector::from_row_slice(&[side(cross_product) * w.norm()]);
+        }
+
+        let distance = cross_product / length;
+        DVector::from_row_slice(&[distance])
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let point = self.point.borrow().data();
+        let anchor = self.line.anchor();
+
+        let u = self.line.direction();
+        let w = point - anchor;
+        let length = u.norm();
+        if length < 1e-6 {
+            return;
+        }
+
+        let cross_product = u.x * w.y - u.y * w.x;
+        let grad_from_distance = weights[0];
+        let grad_point = self.point.borrow().point_gradient();
+
+        if self.is_behind_ray(u, w) {
+            // The distance to the origin does not depend on the direction
+            let grad_distance_from_w = side(cross_product) * w.transpose() / w.norm();
+            self.point.borrow_mut().add_to_gradient(
+                (grad_from_distance * grad_distance_from_w * grad_point).as_view(),
+            );
+            self.line.add_to_gradient(
+                -grad_from_distance * grad_distance_from_w,
+                SMatrix::<f64, 1, 2>::zeros(),
+            );
+            return;
+        }
+
+        let grad_distance_from_u = SMatrix::<f64, 1, 2>::from_row_slice(&[w.y, -w.x]) / length
+            - cross_product / (length * length * length) * u.transpose();
+        let grad_distance_from_w = SMatrix::<f64, 1, 2>::from_row_slice(&[-u.y, u.x]) / length;
+
+        self.point
+            .borrow_mut()
+            .add_to_gradient((grad_from_distance * grad_distance_from_w * grad_point).as_view());
+        self.line.add_to_gradient(
+            -grad_from_distance * grad_distance_from_w,
+            grad_from_distance * grad_distance_from_u,
+        );
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::PointOnLine(self.clone())
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::{coincident::point_on_line::PointOnLine, ConstraintCell, ConstraintLike},
+        primitives::{
+            infinite_line::InfiniteLine, line::Line, point2::Point2, ray::Ray, PrimitiveCell,
+        },
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    #[test]
+    fn test_point_on_line() {
+        let mut sketch = Sketch::new();
+
+        let line_start = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let line_end = Rc::new(RefCell::new(Point2::new(1.0, 0.5)));
+        let line = Rc::new(RefCell::new(Line::new(
+            line_start.clone(),
+            line_end.clone(),
+        )));
+        let point = Rc::new(RefCell::new(Point2::new(3.0, 4.0)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(line_start.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Point2(line_end.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Line(line.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Point2(point.clone()))
+            .unwrap();
+
+        let constr1 = Rc::new(RefCell::new(PointOnLine::new(point.clone(), line.clone())));
+        sketch
+            .add_constraint(ConstraintCell::PointOnLine(constr1.clone()))
+            .unwrap();
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+        let solver = BFGSSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("line: {:?}", line.as_ref().borrow());
+        println!("point: {:?}", point.as_ref().borrow());
+
+        assert!(constr1.as_ref().borrow().loss_value() < 1e-8);
+    }
+
+    #[test]
+    fn test_point_on_infinite_line() {
+        let mut sketch = Sketch::new();
+
+        let line_point = Rc::new(RefCell::new(Point2::new(1.0, -1.0)));
+        let line = Rc::new(RefCell::new(InfiniteLine::new(line_point.clone(), 0.7)));
+        let point = Rc::new(RefCell::new(Point2::new(3.0, 4.0)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(line_point.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::InfiniteLine(line.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Point2(point.clone()))
+            .unwrap();
+
+        let constr1 = Rc::new(RefCell::new(PointOnLine::new(point.clone(), line.clone())));
+        sketch
+            .add_constraint(ConstraintCell::PointOnLine(constr1.clone()))
+            .unwrap();
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+        let solver = BFGSSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("line: {:?}", line.as_ref().borrow());
+        println!("point: {:?}", point.as_ref().borrow());
+
+        assert!(constr1.as_ref().borrow().loss_value() < 1e-8);
+    }
+
+    #[test]
+    fn test_point_on_ray() {
+        let mut sketch = Sketch::new();
+
+        let origin = Rc::new(RefCell::new(Point2::new(1.0, -1.0)));
+        let ray = Rc::new(RefCell::new(Ray::new(origin.clone(), 0.7)));
+        let direction = ray.borrow().direction();
+        // The point is on the extension of the ray behind its origin
+        let behind = origin.borrow().data() - 2.0 * direction;
+        let point = Rc::new(RefCell::new(Point2::new(behind.x, behind.y)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(origin.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Ray(ray.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Point2(point.clone()))
+            .unwrap();
+
+        let constr1 = Rc::new(RefCell::new(PointOnLine::new(point.clone(), ray.clone())));
+        sketch
+            .add_constraint(ConstraintCell::PointOnLine(constr1.clone()))
+            .unwrap();
+
+        // Unlike for a line, the extension does not satisfy the constraint
+        assert!((constr1.borrow().residuals()[0] - 2.0).abs() < 1e-12);
+
+        point.borrow_mut().set_y(behind.y - 1.0);
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+        let solver = BFGSSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("ray: {:?}", ray.as_ref().borrow());
+        println!("point: {:?}", point.as_ref().borrow());
+
+        assert!(constr1.as_ref().borrow().loss_value() < 1e-8);
+        let offset = point.borrow().data() - origin.borrow().data();
+        assert!(offset.dot(&ray.borrow().direction()) > -1e-4);
+    }
+}
@@ -0,0 +1,201 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::{DVector, DVectorView, SMatrix};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::ConstraintLike,
+    primitives::{line::Line, point2::Point2, PrimitiveCell},
+};
+
+// This is a sketch constraint that makes a point lie on a line segment, i.e. between its start and
+// end point. Outside of the segment the point is pulled towards the closest end point.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct PointOnLineSegment {
+    point: Rc<RefCell<Point2>>,
+    line: Rc<RefCell<Line>>,
+}
+
+impl PointOnLineSegment {
+    pub fn new(point: Rc<RefCell<Point2>>, line: Rc<RefCell<Line>>) -> Self {
+        Self { point, line }
+    }
+
+    pub fn point(&self) -> Rc<RefCell<Point2>> {
+        self.point.clone()
+    }
+
+    pub fn set_point(&mut self, point: Rc<RefCell<Point2>>) {
+        self.point = point;
+    }
+
+    pub fn line(&self) -> Rc<RefCell<Line>> {
+        self.line.clone()
+    }
+
+    pub fn set_line(&mut self, line: Rc<RefCell<Line>>) {
+        self.line = line;
+    }
+
+    // The position of the projection of the point onto the line, 0 at the start and 1 at the end
+    pub fn current_parameter(&self) -> f64 {
+        let point = self.point.borrow().data();
+        let start = self.line.borrow().start().borrow().data();
+        let end = self.line.borrow().end().borrow().data();
+
+        let u = end - start;
+        let w = point - start;
+        let length_squared = u.norm_squared();
+        if length_squared < 1e-12 {
+            return 0.0;
+        }
+        w.dot(&u) / length_squared
+    }
+}
+
+impl ConstraintLike for PointOnLineSegment {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![
+            PrimitiveCell::Point2(self.point.clone()),
+            PrimitiveCell::Line(self.line.clone()),
+        ]
+    }
+
+    // Outside of the segment, the residuals are the offset from the closest end point. On the
+    // segment, they are the distance from the line and zero, so there are always two of them.
+    fn residuals(&self) -> DVector<f64> {
+        let point = self.point.borrow().data();
+        let start = self.line.borrow().start().borrow().data();
+        let end = self.line.borrow().end().borrow().data();
+
+        let t = self.current_parameter();
+        if t <= 0.0 {
+            let d = point - start;
+            return DVector::from_row_slice(&[d.x, d.y]);
+        }
+        if t >= 1.0 {
+            let d = point - end;
+            return DVector::from_row_slice(&[d.x, d.y]);
+        }
+
+        let u = end - start;
+        let w = point - start;
+        let distance = (u.x * w.y - u.y * w.x) / u.norm();
+        DVector::from_row_slice(&[distance, 0.0])
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let point = self.point.borrow().data();
+        let start = self.line.borrow().start().borrow().data();
+        let end = self.line.borrow().end().borrow().data();
+
+        let grad_point = self.point.borrow().point_gradient();
+        let grad_start = self.line.borrow().start_gradient();
+        let grad_end = self.line.borrow().end_gradient();
+
+        let t = self.current_parameter();
+        if t <= 0.0 || t >= 1.0 {
+            // Outside of the segment, this is a coincidence with the closest end point
+            let grad_closest = if t <= 0.0 { grad_start } else { grad_end };
+            let grad_from_d = SMatrix::<f64, 1, 2>::from_row_slice(&[weights[0], weights[1]]);
+            self.point
+                .borrow_mut()
+                .add_to_gradient((grad_from_d * grad_point).as_view());
+            self.line
+                .borrow_mut()
+                .add_to_gradient((-grad_from_d * grad_closest).as_view());
+            return;
+        }
+
+        let u = end - start;
+        let w = point - start;
+        let length = u.norm();
+        let cross_product = u.x * w.y - u.y * w.x;
+
+        let grad_from_distance = weights[0];
+        let grad_distance_from_u = SMatrix::<f64, 1, 2>::from_row_slice(&[w.y, -w.x]) / length
+            - cross_product / (length * length * length) * u.transpose();
+        let grad_distance_from_w = SMatrix::<f64, 1, 2>::from_row_slice(&[-u.y, u.x]) / length;
+
+        self.point
+            .borrow_mut()
+            .add_to_gradient((grad_from_distance * grad_distance_from_w * grad_point).as_view());
+        self.line.borrow_mut().add_to_gradient(
+            (grad_from_distance
+                * (grad_distance_from_u * (grad_end - grad_start)
+                    - grad_distance_from_w * grad_start))
+                .as_view(),
+        );
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::PointOnLineSegment(self.clone())
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::{
+            coincident::point_on_line_segment::PointOnLineSegment, ConstraintCell, ConstraintLike,
+        },
+        primitives::{line::Line, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    #[test]
+    fn test_point_on_line_segment() {
+        // One point projects inside of the segment, the other one beyond its end
+        for (x, y) in [(0.5, 2.0), (3.0, 4.0)] {
+            let mut sketch = Sketch::new();
+
+            let line_start = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+            let line_end = Rc::new(RefCell::new(Point2::new(1.0, 0.5)));
+            let line = Rc::new(RefCell::new(Line::new(
+                line_start.clone(),
+                line_end.clone(),
+            )));
+            let point = Rc::new(RefCell::new(Point2::new(x, y)));
+            sketch
+                .add_primitive(PrimitiveCell::Point2(line_start.clone()))
+                .unwrap();
+            sketch
+                .add_primitive(PrimitiveCell::Point2(line_end.clone()))
+                .unwrap();
+            sketch
+                .add_primitive(PrimitiveCell::Line(line.clone()))
+                .unwrap();
+            sketch
+                .add_primitive(PrimitiveCell::Point2(point.clone()))
+                .unwrap();
+
+            let constr1 = Rc::new(RefCell::new(PointOnLineSegment::new(
+                point.clone(),
+                line.clone(),
+            )));
+            sketch
+                .add_constraint(ConstraintCell::PointOnLineSegment(constr1.clone()))
+                .unwrap();
+
+            sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+            let solver = BFGSSolver::new();
+            solver.solve(&mut sketch).unwrap();
+
+            println!("line: {:?}", line.as_ref().borrow());
+            println!("point: {:?}", point.as_ref().borrow());
+
+            assert!(constr1.as_ref().borrow().loss_value() < 1e-8);
+            let t = constr1.as_ref().borrow().current_parameter();
+            assert!((-1e-4..=1.0 + 1e-4).contains(&t));
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::{DVector, DVectorView, SMatrix, Vector2};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::ConstraintLike,
+    primitives::{line::Line, PrimitiveCell},
+};
+
+// This is a sketch constraint that fixes the offset distance between two lines. Both end points of
+// the second line are kept at the desired distance on the same side of the infinite extension of
+// the first line, so the lines also become parallel. The side is the one the second line is mostly
+// on.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct DistanceBetweenLines {
+    line1: Rc<RefCell<Line>>,
+    line2: Rc<RefCell<Line>>,
+
+    desired_distance: f64,
+}
+
+impl DistanceBetweenLines {
+    pub fn new(line1: Rc<RefCell<Line>>, line2: Rc<RefCell<Line>>, desired_distance: f64) -> Self {
+        assert!(desired_distance.is_finite());
+        Self {
+            line1,
+            line2,
+            desired_distance,
+        }
+    }
+
+    pub fn line1(&self) -> Rc<RefCell<Line>> {
+        self.line1.clone()
+    }
+
+    pub fn set_line1(&mut self, line1: Rc<RefCell<Line>>) {
+        self.line1 = line1;
+    }
+
+    pub fn line2(&self) -> Rc<RefCell<Line>> {
+        self.line2.clone()
+    }
+
+    pub fn set_line2(&mut self, line2: Rc<RefCell<Line>>) {
+        self.line2 = line2;
+    }
+
+    pub fn desired_distance(&self) -> f64 {
+        self.desired_distance
+    }
+
+    pub fn set_desired_distance(&mut self, desired_distance: f64) {
+        self.desired_distance = desired_distance;
+    }
+
+    // The mean distance of the end points of the second line to the first line
+    pub fn current_distance(&self) -> f64 {
+        match self.signed_distances() {
+            Some((start_distance, end_distance)) => (0.5 * (start_distance + end_distance)).abs(),
+            None => 0.0,
+        }
+    }
+
+    // The signed distances of the end points of the second line to the first line, positive on its
+    // left side. None if the first line has no direction.
+    fn signed_distances(&self) -> Option<(f64, f64)> {
+        let start1 = self.line1.borrow().start().borrow().data();
+        let end1 = self.line1.borrow().end().borrow().data();
+        let start2 = self.line2.borrow().start().borrow().data();
+        let end2 = self.line2.borrow().end().borrow().data();
+
+        let u = end1 - start1;
+        let length = u.norm();
+        if length < 1e-6 {
+            return None;
+        }
+        let distance = |point: Vector2<f64>| {
+            let w = point - start1;
+            (u.x * w.y - u.y * w.x) / length
+        };
+        Some((distance(start2), distance(end2)))
+    }
+
+    // The side of the first line the second line is mostly on
+    fn side(start_distance: f64, end_distance: f64) -> f64 {
+        if start_distance + end_distance >= 0.0 {
+            1.0
+        } else {
+            -1.0
+        }
+    }
+}
+
+impl ConstraintLike for DistanceBetweenLines {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![
+            PrimitiveCell::Line(self.line1.clone()),
+            PrimitiveCell::Line(self.line2.clone()),
+        ]
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let Some((start_distance, end_distance)) = self.signed_distances() else {
+            return DVector::zeros(2);
+        };
+        let desired_distance = Self::side(start_distance, end_distance) * self.desired_distance;
+        let start_err = start_distance - desired_distance;
+        let end_err = end_distance - desired_distance;
+        DVector::from_row_slice(&[start_err, end_err])
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let start1 = self.line1.borrow().start().borrow().data();
+        let end1 = self.line1.borrow().end().borrow().data();
+        let start2 = self.line2.borrow().start().borrow().data();
+        let end2 = self.line2.borrow().end().borrow().data();
+
+        let u = end1 - start1;
+        let length = u.norm();
+        if length < 1e-6 {
+            return;
+        }
+
+        let grad_start1 = self.line1.borrow().start_gradient();
+        let grad_end1 = self.line1.borrow().end_gradient();
+        let grad_start2 = self.line2.borrow().start_gradient();
+        let grad_end2 = self.line2.borrow().end_gradient();
+
+        let mut grad_line1 = SMatrix::<f64, 1, 4>::zeros();
+        let mut grad_line2 = SMatrix::<f64, 1, 4>::zeros();
+        for (i, (point, grad_point)) in [(start2, grad_start2), (end2, grad_end2)]
+            .into_iter()
+            .enumerate()
+        {
+            // The side only changes where the residuals jump, so it has no gradient
+            let w = point - start1;
+            let cross_product = u.x * w.y - u.y * w.x;
+            let grad_from_err = weights[i];
+            let grad_distance_from_u = SMatrix::<f64, 1, 2>::from_row_slice(&[w.y, -w.x]) / length
+                - cross_product / (length * length * length) * u.transpose();
+            let grad_distance_from_w = SMatrix::<f64, 1, 2>::from_row_slice(&[-u.y, u.x]) / length;
+
+            grad_line1 += grad_from_err
+                * (grad_distance_from_u * (grad_end1 - grad_start1)
+                    - grad_distance_from_w * grad_start1);
+            grad_line2 += grad_from_err * grad_distance_from_w * grad_point;
+        }
+
+        self.line1
+            .borrow_mut()
+            .add_to_gradient(grad_line1.as_view());
+        self.line2
+            .borrow_mut()
+            .add_to_gradient(grad_line2.as_view());
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::DistanceBetweenLines(self.clone())
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::{
+            distance::distance_between_lines::DistanceBetweenLines, ConstraintCell, ConstraintLike,
+        },
+        primitives::{line::Line, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    #[test]
+    fn test_distance_between_lines() {
+        let mut sketch = Sketch::new();
+
+        let line1_start = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let line1_end = Rc::new(RefCell::new(Point2::new(2.0, 0.3)));
+        let line1 = Rc::new(RefCell::new(Line::new(
+            line1_start.clone(),
+            line1_end.clone(),
+        )));
+        let line2_start = Rc::new(RefCell::new(Point2::new(0.5, 2.0)));
+        let line2_end = Rc::new(RefCell::new(Point2::new(3.0, 1.0)));
+        let line2 = Rc::new(RefCell::new(Line::new(
+            line2_start.clone(),
+            line2_end.clone(),
+        )));
+        for point in [&line1_start, &line1_end, &line2_start, &line2_end] {
+            sketch
+                .add_primitive(PrimitiveCell::Point2(point.clone()))
+                .unwrap();
+        }
+        sketch
+            .add_primitive(PrimitiveCell::Line(line1.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Line(line2.clone()))
+            .unwrap();
+
+        let constr1 = Rc::new(RefCell::new(DistanceBetweenLines::new(
+            line1.clone(),
+            line2.clone(),
+            0.75,
+        )));
+        sketch
+            .add_constraint(ConstraintCell::DistanceBetweenLines(constr1.clone()))
+            .unwrap();
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+        let solver = BFGSSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("line1: {:?}", line1.as_ref().borrow());
+        println!("line2: {:?}", line2.as_ref().borrow());
+        println!("distance: {:?}", constr1.borrow().current_distance());
+
+        assert!(constr1.borrow().loss_value() < 1e-8);
+        assert!((constr1.borrow().current_distance() - 0.75).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_distance_between_crossing_lines() {
+        let mut sketch = Sketch::new();
+
+        // The end points of the second line are about the desired distance away, but on opposite
+        // sides
+        let line1_start = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let line1_end = Rc::new(RefCell::new(Point2::new(2.0, 0.0)));
+        let line1 = Rc::new(RefCell::new(Line::new(
+            line1_start.clone(),
+            line1_end.clone(),
+        )));
+        let line2_start = Rc::new(RefCell::new(Point2::new(0.0, 1.2)));
+        let line2_end = Rc::new(RefCell::new(Point2::new(2.0, -1.0)));
+        let line2 = Rc::new(RefCell::new(Line::new(
+            line2_start.clone(),
+            line2_end.clone(),
+        )));
+        for point in [&line1_start, &line1_end, &line2_start, &line2_end] {
+            sketch
+                .add_primitive(PrimitiveCell::Point2(point.clone()))
+                .unwrap();
+        }
+        sketch
+            .add_primitive(PrimitiveCell::Line(line1.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Line(line2.clone()))
+            .unwrap();
+
+        let constr1 = Rc::new(RefCell::new(DistanceBetweenLines::new(
+            line1.clone(),
+            line2.clone(),
+            1.0,
+        )));
+        sketch
+            .add_constraint(ConstraintCell::DistanceBetweenLines(constr1.clone()))
+            .unwrap();
+        assert!(constr1.borrow().loss_value() > 1.0);
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+        let solver = BFGSSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        // Now the lines are parallel
+        let u1 = line1_end.borrow().data() - line1_start.borrow().data();
+        let u2 = line2_end.borrow().data() - line2_start.borrow().data();
+        assert!(constr1.borrow().loss_value() < 1e-8);
+        assert!((u1.x * u2.y - u1.y * u2.x).abs() < 1e-4);
+        assert!((constr1.borrow().current_distance() - 1.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,176 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::{DVector, DVectorView};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::ConstraintLike,
+    primitives::{arc::Arc, point2::Point2, PrimitiveCell},
+};
+
+// This is a sketch constraint that fixes the radial distance between a point and an arc, measured
+// to the circle the arc lies on.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct DistanceBetweenPointAndArc {
+    point: Rc<RefCell<Point2>>,
+    arc: Rc<RefCell<Arc>>,
+
+    desired_distance: f64,
+}
+
+impl DistanceBetweenPointAndArc {
+    pub fn new(point: Rc<RefCell<Point2>>, arc: Rc<RefCell<Arc>>, desired_distance: f64) -> Self {
+        assert!(desired_distance.is_finite());
+        Self {
+            point,
+            arc,
+            desired_distance,
+        }
+    }
+
+    pub fn point(&self) -> Rc<RefCell<Point2>> {
+        self.point.clone()
+    }
+
+    pub fn set_point(&mut self, point: Rc<RefCell<Point2>>) {
+        self.point = point;
+    }
+
+    pub fn arc(&self) -> Rc<RefCell<Arc>> {
+        self.arc.clone()
+    }
+
+    pub fn set_arc(&mut self, arc: Rc<RefCell<Arc>>) {
+        self.arc = arc;
+    }
+
+    pub fn desired_distance(&self) -> f64 {
+        self.desired_distance
+    }
+
+    pub fn set_desired_distance(&mut self, desired_distance: f64) {
+        self.desired_distance = desired_distance;
+    }
+
+    pub fn current_distance(&self) -> f64 {
+        let point = self.point.borrow().data();
+        let center = self.arc.borrow().center().borrow().data();
+        let radius = self.arc.borrow().radius();
+
+        ((point - center).norm() - radius).abs()
+    }
+}
+
+impl ConstraintLike for DistanceBetweenPointAndArc {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![
+            PrimitiveCell::Point2(self.point.clone()),
+            PrimitiveCell::Arc(self.arc.clone()),
+        ]
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let distance = self.current_distance();
+        let err = distance - self.desired_distance;
+        DVector::from_row_slice(&[err])
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let point = self.point.borrow().data();
+        let center = self.arc.borrow().center().borrow().data();
+        let radius = self.arc.borrow().radius();
+
+        let d = point - center;
+        let center_distance = d.norm();
+        if center_distance < 1e-6 {
+            return;
+        }
+
+        let radial_offset = center_distance - radius;
+        let grad_from_err = weights[0];
+        let grad_distance_from_radial_offset = radial_offset.signum();
+        let grad_center_distance_from_d = d.transpose() / center_distance;
+
+        let grad_point = self.point.borrow().point_gradient();
+        let grad_center = self.arc.borrow().center_gradient();
+        let grad_radius = self.arc.borrow().radius_gradient();
+
+        let grad_from_radial_offset = grad_from_err * grad_distance_from_radial_offset;
+
+        self.point.borrow_mut().add_to_gradient(
+            (grad_from_radial_offset * grad_center_distance_from_d * grad_point).as_view(),
+        );
+        self.arc.borrow_mut().add_to_gradient(
+            (grad_from_radial_offset * (-grad_center_distance_from_d * grad_center - grad_radius))
+                .as_view(),
+        );
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::DistanceBetweenPointAndArc(self.clone())
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::{
+            distance::distance_between_point_and_arc::DistanceBetweenPointAndArc, ConstraintCell,
+            ConstraintLike,
+        },
+        primitives::{arc::Arc, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    #[test]
+    fn test_distance_between_point_and_arc() {
+        let mut sketch = Sketch::new();
+
+        let center = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let arc = Rc::new(RefCell::new(Arc::new(
+            center.clone(),
+            1.0,
+            false,
+            0.0,
+            std::f64::consts::PI,
+        )));
+        let point = Rc::new(RefCell::new(Point2::new(3.0, 4.0)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(center.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Arc(arc.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Point2(point.clone()))
+            .unwrap();
+
+        let constr1 = Rc::new(RefCell::new(DistanceBetweenPointAndArc::new(
+            point.clone(),
+            arc.clone(),
+            0.5,
+        )));
+        sketch
+            .add_constraint(ConstraintCell::DistanceBetweenPointAndArc(constr1.clone()))
+            .unwrap();
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+        let solver = BFGSSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("arc: {:?}", arc.as_ref().borrow());
+        println!("point: {:?}", point.as_ref().borrow());
+        println!("distance: {:?}", constr1.borrow().current_distance());
+
+        assert!(constr1.borrow().loss_value() < 1e-8);
+    }
+}
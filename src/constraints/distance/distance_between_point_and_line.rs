@@ -0,0 +1,189 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::{DVector, DVectorView, SMatrix};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::ConstraintLike,
+    primitives::{line::Line, point2::Point2, PrimitiveCell},
+};
+
+// This is a sketch constraint that fixes the perpendicular distance between a point and the
+// infinite extension of a line.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct DistanceBetweenPointAndLine {
+    point: Rc<RefCell<Point2>>,
+    line: Rc<RefCell<Line>>,
+
+    desired_distance: f64,
+}
+
+impl DistanceBetweenPointAndLine {
+    pub fn new(point: Rc<RefCell<Point2>>, line: Rc<RefCell<Line>>, desired_distance: f64) -> Self {
+        assert!(desired_distance.is_finite());
+        Self {
+            point,
+            line,
+            desired_distance,
+        }
+    }
+
+    pub fn point(&self) -> Rc<RefCell<Point2>> {
+        self.point.clone()
+    }
+
+    pub fn set_point(&mut self, point: Rc<RefCell<Point2>>) {
+        self.point = point;
+    }
+
+    pub fn line(&self) -> Rc<RefCell<Line>> {
+        self.line.clone()
+    }
+
+    pub fn set_line(&mut self, line: Rc<RefCell<Line>>) {
+        self.line = line;
+    }
+
+    pub fn desired_distance(&self) -> f64 {
+        self.desired_distance
+    }
+
+    pub fn set_desired_distance(&mut self, desired_distance: f64) {
+        self.desired_distance = desired_distance;
+    }
+
+    pub fn current_distance(&self) -> f64 {
+        let point = self.point.borrow().data();
+        let start = self.line.borrow().start().borrow().data();
+        let end = self.line.borrow().end().borrow().data();
+
+        let u = end - start;
+        let w = point - start;
+        let length = u.norm();
+        if length < 1e-6 {
+            return w.norm();
+        }
+        (u.x * w.y - u.y * w.x).abs() / length
+    }
+}
+
+impl ConstraintLike for DistanceBetweenPointAndLine {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![
+            PrimitiveCell::Point2(self.point.clone()),
+            PrimitiveCell::Line(self.line.clone()),
+        ]
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let distance = self.current_distance();
+        let err = distance - self.desired_distance;
+        DVector::from_row_slice(&[err])
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let point = self.point.borrow().data();
+        let start = self.line.borrow().start().borrow().data();
+        let end = self.line.borrow().end().borrow().data();
+
+        let u = end - start;
+        let w = point - start;
+        let length = u.norm();
+        if length < 1e-6 {
+            return;
+        }
+
+        let cross_product = u.x * w.y - u.y * w.x;
+        let grad_from_err = weights[0];
+        let sign = cross_product.signum();
+        let grad_distance_from_u = sign * SMatrix::<f64, 1, 2>::from_row_slice(&[w.y, -w.x])
+            / length
+            - cross_product.abs() / (length * length * length) * u.transpose();
+        let grad_distance_from_w =
+            sign * SMatrix::<f64, 1, 2>::from_row_slice(&[-u.y, u.x]) / length;
+
+        let grad_point = self.point.borrow().point_gradient();
+        let grad_start = self.line.borrow().start_gradient();
+        let grad_end = self.line.borrow().end_gradient();
+
+        self.point
+            .borrow_mut()
+            .add_to_gradient((grad_from_err * grad_distance_from_w * grad_point).as_view());
+        self.line.borrow_mut().add_to_gradient(
+            (grad_from_err
+                * (grad_distance_from_u * (grad_end - grad_start)
+                    - grad_distance_from_w * grad_start))
+                .as_view(),
+        );
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::DistanceBetweenPointAndLine(self.clone())
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::{
+            distance::distance_between_point_and_line::DistanceBetweenPointAndLine, ConstraintCell,
+            ConstraintLike,
+        },
+        primitives::{line::Line, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    #[test]
+    fn test_distance_between_point_and_line() {
+        let mut sketch = Sketch::new();
+
+        let line_start = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let line_end = Rc::new(RefCell::new(Point2::new(2.0, 0.5)));
+        let line = Rc::new(RefCell::new(Line::new(
+            line_start.clone(),
+            line_end.clone(),
+        )));
+        let point = Rc::new(RefCell::new(Point2::new(1.0, 3.0)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(line_start.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Point2(line_end.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Line(line.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Point2(point.clone()))
+            .unwrap();
+
+        let constr1 = Rc::new(RefCell::new(DistanceBetweenPointAndLine::new(
+            point.clone(),
+            line.clone(),
+            1.5,
+        )));
+        sketch
+            .add_constraint(ConstraintCell::DistanceBetweenPointAndLine(constr1.clone()))
+            .unwrap();
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+        let solver = BFGSSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("line: {:?}", line.as_ref().borrow());
+        println!("point: {:?}", point.as_ref().borrow());
+        println!("distance: {:?}", constr1.borrow().current_distance());
+
+        assert!(constr1.borrow().loss_value() < 1e-8);
+        assert!((constr1.borrow().current_distance() - 1.5).abs() < 1e-4);
+    }
+}
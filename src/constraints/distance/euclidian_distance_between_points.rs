@@ -1,5 +1,6 @@
 use std::{cell::RefCell, rc::Rc};
 
+use nalgebra::{DVector, DVectorView};
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "tsify")]
@@ -76,30 +77,27 @@ impl ConstraintLike for EuclidianDistanceBetweenPoints {
         ]
     }
 
-    fn loss_value(&self) -> f64 {
+    fn residuals(&self) -> DVector<f64> {
         let distance = self.current_distance();
         let err = distance - self.desired_distance;
-        0.5 * err * err
+        DVector::from_row_slice(&[err])
     }
 
-    fn update_gradient(&mut self) {
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
         let point1 = self.point1.borrow().data();
         let point2 = self.point2.borrow().data();
 
         let d = point1 - point2;
 
         let distance = d.norm();
-        let err = distance - self.desired_distance;
-        let _loss = 0.5 * err * err;
-
         if distance < 1e-6 {
             return;
         }
-        let grad_loss_from_err = err;
+        let grad_from_err = weights[0];
         let grad_err_from_distance = 1.0;
         let grad_distance_from_d = d.transpose() / distance;
 
-        let grad_from_d = grad_loss_from_err * grad_err_from_distance * grad_distance_from_d;
+        let grad_from_d = grad_from_err * grad_err_from_distance * grad_distance_from_d;
 
         let grad_point1 = self.point1.borrow().point_gradient();
         let grad_point2 = self.point2.borrow().point_gradient();
@@ -1,6 +1,6 @@
 use std::{cell::RefCell, rc::Rc};
 
-use nalgebra::Matrix1x2;
+use nalgebra::{DVector, DVectorView, Matrix1x2};
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "tsify")]
@@ -76,27 +76,18 @@ impl ConstraintLike for HorizontalDistanceBetweenPoints {
         ]
     }
 
-    fn loss_value(&self) -> f64 {
+    fn residuals(&self) -> DVector<f64> {
         let distance = self.current_distance();
         let err = distance - self.desired_distance;
-        0.5 * err * err
+        DVector::from_row_slice(&[err])
     }
 
-    fn update_gradient(&mut self) {
-        let point1 = self.point1.borrow().data();
-        let point2 = self.point2.borrow().data();
-
-        let d = point2 - point1;
-
-        let distance = d.x;
-        let err = distance - self.desired_distance;
-        let _loss = 0.5 * err * err;
-
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
         let grad_distance_from_d = Matrix1x2::<f64>::new(1.0, 0.0);
         let grad_err_from_distance = 1.0;
-        let grad_loss_from_err = err;
+        let grad_from_err = weights[0];
 
-        let grad_from_d = grad_loss_from_err * grad_err_from_distance * grad_distance_from_d;
+        let grad_from_d = grad_from_err * grad_err_from_distance * grad_distance_from_d;
 
         let grad_point1 = self.point1.borrow().point_gradient();
         let grad_point2 = self.point2.borrow().point_gradient();
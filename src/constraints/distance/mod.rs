@@ -1,3 +1,7 @@
+pub mod distance_between_lines;
+pub mod distance_between_point_and_arc;
+pub mod distance_between_point_and_circle;
+pub mod distance_between_point_and_line;
 pub mod euclidian_distance_between_points;
 pub mod horizontal_distance_between_points;
 pub mod vertical_distance_between_points;
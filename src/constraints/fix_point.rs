@@ -1,6 +1,6 @@
 use std::{cell::RefCell, rc::Rc};
 
-use nalgebra::Vector2;
+use nalgebra::{DVector, DVectorView, SMatrix, Vector2};
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "tsify")]
@@ -49,17 +49,14 @@ impl ConstraintLike for FixPoint {
         vec![PrimitiveCell::Point2(self.point.clone())]
     }
 
-    fn loss_value(&self) -> f64 {
+    fn residuals(&self) -> DVector<f64> {
         let point = self.point.borrow().data();
         let d = point - self.desired_pos;
-        0.5 * d.norm_squared()
+        DVector::from_row_slice(&[d.x, d.y])
     }
 
-    fn update_gradient(&mut self) {
-        let point = self.point.borrow().data();
-        let d = point - self.desired_pos;
-
-        let grad = d.transpose();
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let grad = SMatrix::<f64, 1, 2>::from_row_slice(&[weights[0], weights[1]]);
         self.point.borrow_mut().add_to_gradient(grad.as_view());
     }
 
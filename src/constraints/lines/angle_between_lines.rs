@@ -0,0 +1,244 @@
+use std::{
+    cell::RefCell,
+    f64::consts::{PI, TAU},
+    rc::Rc,
+};
+
+use nalgebra::{DVector, DVectorView, SMatrix, Vector2};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::ConstraintLike,
+    primitives::{line::Line, PrimitiveCell},
+};
+
+// This is a sketch constraint that fixes the signed angle from the direction of the first line to the
+// direction of the second line, measured counter clockwise. If the supplementary angle is accepted,
+// the constraint is also satisfied when one of the lines points the other way, so the solver picks
+// whichever configuration is closer instead of flipping a line around.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct AngleBetweenLines {
+    line1: Rc<RefCell<Line>>,
+    line2: Rc<RefCell<Line>>,
+
+    desired_angle: f64,
+    accept_supplementary: bool,
+}
+
+impl AngleBetweenLines {
+    pub fn new(
+        line1: Rc<RefCell<Line>>,
+        line2: Rc<RefCell<Line>>,
+        desired_angle: f64,
+        accept_supplementary: bool,
+    ) -> Self {
+        assert!(desired_angle.is_finite());
+        Self {
+            line1,
+            line2,
+            desired_angle,
+            accept_supplementary,
+        }
+    }
+
+    pub fn line1(&self) -> Rc<RefCell<Line>> {
+        self.line1.clone()
+    }
+
+    pub fn set_line1(&mut self, line1: Rc<RefCell<Line>>) {
+        self.line1 = line1;
+    }
+
+    pub fn line2(&self) -> Rc<RefCell<Line>> {
+        self.line2.clone()
+    }
+
+    pub fn set_line2(&mut self, line2: Rc<RefCell<Line>>) {
+        self.line2 = line2;
+    }
+
+    pub fn desired_angle(&self) -> f64 {
+        self.desired_angle
+    }
+
+    pub fn set_desired_angle(&mut self, desired_angle: f64) {
+        self.desired_angle = desired_angle;
+    }
+
+    pub fn accept_supplementary(&self) -> bool {
+        self.accept_supplementary
+    }
+
+    pub fn set_accept_supplementary(&mut self, accept_supplementary: bool) {
+        self.accept_supplementary = accept_supplementary;
+    }
+
+    // The signed angle from the first to the second line in the range (-pi, pi]
+    pub fn current_angle(&self) -> f64 {
+        let (u1, u2) = self.directions();
+        let cross_product = u1.x * u2.y - u1.y * u2.x;
+        let dot_product = u1.dot(&u2);
+        cross_product.atan2(dot_product)
+    }
+
+    fn directions(&self) -> (Vector2<f64>, Vector2<f64>) {
+        let start1 = self.line1.borrow().start().borrow().data();
+        let end1 = self.line1.borrow().end().borrow().data();
+        let start2 = self.line2.borrow().start().borrow().data();
+        let end2 = self.line2.borrow().end().borrow().data();
+        (end1 - start1, end2 - start2)
+    }
+
+    // The difference to the desired angle, wrapped so that the closest valid angle is used
+    fn angle_error(&self) -> f64 {
+        let period = if self.accept_supplementary { PI } else { TAU };
+        let difference = self.current_angle() - self.desired_angle;
+        (difference + 0.5 * period).rem_euclid(period) - 0.5 * period
+    }
+}
+
+impl ConstraintLike for AngleBetweenLines {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![
+            PrimitiveCell::Line(self.line1.clone()),
+            PrimitiveCell::Line(self.line2.clone()),
+        ]
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let (u1, u2) = self.directions();
+        if u1.norm() < 1e-6 || u2.norm() < 1e-6 {
+            return DVector::zeros(1);
+        }
+
+        let err = self.angle_error();
+        DVector::from_row_slice(&[err])
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let (u1, u2) = self.directions();
+        let norm1_squared = u1.norm_squared();
+        let norm2_squared = u2.norm_squared();
+        if norm1_squared < 1e-12 || norm2_squared < 1e-12 {
+            return;
+        }
+
+        // d/du atan2(cross, dot) simplifies to the perpendicular of each direction
+        let grad_from_err = weights[0];
+        let grad_theta_from_u1 =
+            SMatrix::<f64, 1, 2>::from_row_slice(&[u1.y, -u1.x]) / norm1_squared;
+        let grad_theta_from_u2 =
+            SMatrix::<f64, 1, 2>::from_row_slice(&[-u2.y, u2.x]) / norm2_squared;
+
+        let grad_start1 = self.line1.borrow().start_gradient();
+        let grad_end1 = self.line1.borrow().end_gradient();
+        let grad_start2 = self.line2.borrow().start_gradient();
+        let grad_end2 = self.line2.borrow().end_gradient();
+
+        self.line1.borrow_mut().add_to_gradient(
+            (grad_from_err * grad_theta_from_u1 * (grad_end1 - grad_start1)).as_view(),
+        );
+        self.line2.borrow_mut().add_to_gradient(
+            (grad_from_err * grad_theta_from_u2 * (grad_end2 - grad_start2)).as_view(),
+        );
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::AngleBetweenLines(self.clone())
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, f64::consts::PI, rc::Rc};
+
+    use crate::{
+        constraints::{
+            lines::angle_between_lines::AngleBetweenLines, ConstraintCell, ConstraintLike,
+        },
+        primitives::{line::Line, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    fn solve_angle(
+        line2_end: (f64, f64),
+        desired_angle: f64,
+        accept_supplementary: bool,
+    ) -> Rc<RefCell<AngleBetweenLines>> {
+        let mut sketch = Sketch::new();
+
+        let line1_start = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let line1_end = Rc::new(RefCell::new(Point2::new(2.0, 0.0)));
+        let line1 = Rc::new(RefCell::new(Line::new(
+            line1_start.clone(),
+            line1_end.clone(),
+        )));
+        let line2_start = Rc::new(RefCell::new(Point2::new(0.0, 1.0)));
+        let line2_end = Rc::new(RefCell::new(Point2::new(line2_end.0, line2_end.1)));
+        let line2 = Rc::new(RefCell::new(Line::new(
+            line2_start.clone(),
+            line2_end.clone(),
+        )));
+        for point in [&line1_start, &line1_end, &line2_start, &line2_end] {
+            sketch
+                .add_primitive(PrimitiveCell::Point2(point.clone()))
+                .unwrap();
+        }
+        sketch
+            .add_primitive(PrimitiveCell::Line(line1.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Line(line2.clone()))
+            .unwrap();
+
+        let constr1 = Rc::new(RefCell::new(AngleBetweenLines::new(
+            line1.clone(),
+            line2.clone(),
+            desired_angle,
+            accept_supplementary,
+        )));
+        sketch
+            .add_constraint(ConstraintCell::AngleBetweenLines(constr1.clone()))
+            .unwrap();
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+        let solver = BFGSSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("line1: {:?}", line1.as_ref().borrow());
+        println!("line2: {:?}", line2.as_ref().borrow());
+        println!(
+            "current angle: {}",
+            constr1.borrow().current_angle() * 180.0 / PI
+        );
+
+        constr1
+    }
+
+    #[test]
+    fn test_angle_between_lines() {
+        // Starting below the first line, the signed angle has to cross zero
+        let constr1 = solve_angle((1.0, 0.5), PI / 6.0, false);
+        assert!(constr1.borrow().loss_value() < 1e-10);
+        assert!((constr1.borrow().current_angle() - PI / 6.0).abs() < 1e-5);
+
+        let constr1 = solve_angle((1.0, 1.5), -PI / 6.0, false);
+        assert!(constr1.borrow().loss_value() < 1e-10);
+        assert!((constr1.borrow().current_angle() + PI / 6.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_angle_between_lines_supplementary() {
+        // The second line points backwards, so the supplementary angle is closer
+        let constr1 = solve_angle((-1.0, 1.5), PI / 6.0, true);
+        assert!(constr1.borrow().loss_value() < 1e-10);
+        assert!((constr1.borrow().current_angle() - (PI / 6.0 - PI)).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,179 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::{DVector, DVectorView, SMatrix};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::ConstraintLike,
+    primitives::{line::Line, PrimitiveCell},
+};
+
+// This is a sketch constraint that makes two lines lie on the same infinite line. Both end points of
+// the second line are pulled onto the infinite extension of the first line.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct Collinear {
+    line1: Rc<RefCell<Line>>,
+    line2: Rc<RefCell<Line>>,
+}
+
+impl Collinear {
+    pub fn new(line1: Rc<RefCell<Line>>, line2: Rc<RefCell<Line>>) -> Self {
+        Self { line1, line2 }
+    }
+
+    pub fn line1(&self) -> Rc<RefCell<Line>> {
+        self.line1.clone()
+    }
+
+    pub fn set_line1(&mut self, line1: Rc<RefCell<Line>>) {
+        self.line1 = line1;
+    }
+
+    pub fn line2(&self) -> Rc<RefCell<Line>> {
+        self.line2.clone()
+    }
+
+    pub fn set_line2(&mut self, line2: Rc<RefCell<Line>>) {
+        self.line2 = line2;
+    }
+}
+
+impl ConstraintLike for Collinear {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![
+            PrimitiveCell::Line(self.line1.clone()),
+            PrimitiveCell::Line(self.line2.clone()),
+        ]
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let start1 = self.line1.borrow().start().borrow().data();
+        let end1 = self.line1.borrow().end().borrow().data();
+        let start2 = self.line2.borrow().start().borrow().data();
+        let end2 = self.line2.borrow().end().borrow().data();
+
+        let u = end1 - start1;
+        let length = u.norm();
+        if length < 1e-6 {
+            return DVector::zeros(2);
+        }
+
+        DVector::from_iterator(
+            2,
+            [start2, end2].iter().map(|point| {
+                let w = point - start1;
+                (u.x * w.y - u.y * w.x) / length
+            }),
+        )
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let start1 = self.line1.borrow().start().borrow().data();
+        let end1 = self.line1.borrow().end().borrow().data();
+        let start2 = self.line2.borrow().start().borrow().data();
+        let end2 = self.line2.borrow().end().borrow().data();
+
+        let u = end1 - start1;
+        let length = u.norm();
+        if length < 1e-6 {
+            return;
+        }
+
+        let grad_start1 = self.line1.borrow().start_gradient();
+        let grad_end1 = self.line1.borrow().end_gradient();
+        let grad_start2 = self.line2.borrow().start_gradient();
+        let grad_end2 = self.line2.borrow().end_gradient();
+
+        let mut grad_line1 = SMatrix::<f64, 1, 4>::zeros();
+        let mut grad_line2 = SMatrix::<f64, 1, 4>::zeros();
+        for (i, (point, grad_point)) in [(start2, grad_start2), (end2, grad_end2)]
+            .into_iter()
+            .enumerate()
+        {
+            let w = point - start1;
+            let cross_product = u.x * w.y - u.y * w.x;
+
+            let grad_from_distance = weights[i];
+            let grad_distance_from_u = SMatrix::<f64, 1, 2>::from_row_slice(&[w.y, -w.x]) / length
+                - cross_product / (length * length * length) * u.transpose();
+            let grad_distance_from_w = SMatrix::<f64, 1, 2>::from_row_slice(&[-u.y, u.x]) / length;
+
+            grad_line1 += grad_from_distance
+                * (grad_distance_from_u * (grad_end1 - grad_start1)
+                    - grad_distance_from_w * grad_start1);
+            grad_line2 += grad_from_distance * grad_distance_from_w * grad_point;
+        }
+
+        self.line1
+            .borrow_mut()
+            .add_to_gradient(grad_line1.as_view());
+        self.line2
+            .borrow_mut()
+            .add_to_gradient(grad_line2.as_view());
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::Collinear(self.clone())
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::{lines::collinear::Collinear, ConstraintCell, ConstraintLike},
+        primitives::{line::Line, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    #[test]
+    fn test_collinear() {
+        let mut sketch = Sketch::new();
+
+        let line1_start = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let line1_end = Rc::new(RefCell::new(Point2::new(2.0, 0.3)));
+        let line1 = Rc::new(RefCell::new(Line::new(
+            line1_start.clone(),
+            line1_end.clone(),
+        )));
+        let line2_start = Rc::new(RefCell::new(Point2::new(3.0, 1.0)));
+        let line2_end = Rc::new(RefCell::new(Point2::new(5.0, 0.2)));
+        let line2 = Rc::new(RefCell::new(Line::new(
+            line2_start.clone(),
+            line2_end.clone(),
+        )));
+        for point in [&line1_start, &line1_end, &line2_start, &line2_end] {
+            sketch
+                .add_primitive(PrimitiveCell::Point2(point.clone()))
+                .unwrap();
+        }
+        sketch
+            .add_primitive(PrimitiveCell::Line(line1.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Line(line2.clone()))
+            .unwrap();
+
+        let constr1 = Rc::new(RefCell::new(Collinear::new(line1.clone(), line2.clone())));
+        sketch
+            .add_constraint(ConstraintCell::Collinear(constr1.clone()))
+            .unwrap();
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+        let solver = BFGSSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("line1: {:?}", line1.as_ref().borrow());
+        println!("line2: {:?}", line2.as_ref().borrow());
+
+        assert!(constr1.borrow().loss_value() < 1e-10);
+    }
+}
@@ -1,5 +1,6 @@
 use std::{cell::RefCell, rc::Rc};
 
+use nalgebra::{DVector, DVectorView};
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "tsify")]
@@ -49,7 +50,7 @@ impl ConstraintLike for EqualLength {
         ]
     }
 
-    fn loss_value(&self) -> f64 {
+    fn residuals(&self) -> DVector<f64> {
         let start1 = self.line1.borrow().start().borrow().data();
         let end1 = self.line1.borrow().end().borrow().data();
         let start2 = self.line2.borrow().start().borrow().data();
@@ -59,10 +60,10 @@ impl ConstraintLike for EqualLength {
         let d2 = end2 - start2;
 
         let difference = d1.norm() - d2.norm();
-        0.5 * difference * difference
+        DVector::from_row_slice(&[difference])
     }
 
-    fn update_gradient(&mut self) {
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
         let start1 = self.line1.borrow().start().borrow().data();
         let end1 = self.line1.borrow().end().borrow().data();
         let start2 = self.line2.borrow().start().borrow().data();
@@ -71,10 +72,7 @@ impl ConstraintLike for EqualLength {
         let d1 = end1 - start1;
         let d2 = end2 - start2;
 
-        let difference = d1.norm() - d2.norm();
-        let _loss = 0.5 * difference * difference;
-
-        let grad_from_difference = difference;
+        let grad_from_difference = weights[0];
         let grad_difference_from_d1 = d1.transpose() / d1.norm();
         let grad_difference_from_d2 = -d2.transpose() / d2.norm();
 
@@ -0,0 +1,146 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::{DVector, DVectorView};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::ConstraintLike,
+    primitives::{line::Line, PrimitiveCell},
+};
+
+// This is a sketch constraint that fixes the length of a line, i.e. the distance between its start
+// and end point, whichever points those currently are.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct LineLength {
+    line: Rc<RefCell<Line>>,
+
+    desired_length: f64,
+}
+
+impl LineLength {
+    pub fn new(line: Rc<RefCell<Line>>, desired_length: f64) -> Self {
+        assert!(desired_length.is_finite());
+        Self {
+            line,
+            desired_length,
+        }
+    }
+
+    pub fn line(&self) -> Rc<RefCell<Line>> {
+        self.line.clone()
+    }
+
+    pub fn set_line(&mut self, line: Rc<RefCell<Line>>) {
+        self.line = line;
+    }
+
+    pub fn desired_length(&self) -> f64 {
+        self.desired_length
+    }
+
+    pub fn set_desired_length(&mut self, desired_length: f64) {
+        self.desired_length = desired_length;
+    }
+
+    pub fn current_length(&self) -> f64 {
+        let start = self.line.borrow().start().borrow().data();
+        let end = self.line.borrow().end().borrow().data();
+        (end - start).norm()
+    }
+}
+
+impl ConstraintLike for LineLength {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![PrimitiveCell::Line(self.line.clone())]
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let err = self.current_length() - self.desired_length;
+        DVector::from_row_slice(&[err])
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let start = self.line.borrow().start().borrow().data();
+        let end = self.line.borrow().end().borrow().data();
+
+        let d = end - start;
+        let length = d.norm();
+        if length < 1e-6 {
+            return;
+        }
+
+        let grad_from_err = weights[0];
+        let grad_length_from_d = d.transpose() / length;
+
+        let grad_start = self.line.borrow().start_gradient();
+        let grad_end = self.line.borrow().end_gradient();
+
+        self.line.borrow_mut().add_to_gradient(
+            (grad_from_err * grad_length_from_d * (grad_end - grad_start)).as_view(),
+        );
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::LineLength(self.clone())
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::{lines::line_length::LineLength, ConstraintCell, ConstraintLike},
+        primitives::{line::Line, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    #[test]
+    fn test_line_length() {
+        let mut sketch = Sketch::new();
+
+        let line_start = Rc::new(RefCell::new(Point2::new(1.0, 1.0)));
+        let line_end = Rc::new(RefCell::new(Point2::new(2.0, 3.0)));
+        let other_end = Rc::new(RefCell::new(Point2::new(-1.0, 0.5)));
+        let line = Rc::new(RefCell::new(Line::new(
+            line_start.clone(),
+            line_end.clone(),
+        )));
+        for point in [&line_start, &line_end, &other_end] {
+            sketch
+                .add_primitive(PrimitiveCell::Point2(point.clone()))
+                .unwrap();
+        }
+        sketch
+            .add_primitive(PrimitiveCell::Line(line.clone()))
+            .unwrap();
+
+        let constr1 = Rc::new(RefCell::new(LineLength::new(line.clone(), 4.0)));
+        sketch
+            .add_constraint(ConstraintCell::LineLength(constr1.clone()))
+            .unwrap();
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+        let solver = BFGSSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("line: {:?}", line.as_ref().borrow());
+        assert!(constr1.borrow().loss_value() < 1e-10);
+        assert!((constr1.borrow().current_length() - 4.0).abs() < 1e-5);
+
+        // The length stays attached to the line when its end point is replaced
+        line.borrow_mut().set_end(other_end.clone());
+        solver.solve(&mut sketch).unwrap();
+
+        println!("line: {:?}", line.as_ref().borrow());
+        assert!(constr1.borrow().loss_value() < 1e-10);
+        assert!((constr1.borrow().current_length() - 4.0).abs() < 1e-5);
+    }
+}
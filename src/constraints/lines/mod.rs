@@ -1,5 +1,183 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::{SMatrix, Vector2};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::primitives::{infinite_line::InfiniteLine, line::Line, ray::Ray, PrimitiveCell};
+
+pub mod angle_between_lines;
+pub mod collinear;
 pub mod equal_length;
 pub mod horizontal_line;
+pub mod line_length;
 pub mod parallel_lines;
 pub mod perpendicular_lines;
+pub mod points_collinear;
 pub mod vertical_line;
+
+// A primitive that describes a straight line, so it can be used by the line constraints. Lines are
+// described by an anchor point they pass through and a direction, which is only normalized for
+// infinite lines and rays.
+//
+// The horizontal, vertical, parallel, perpendicular and point on line constraints take any of them,
+// so their accessors return a LinePrimitive instead of a Line, as_line gives the line back. It is
+// serialized without a tag, so constraints saved with a plain line still deserialize.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+#[serde(untagged)]
+pub enum LinePrimitive {
+    Line(Rc<RefCell<Line>>),
+    InfiniteLine(Rc<RefCell<InfiniteLine>>),
+    Ray(Rc<RefCell<Ray>>),
+}
+
+impl From<Rc<RefCell<Line>>> for LinePrimitive {
+    fn from(line: Rc<RefCell<Line>>) -> Self {
+        LinePrimitive::Line(line)
+    }
+}
+
+impl From<Rc<RefCell<InfiniteLine>>> for LinePrimitive {
+    fn from(line: Rc<RefCell<InfiniteLine>>) -> Self {
+        LinePrimitive::InfiniteLine(line)
+    }
+}
+
+impl From<Rc<RefCell<Ray>>> for LinePrimitive {
+    fn from(ray: Rc<RefCell<Ray>>) -> Self {
+        LinePrimitive::Ray(ray)
+    }
+}
+
+impl LinePrimitive {
+    pub fn as_line(&self) -> Option<Rc<RefCell<Line>>> {
+        match self {
+            LinePrimitive::Line(line) => Some(line.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn anchor(&self) -> Vector2<f64> {
+        match self {
+            LinePrimitive::Line(line) => line.borrow().start().borrow().data(),
+            LinePrimitive::InfiniteLine(line) => line.borrow().point().borrow().data(),
+            LinePrimitive::Ray(ray) => ray.borrow().origin().borrow().data(),
+        }
+    }
+
+    pub fn direction(&self) -> Vector2<f64> {
+        match self {
+            LinePrimitive::Line(line) => {
+                line.borrow().end().borrow().data() - line.borrow().start().borrow().data()
+            }
+            LinePrimitive::InfiniteLine(line) => line.borrow().direction(),
+            LinePrimitive::Ray(ray) => ray.borrow().direction(),
+        }
+    }
+
+    pub fn to_primitive_cell(&self) -> PrimitiveCell {
+        match self {
+            LinePrimitive::Line(line) => PrimitiveCell::Line(line.clone()),
+            LinePrimitive::InfiniteLine(line) => PrimitiveCell::InfiniteLine(line.clone()),
+            LinePrimitive::Ray(ray) => PrimitiveCell::Ray(ray.clone()),
+        }
+    }
+
+    // Adds the derivatives of the loss with respect to the anchor and the direction to the gradient
+    // of the primitive
+    pub(crate) fn add_to_gradient(
+        &self,
+        grad_from_anchor: SMatrix<f64, 1, 2>,
+        grad_from_direction: SMatrix<f64, 1, 2>,
+    ) {
+        match self {
+            LinePrimitive::Line(line) => {
+                let grad_start = line.borrow().start_gradient();
+                let grad_end = line.borrow().end_gradient();
+                line.borrow_mut().add_to_gradient(
+                    (grad_from_anchor * grad_start + grad_from_direction * (grad_end - grad_start))
+                        .as_view(),
+                );
+            }
+            LinePrimitive::InfiniteLine(line) => {
+                let grad_point = line.borrow().point_gradient();
+                let grad_direction = line.borrow().direction_gradient();
+                line.borrow_mut().add_to_gradient(
+                    (grad_from_anchor * grad_point + grad_from_direction * grad_direction)
+                        .as_view(),
+                );
+            }
+            LinePrimitive::Ray(ray) => {
+                let grad_origin = ray.borrow().origin_gradient();
+                let grad_direction = ray.borrow().direction_gradient();
+                ray.borrow_mut().add_to_gradient(
+                    (grad_from_anchor * grad_origin + grad_from_direction * grad_direction)
+                        .as_view(),
+                );
+            }
+        }
+    }
+
+    pub(crate) fn add_to_direction_gradient(&self, grad_from_direction: SMatrix<f64, 1, 2>) {
+        self.add_to_gradient(SMatrix::<f64, 1, 2>::zeros(), grad_from_direction);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::lines::{
+            horizontal_line::HorizontalLine, parallel_lines::ParallelLines, LinePrimitive,
+        },
+        primitives::{infinite_line::InfiniteLine, point2::Point2, ray::Ray},
+    };
+
+    // A line from (0, 0) to (1, 2) as it was serialized before the line constraints took infinite
+    // lines and rays
+    const LINE_JSON: &str = r#"{
+        "start": {"data": [0.0, 0.0], "gradient": [0.0, 0.0]},
+        "end": {"data": [1.0, 2.0], "gradient": [0.0, 0.0]},
+        "empty": []
+    }"#;
+
+    #[test]
+    fn test_deserialize_plain_lines() {
+        let json = format!(r#"{{"line": {LINE_JSON}}}"#);
+        let constraint: HorizontalLine = serde_json::from_str(&json).unwrap();
+        let line = constraint.line().as_line().unwrap();
+        assert_eq!(line.borrow().end().borrow().data().y, 2.0);
+
+        let json = format!(r#"{{"line1": {LINE_JSON}, "line2": {LINE_JSON}}}"#);
+        let constraint: ParallelLines = serde_json::from_str(&json).unwrap();
+        assert!(constraint.line1().as_line().is_some());
+        assert!(constraint.line2().as_line().is_some());
+
+        // Lines are still serialized the same way
+        let value = serde_json::to_value(&constraint).unwrap();
+        let line: serde_json::Value = serde_json::from_str(LINE_JSON).unwrap();
+        assert_eq!(value["line1"], line);
+    }
+
+    #[test]
+    fn test_serde_round_trip_infinite_lines_and_rays() {
+        let point = Rc::new(RefCell::new(Point2::new(1.0, -1.0)));
+        for line in [
+            LinePrimitive::InfiniteLine(Rc::new(RefCell::new(InfiniteLine::new(
+                point.clone(),
+                0.3,
+            )))),
+            LinePrimitive::Ray(Rc::new(RefCell::new(Ray::new(point.clone(), 0.3)))),
+        ] {
+            let json = serde_json::to_string(&line).unwrap();
+            let deserialized: LinePrimitive = serde_json::from_str(&json).unwrap();
+            assert_eq!(deserialized, line);
+            assert!(deserialized.as_line().is_none());
+        }
+    }
+}
@@ -1,14 +1,12 @@
-use std::{cell::RefCell, rc::Rc};
-
-use nalgebra::Matrix2;
+use nalgebra::{DVector, DVectorView, Matrix2};
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "tsify")]
 use tsify::Tsify;
 
 use crate::{
-    constraints::ConstraintLike,
-    primitives::{line::Line, PrimitiveCell},
+    constraints::{lines::LinePrimitive, ConstraintLike},
+    primitives::PrimitiveCell,
 };
 
 // This is a sketch constraint that makes the end point of an arc coincident with a point.
@@ -16,66 +14,59 @@ use crate::{
 #[cfg_attr(feature = "tsify", derive(Tsify))]
 #[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
 pub struct PerpendicularLines {
-    line1: Rc<RefCell<Line>>,
-    line2: Rc<RefCell<Line>>,
+    line1: LinePrimitive,
+    line2: LinePrimitive,
 }
 
 impl PerpendicularLines {
-    pub fn new(line1: Rc<RefCell<Line>>, line2: Rc<RefCell<Line>>) -> Self {
-        Self { line1, line2 }
+    pub fn new(line1: impl Into<LinePrimitive>, line2: impl Into<LinePrimitive>) -> Self {
+        Self {
+            line1: line1.into(),
+            line2: line2.into(),
+        }
     }
 
-    pub fn line1(&self) -> Rc<RefCell<Line>> {
+    pub fn line1(&self) -> LinePrimitive {
         self.line1.clone()
     }
 
-    pub fn set_line1(&mut self, line1: Rc<RefCell<Line>>) {
-        self.line1 = line1;
+    pub fn set_line1(&mut self, line1: impl Into<LinePrimitive>) {
+        self.line1 = line1.into();
     }
 
-    pub fn line2(&self) -> Rc<RefCell<Line>> {
+    pub fn line2(&self) -> LinePrimitive {
         self.line2.clone()
     }
 
-    pub fn set_line2(&mut self, line2: Rc<RefCell<Line>>) {
-        self.line2 = line2;
+    pub fn set_line2(&mut self, line2: impl Into<LinePrimitive>) {
+        self.line2 = line2.into();
     }
 }
 
 impl ConstraintLike for PerpendicularLines {
     fn references(&self) -> Vec<PrimitiveCell> {
         vec![
-            PrimitiveCell::Line(self.line1.clone()),
-            PrimitiveCell::Line(self.line2.clone()),
+            self.line1.to_primitive_cell(),
+            self.line2.to_primitive_cell(),
         ]
     }
 
-    fn loss_value(&self) -> f64 {
-        let start1 = self.line1.borrow().start().borrow().data();
-        let end1 = self.line1.borrow().end().borrow().data();
-        let start2 = self.line2.borrow().start().borrow().data();
-        let end2 = self.line2.borrow().end().borrow().data();
-
-        let dir1 = (end1 - start1).normalize();
-        let dir2 = (end2 - start2).normalize();
+    fn residuals(&self) -> DVector<f64> {
+        let dir1 = self.line1.direction().normalize();
+        let dir2 = self.line2.direction().normalize();
         if !dir1.x.is_finite() || !dir1.y.is_finite() || !dir2.x.is_finite() || !dir2.y.is_finite()
         {
-            return 0.0;
+            return DVector::zeros(1);
         }
 
         let dot_product = dir1.dot(&dir2);
-        0.5 * dot_product * dot_product
+        DVector::from_row_slice(&[dot_product])
     }
 
-    fn update_gradient(&mut self) {
-        let start1 = self.line1.borrow().start().borrow().data();
-        let end1 = self.line1.borrow().end().borrow().data();
-        let start2 = self.line2.borrow().start().borrow().data();
-        let end2 = self.line2.borrow().end().borrow().data();
-
-        let dir1 = end1 - start1;
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let dir1 = self.line1.direction();
         let dir1_norm = dir1.normalize(); // dir1 / dir1.norm();
-        let dir2 = end2 - start2;
+        let dir2 = self.line2.direction();
         let dir2_norm = dir2.normalize();
         if !dir1_norm.x.is_finite()
             || !dir1_norm.y.is_finite()
@@ -85,10 +76,7 @@ impl ConstraintLike for PerpendicularLines {
             return;
         }
 
-        let dot_product = dir1_norm.dot(&dir2_norm);
-        let _loss = 0.5 * dot_product * dot_product;
-
-        let grad_from_dot_product = dot_product;
+        let grad_from_dot_product = weights[0];
         let grad_dot_product_from_dir1_norm = dir2_norm.transpose();
         let grad_dot_product_from_dir2_norm = dir1_norm.transpose();
         let grad_dir1_norm_from_dir1 =
@@ -96,25 +84,11 @@ impl ConstraintLike for PerpendicularLines {
         let grad_dir2_norm_from_dir2 =
             (Matrix2::identity() - dir2 * dir2.transpose() / dir2.norm_squared()) / dir2.norm();
 
-        let grad_start1 = self.line1.borrow().start_gradient();
-        let grad_end1 = self.line1.borrow().end_gradient();
-        let grad_start2 = self.line2.borrow().start_gradient();
-        let grad_end2 = self.line2.borrow().end_gradient();
-
-        self.line1.borrow_mut().add_to_gradient(
-            (grad_from_dot_product
-                * grad_dot_product_from_dir1_norm
-                * grad_dir1_norm_from_dir1
-                * (grad_end1 - grad_start1))
-                .as_view(),
+        self.line1.add_to_direction_gradient(
+            grad_from_dot_product * grad_dot_product_from_dir1_norm * grad_dir1_norm_from_dir1,
         );
-
-        self.line2.borrow_mut().add_to_gradient(
-            (grad_from_dot_product
-                * grad_dot_product_from_dir2_norm
-                * grad_dir2_norm_from_dir2
-                * (grad_end2 - grad_start2))
-                .as_view(),
+        self.line2.add_to_direction_gradient(
+            grad_from_dot_product * grad_dot_product_from_dir2_norm * grad_dir2_norm_from_dir2,
         );
     }
 
@@ -134,7 +108,7 @@ mod tests {
         constraints::{
             lines::perpendicular_lines::PerpendicularLines, ConstraintCell, ConstraintLike,
         },
-        primitives::{line::Line, point2::Point2, PrimitiveCell},
+        primitives::{line::Line, point2::Point2, ray::Ray, PrimitiveCell},
         sketch::Sketch,
         solvers::{gradient_based_solver::GradientBasedSolver, Solver},
     };
@@ -211,4 +185,47 @@ mod tests {
 
         assert!(constr1.as_ref().borrow().loss_value() < 0.01);
     }
+
+    #[test]
+    fn test_perpendicular_line_and_ray() {
+        let mut sketch = Sketch::new();
+
+        let line_start = Rc::new(RefCell::new(Point2::new(3.0, 4.0)));
+        let line_end = Rc::new(RefCell::new(Point2::new(5.0, 6.0)));
+        let line = Rc::new(RefCell::new(Line::new(
+            line_start.clone(),
+            line_end.clone(),
+        )));
+        let origin = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let ray = Rc::new(RefCell::new(Ray::new(origin.clone(), 0.2)));
+        for p in [&line_start, &line_end, &origin] {
+            sketch
+                .add_primitive(PrimitiveCell::Point2(p.clone()))
+                .unwrap();
+        }
+        sketch
+            .add_primitive(PrimitiveCell::Line(line.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Ray(ray.clone()))
+            .unwrap();
+
+        let constr1 = Rc::new(RefCell::new(PerpendicularLines::new(
+            line.clone(),
+            ray.clone(),
+        )));
+        sketch
+            .add_constraint(ConstraintCell::PerpendicularLines(constr1.clone()))
+            .unwrap();
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-6);
+        let solver = GradientBasedSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("line: {:?}", line.as_ref().borrow());
+        println!("ray: {:?}", ray.as_ref().borrow());
+
+        let line_dir = line_end.borrow().data() - line_start.borrow().data();
+        assert!(line_dir.normalize().dot(&ray.borrow().direction()).abs() < 1e-3);
+    }
 }
@@ -0,0 +1,183 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::{DVector, DVectorView, Vector2};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::ConstraintLike,
+    primitives::{point2::Point2, PrimitiveCell},
+};
+
+// This is a sketch constraint that makes any number of points lie on a common line. The loss is half
+// of the sum of the squared distances of the points to their best fitting line, so no point is
+// treated differently from the others.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct PointsCollinear {
+    points: Vec<Rc<RefCell<Point2>>>,
+}
+
+impl PointsCollinear {
+    pub fn new(points: Vec<Rc<RefCell<Point2>>>) -> Self {
+        Self { points }
+    }
+
+    pub fn points(&self) -> Vec<Rc<RefCell<Point2>>> {
+        self.points.clone()
+    }
+
+    pub fn set_points(&mut self, points: Vec<Rc<RefCell<Point2>>>) {
+        self.points = points;
+    }
+
+    // The mean of the points, the unit normal of the best fitting line through them and the gap
+    // between the two eigenvalues of their scatter matrix
+    fn best_fit(&self) -> Option<(Vector2<f64>, Vector2<f64>, f64)> {
+        if self.points.len() < 3 {
+            return None;
+        }
+
+        let data: Vec<Vector2<f64>> = self.points.iter().map(|p| p.borrow().data()).collect();
+        let mean = data.iter().sum::<Vector2<f64>>() / data.len() as f64;
+
+        // Entries of the scatter matrix [[a, b], [b, c]]
+        let (mut a, mut b, mut c) = (0.0, 0.0, 0.0);
+        for point in data.iter() {
+            let d = point - mean;
+            a += d.x * d.x;
+            b += d.x * d.y;
+            c += d.y * d.y;
+        }
+
+        // The normal is the eigenvector of the smallest eigenvalue
+        let half_gap = (0.25 * (a - c) * (a - c) + b * b).max(0.0).sqrt();
+        let smallest_eigenvalue = 0.5 * (a + c) - half_gap;
+        let candidate1 = Vector2::new(b, smallest_eigenvalue - a);
+        let candidate2 = Vector2::new(smallest_eigenvalue - c, b);
+        let normal = if candidate1.norm_squared() > candidate2.norm_squared() {
+            candidate1
+        } else {
+            candidate2
+        };
+        if normal.norm() < 1e-12 {
+            // All directions are equally good, e.g. when all points coincide
+            return Some((mean, Vector2::new(0.0, 1.0), 0.0));
+        }
+        Some((mean, normal.normalize(), 2.0 * half_gap))
+    }
+}
+
+impl ConstraintLike for PointsCollinear {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        self.points
+            .iter()
+            .map(|point| PrimitiveCell::Point2(point.clone()))
+            .collect()
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let Some((mean, normal, _)) = self.best_fit() else {
+            return DVector::zeros(self.points.len());
+        };
+
+        DVector::from_iterator(
+            self.points.len(),
+            self.points
+                .iter()
+                .map(|point| normal.dot(&(point.borrow().data() - mean))),
+        )
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let Some((mean, normal, gap)) = self.best_fit() else {
+            return;
+        };
+        let tangent = Vector2::new(-normal.y, normal.x);
+        let n = self.points.len() as f64;
+
+        // Each residual is the distance n * (p_i - mean) of a point to the best fitting line. Moving
+        // a point moves the mean and turns the normal, which is the eigenvector of the scatter
+        // matrix, by -t * (t * dC * n) / gap. Weighted by the residuals themselves, the two sums
+        // below vanish and only the distances to the fixed line remain.
+        let offsets: Vec<Vector2<f64>> = self
+            .points
+            .iter()
+            .map(|point| point.borrow().data() - mean)
+            .collect();
+        let weight_sum = weights.sum();
+        let weighted_tangential_sum = if gap < 1e-12 {
+            0.0
+        } else {
+            offsets
+                .iter()
+                .enumerate()
+                .map(|(i, offset)| weights[i] * tangent.dot(offset))
+                .sum::<f64>()
+                / gap
+        };
+
+        for (i, point) in self.points.iter().enumerate() {
+            let distance = normal.dot(&offsets[i]);
+            let along = tangent.dot(&offsets[i]);
+
+            let grad_from_point = (weights[i] - weight_sum / n) * normal.transpose()
+                - weighted_tangential_sum * (distance * tangent + along * normal).transpose();
+            let grad_point = point.borrow().point_gradient();
+
+            point
+                .borrow_mut()
+                .add_to_gradient((grad_from_point * grad_point).as_view());
+        }
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::PointsCollinear(self.clone())
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::{lines::points_collinear::PointsCollinear, ConstraintCell, ConstraintLike},
+        primitives::{point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    #[test]
+    fn test_points_collinear() {
+        let mut sketch = Sketch::new();
+
+        let points: Vec<_> = [(0.0, 0.0), (1.0, 0.4), (2.0, -0.3), (3.5, 1.2), (4.0, 0.1)]
+            .iter()
+            .map(|(x, y)| Rc::new(RefCell::new(Point2::new(*x, *y))))
+            .collect();
+        for point in points.iter() {
+            sketch
+                .add_primitive(PrimitiveCell::Point2(point.clone()))
+                .unwrap();
+        }
+
+        let constr1 = Rc::new(RefCell::new(PointsCollinear::new(points.clone())));
+        sketch
+            .add_constraint(ConstraintCell::PointsCollinear(constr1.clone()))
+            .unwrap();
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+        let solver = BFGSSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        for point in points.iter() {
+            println!("point: {:?}", point.as_ref().borrow());
+        }
+
+        assert!(constr1.borrow().loss_value() < 1e-10);
+    }
+}
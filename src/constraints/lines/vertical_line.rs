@@ -1,14 +1,12 @@
-use std::{cell::RefCell, rc::Rc};
-
-use nalgebra::SMatrix;
+use nalgebra::{DVector, DVectorView, SMatrix};
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "tsify")]
 use tsify::Tsify;
 
 use crate::{
-    constraints::ConstraintLike,
-    primitives::{line::Line, PrimitiveCell},
+    constraints::{lines::LinePrimitive, ConstraintLike},
+    primitives::PrimitiveCell,
 };
 
 // This is a sketch constraint that makes the end point of an arc coincident with a point.
@@ -16,51 +14,37 @@ use crate::{
 #[cfg_attr(feature = "tsify", derive(Tsify))]
 #[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
 pub struct VerticalLine {
-    line: Rc<RefCell<Line>>,
+    line: LinePrimitive,
 }
 
 impl VerticalLine {
-    pub fn new(line: Rc<RefCell<Line>>) -> Self {
-        Self { line }
+    pub fn new(line: impl Into<LinePrimitive>) -> Self {
+        Self { line: line.into() }
     }
 
-    pub fn line(&self) -> Rc<RefCell<Line>> {
+    pub fn line(&self) -> LinePrimitive {
         self.line.clone()
     }
 
-    pub fn set_line(&mut self, line: Rc<RefCell<Line>>) {
-        self.line = line;
+    pub fn set_line(&mut self, line: impl Into<LinePrimitive>) {
+        self.line = line.into();
     }
 }
 
 impl ConstraintLike for VerticalLine {
     fn references(&self) -> Vec<PrimitiveCell> {
-        vec![PrimitiveCell::Line(self.line.clone())]
+        vec![self.line.to_primitive_cell()]
     }
 
-    fn loss_value(&self) -> f64 {
-        let start = self.line.borrow().start().borrow().data();
-        let end = self.line.borrow().end().borrow().data();
-        let dx = end.x - start.x;
-        0.5 * dx * dx
+    fn residuals(&self) -> DVector<f64> {
+        let dx = self.line.direction().x;
+        DVector::from_row_slice(&[dx])
     }
 
-    fn update_gradient(&mut self) {
-        let start = self.line.borrow().start().borrow().data();
-        let end = self.line.borrow().end().borrow().data();
-        let dx = end.x - start.x;
-
-        let gradient_constraint = SMatrix::<f64, 1, 2>::from_row_slice(&[dx, 0.0]);
-
-        let grad_start = self.line.borrow().start_gradient();
-        let grad_end = self.line.borrow().end_gradient();
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let gradient_constraint = SMatrix::<f64, 1, 2>::from_row_slice(&[weights[0], 0.0]);
 
-        self.line
-            .borrow_mut()
-            .add_to_gradient((-gradient_constraint * grad_start).as_view());
-        self.line
-            .borrow_mut()
-            .add_to_gradient((gradient_constraint * grad_end).as_view());
+        self.line.add_to_direction_gradient(gradient_constraint);
     }
 
     fn get_type(&self) -> crate::constraints::Constraint {
@@ -75,7 +59,7 @@ mod tests {
 
     use crate::{
         constraints::{lines::vertical_line::VerticalLine, ConstraintCell},
-        primitives::{line::Line, point2::Point2, PrimitiveCell},
+        primitives::{line::Line, point2::Point2, ray::Ray, PrimitiveCell},
         sketch::Sketch,
         solvers::{gradient_based_solver::GradientBasedSolver, Solver},
     };
@@ -117,4 +101,31 @@ mod tests {
                 < 1e-6
         );
     }
+
+    #[test]
+    fn test_vertical_ray() {
+        let mut sketch = Sketch::new();
+
+        let origin = Rc::new(RefCell::new(Point2::new(3.0, 4.0)));
+        let ray = Rc::new(RefCell::new(Ray::new(origin.clone(), 1.2)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(origin.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Ray(ray.clone()))
+            .unwrap();
+
+        let constr1 = Rc::new(RefCell::new(VerticalLine::new(ray.clone())));
+        sketch
+            .add_constraint(ConstraintCell::VerticalLine(constr1.clone()))
+            .unwrap();
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-6);
+        let solver = GradientBasedSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("ray: {:?}", ray.as_ref().borrow());
+
+        assert!(ray.as_ref().borrow().direction().x.abs() < 1e-6);
+    }
 }
@@ -3,6 +3,7 @@ use std::fmt::Debug;
 use std::ptr;
 use std::rc::Rc;
 
+use nalgebra::{DVector, DVectorView};
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "tsify")]
@@ -11,16 +12,40 @@ use tsify::Tsify;
 use crate::primitives::PrimitiveCell;
 
 pub mod angle_between_points;
+pub mod arcs;
+pub mod bounds;
 pub mod coincident;
 pub mod distance;
 pub mod fix_point;
 pub mod lines;
+pub mod radius;
+pub mod symmetric_about_line;
+pub mod tangent;
 
 pub trait ConstraintLike: Debug {
     fn references(&self) -> Vec<PrimitiveCell>;
-    fn loss_value(&self) -> f64;
-    fn update_gradient(&mut self);
+    // The signed residuals of the constraint, which are all zero if it is satisfied
+    fn residuals(&self) -> DVector<f64>;
+    // Adds the gradients of the residuals, each multiplied by its weight, to the gradients of the
+    // referenced primitives. Weighting them by the residuals gives the gradient of the loss, a unit
+    // vector gives a single row of the jacobian.
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>);
     fn get_type(&self) -> Constraint;
+
+    // The energy of the constraint is half of its squared residuals
+    fn loss_value(&self) -> f64 {
+        0.5 * self.residuals().norm_squared()
+    }
+
+    fn update_gradient(&mut self) {
+        let residuals = self.residuals();
+        self.update_residual_gradient(residuals.as_view());
+    }
+
+    // Inequality constraints have a zero loss everywhere inside of their feasible region
+    fn is_inequality(&self) -> bool {
+        false
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
@@ -41,6 +66,46 @@ pub enum Constraint {
     VerticalLine(lines::vertical_line::VerticalLine),
     ParallelLines(lines::parallel_lines::ParallelLines),
     PerpendicularLines(lines::perpendicular_lines::PerpendicularLines),
+    LineCircleTangent(tangent::line_circle_tangent::LineCircleTangent),
+    LineArcTangent(tangent::line_arc_tangent::LineArcTangent),
+    CircleCircleTangent(tangent::circle_circle_tangent::CircleCircleTangent),
+    ArcArcTangent(tangent::arc_arc_tangent::ArcArcTangent),
+    ArcCircleTangent(tangent::arc_circle_tangent::ArcCircleTangent),
+    PointOnLine(coincident::point_on_line::PointOnLine),
+    PointOnLineSegment(coincident::point_on_line_segment::PointOnLineSegment),
+    PointOnCircle(coincident::point_on_circle::PointOnCircle),
+    PointOnArc(coincident::point_on_arc::PointOnArc),
+    DistanceBetweenPointAndLine(
+        distance::distance_between_point_and_line::DistanceBetweenPointAndLine,
+    ),
+    DistanceBetweenLines(distance::distance_between_lines::DistanceBetweenLines),
+    DistanceBetweenPointAndCircle(
+        distance::distance_between_point_and_circle::DistanceBetweenPointAndCircle,
+    ),
+    DistanceBetweenPointAndArc(
+        distance::distance_between_point_and_arc::DistanceBetweenPointAndArc,
+    ),
+    SymmetricPointsAboutLine(
+        symmetric_about_line::symmetric_points_about_line::SymmetricPointsAboutLine,
+    ),
+    SymmetricLinesAboutLine(
+        symmetric_about_line::symmetric_lines_about_line::SymmetricLinesAboutLine,
+    ),
+    SymmetricArcsAboutLine(symmetric_about_line::symmetric_arcs_about_line::SymmetricArcsAboutLine),
+    LineMidPointCoincident(coincident::line_mid_point_coincident::LineMidPointCoincident),
+    ArcMidPointCoincident(coincident::arc_mid_point_coincident::ArcMidPointCoincident),
+    FixedRadius(radius::fixed_radius::FixedRadius),
+    FixedDiameter(radius::fixed_diameter::FixedDiameter),
+    EqualRadius(radius::equal_radius::EqualRadius),
+    AngleBetweenLines(lines::angle_between_lines::AngleBetweenLines),
+    Collinear(lines::collinear::Collinear),
+    PointsCollinear(lines::points_collinear::PointsCollinear),
+    LineLength(lines::line_length::LineLength),
+    ArcLength(arcs::arc_length::ArcLength),
+    DistanceBounds(bounds::distance_bounds::DistanceBounds),
+    RadiusBounds(bounds::radius_bounds::RadiusBounds),
+    AngleBetweenLinesBounds(bounds::angle_between_lines_bounds::AngleBetweenLinesBounds),
+    EndpointArcEqualRadius(arcs::endpoint_arc_equal_radius::EndpointArcEqualRadius),
 }
 
 impl Constraint {
@@ -58,6 +123,36 @@ impl Constraint {
             Constraint::VerticalLine(c) => c,
             Constraint::ParallelLines(c) => c,
             Constraint::PerpendicularLines(c) => c,
+            Constraint::LineCircleTangent(c) => c,
+            Constraint::LineArcTangent(c) => c,
+            Constraint::CircleCircleTangent(c) => c,
+            Constraint::ArcArcTangent(c) => c,
+            Constraint::ArcCircleTangent(c) => c,
+            Constraint::PointOnLine(c) => c,
+            Constraint::PointOnLineSegment(c) => c,
+            Constraint::PointOnCircle(c) => c,
+            Constraint::PointOnArc(c) => c,
+            Constraint::DistanceBetweenPointAndLine(c) => c,
+            Constraint::DistanceBetweenLines(c) => c,
+            Constraint::DistanceBetweenPointAndCircle(c) => c,
+            Constraint::DistanceBetweenPointAndArc(c) => c,
+            Constraint::SymmetricPointsAboutLine(c) => c,
+            Constraint::SymmetricLinesAboutLine(c) => c,
+            Constraint::SymmetricArcsAboutLine(c) => c,
+            Constraint::LineMidPointCoincident(c) => c,
+            Constraint::ArcMidPointCoincident(c) => c,
+            Constraint::FixedRadius(c) => c,
+            Constraint::FixedDiameter(c) => c,
+            Constraint::EqualRadius(c) => c,
+            Constraint::AngleBetweenLines(c) => c,
+            Constraint::Collinear(c) => c,
+            Constraint::PointsCollinear(c) => c,
+            Constraint::LineLength(c) => c,
+            Constraint::ArcLength(c) => c,
+            Constraint::DistanceBounds(c) => c,
+            Constraint::RadiusBounds(c) => c,
+            Constraint::AngleBetweenLinesBounds(c) => c,
+            Constraint::EndpointArcEqualRadius(c) => c,
         }
     }
 }
@@ -86,10 +181,56 @@ pub enum ConstraintCell {
     VerticalLine(Rc<RefCell<lines::vertical_line::VerticalLine>>),
     ParallelLines(Rc<RefCell<lines::parallel_lines::ParallelLines>>),
     PerpendicularLines(Rc<RefCell<lines::perpendicular_lines::PerpendicularLines>>),
+    LineCircleTangent(Rc<RefCell<tangent::line_circle_tangent::LineCircleTangent>>),
+    LineArcTangent(Rc<RefCell<tangent::line_arc_tangent::LineArcTangent>>),
+    CircleCircleTangent(Rc<RefCell<tangent::circle_circle_tangent::CircleCircleTangent>>),
+    ArcArcTangent(Rc<RefCell<tangent::arc_arc_tangent::ArcArcTangent>>),
+    ArcCircleTangent(Rc<RefCell<tangent::arc_circle_tangent::ArcCircleTangent>>),
+    PointOnLine(Rc<RefCell<coincident::point_on_line::PointOnLine>>),
+    PointOnLineSegment(Rc<RefCell<coincident::point_on_line_segment::PointOnLineSegment>>),
+    PointOnCircle(Rc<RefCell<coincident::point_on_circle::PointOnCircle>>),
+    PointOnArc(Rc<RefCell<coincident::point_on_arc::PointOnArc>>),
+    DistanceBetweenPointAndLine(
+        Rc<RefCell<distance::distance_between_point_and_line::DistanceBetweenPointAndLine>>,
+    ),
+    DistanceBetweenLines(Rc<RefCell<distance::distance_between_lines::DistanceBetweenLines>>),
+    DistanceBetweenPointAndCircle(
+        Rc<RefCell<distance::distance_between_point_and_circle::DistanceBetweenPointAndCircle>>,
+    ),
+    DistanceBetweenPointAndArc(
+        Rc<RefCell<distance::distance_between_point_and_arc::DistanceBetweenPointAndArc>>,
+    ),
+    SymmetricPointsAboutLine(
+        Rc<RefCell<symmetric_about_line::symmetric_points_about_line::SymmetricPointsAboutLine>>,
+    ),
+    SymmetricLinesAboutLine(
+        Rc<RefCell<symmetric_about_line::symmetric_lines_about_line::SymmetricLinesAboutLine>>,
+    ),
+    SymmetricArcsAboutLine(
+        Rc<RefCell<symmetric_about_line::symmetric_arcs_about_line::SymmetricArcsAboutLine>>,
+    ),
+    LineMidPointCoincident(
+        Rc<RefCell<coincident::line_mid_point_coincident::LineMidPointCoincident>>,
+    ),
+    ArcMidPointCoincident(Rc<RefCell<coincident::arc_mid_point_coincident::ArcMidPointCoincident>>),
+    FixedRadius(Rc<RefCell<radius::fixed_radius::FixedRadius>>),
+    FixedDiameter(Rc<RefCell<radius::fixed_diameter::FixedDiameter>>),
+    EqualRadius(Rc<RefCell<radius::equal_radius::EqualRadius>>),
+    AngleBetweenLines(Rc<RefCell<lines::angle_between_lines::AngleBetweenLines>>),
+    Collinear(Rc<RefCell<lines::collinear::Collinear>>),
+    PointsCollinear(Rc<RefCell<lines::points_collinear::PointsCollinear>>),
+    LineLength(Rc<RefCell<lines::line_length::LineLength>>),
+    ArcLength(Rc<RefCell<arcs::arc_length::ArcLength>>),
+    DistanceBounds(Rc<RefCell<bounds::distance_bounds::DistanceBounds>>),
+    RadiusBounds(Rc<RefCell<bounds::radius_bounds::RadiusBounds>>),
+    AngleBetweenLinesBounds(
+        Rc<RefCell<bounds::angle_between_lines_bounds::AngleBetweenLinesBounds>>,
+    ),
+    EndpointArcEqualRadius(Rc<RefCell<arcs::endpoint_arc_equal_radius::EndpointArcEqualRadius>>),
 }
 
 impl ConstraintCell {
-    pub fn borrow(&self) -> Ref<dyn ConstraintLike> {
+    pub fn borrow(&self) -> Ref<'_, dyn ConstraintLike> {
         match self {
             ConstraintCell::AngleBetweenPoints(c) => c.borrow(),
             ConstraintCell::ArcEndPointCoincident(c) => c.borrow(),
@@ -103,10 +244,40 @@ impl ConstraintCell {
             ConstraintCell::VerticalLine(c) => c.borrow(),
             ConstraintCell::ParallelLines(c) => c.borrow(),
             ConstraintCell::PerpendicularLines(c) => c.borrow(),
+            ConstraintCell::LineCircleTangent(c) => c.borrow(),
+            ConstraintCell::LineArcTangent(c) => c.borrow(),
+            ConstraintCell::CircleCircleTangent(c) => c.borrow(),
+            ConstraintCell::ArcArcTangent(c) => c.borrow(),
+            ConstraintCell::ArcCircleTangent(c) => c.borrow(),
+            ConstraintCell::PointOnLine(c) => c.borrow(),
+            ConstraintCell::PointOnLineSegment(c) => c.borrow(),
+            ConstraintCell::PointOnCircle(c) => c.borrow(),
+            ConstraintCell::PointOnArc(c) => c.borrow(),
+            ConstraintCell::DistanceBetweenPointAndLine(c) => c.borrow(),
+            ConstraintCell::DistanceBetweenLines(c) => c.borrow(),
+            ConstraintCell::DistanceBetweenPointAndCircle(c) => c.borrow(),
+            ConstraintCell::DistanceBetweenPointAndArc(c) => c.borrow(),
+            ConstraintCell::SymmetricPointsAboutLine(c) => c.borrow(),
+            ConstraintCell::SymmetricLinesAboutLine(c) => c.borrow(),
+            ConstraintCell::SymmetricArcsAboutLine(c) => c.borrow(),
+            ConstraintCell::LineMidPointCoincident(c) => c.borrow(),
+            ConstraintCell::ArcMidPointCoincident(c) => c.borrow(),
+            ConstraintCell::FixedRadius(c) => c.borrow(),
+            ConstraintCell::FixedDiameter(c) => c.borrow(),
+            ConstraintCell::EqualRadius(c) => c.borrow(),
+            ConstraintCell::AngleBetweenLines(c) => c.borrow(),
+            ConstraintCell::Collinear(c) => c.borrow(),
+            ConstraintCell::PointsCollinear(c) => c.borrow(),
+            ConstraintCell::LineLength(c) => c.borrow(),
+            ConstraintCell::ArcLength(c) => c.borrow(),
+            ConstraintCell::DistanceBounds(c) => c.borrow(),
+            ConstraintCell::RadiusBounds(c) => c.borrow(),
+            ConstraintCell::AngleBetweenLinesBounds(c) => c.borrow(),
+            ConstraintCell::EndpointArcEqualRadius(c) => c.borrow(),
         }
     }
 
-    pub fn borrow_mut(&self) -> RefMut<dyn ConstraintLike> {
+    pub fn borrow_mut(&self) -> RefMut<'_, dyn ConstraintLike> {
         match self {
             ConstraintCell::AngleBetweenPoints(c) => c.borrow_mut(),
             ConstraintCell::ArcEndPointCoincident(c) => c.borrow_mut(),
@@ -120,6 +291,36 @@ impl ConstraintCell {
             ConstraintCell::VerticalLine(c) => c.borrow_mut(),
             ConstraintCell::ParallelLines(c) => c.borrow_mut(),
             ConstraintCell::PerpendicularLines(c) => c.borrow_mut(),
+            ConstraintCell::LineCircleTangent(c) => c.borrow_mut(),
+            ConstraintCell::LineArcTangent(c) => c.borrow_mut(),
+            ConstraintCell::CircleCircleTangent(c) => c.borrow_mut(),
+            ConstraintCell::ArcArcTangent(c) => c.borrow_mut(),
+            ConstraintCell::ArcCircleTangent(c) => c.borrow_mut(),
+            ConstraintCell::PointOnLine(c) => c.borrow_mut(),
+            ConstraintCell::PointOnLineSegment(c) => c.borrow_mut(),
+            ConstraintCell::PointOnCircle(c) => c.borrow_mut(),
+            ConstraintCell::PointOnArc(c) => c.borrow_mut(),
+            ConstraintCell::DistanceBetweenPointAndLine(c) => c.borrow_mut(),
+            ConstraintCell::DistanceBetweenLines(c) => c.borrow_mut(),
+            ConstraintCell::DistanceBetweenPointAndCircle(c) => c.borrow_mut(),
+            ConstraintCell::DistanceBetweenPointAndArc(c) => c.borrow_mut(),
+            ConstraintCell::SymmetricPointsAboutLine(c) => c.borrow_mut(),
+            ConstraintCell::SymmetricLinesAboutLine(c) => c.borrow_mut(),
+            ConstraintCell::SymmetricArcsAboutLine(c) => c.borrow_mut(),
+            ConstraintCell::LineMidPointCoincident(c) => c.borrow_mut(),
+            ConstraintCell::ArcMidPointCoincident(c) => c.borrow_mut(),
+            ConstraintCell::FixedRadius(c) => c.borrow_mut(),
+            ConstraintCell::FixedDiameter(c) => c.borrow_mut(),
+            ConstraintCell::EqualRadius(c) => c.borrow_mut(),
+            ConstraintCell::AngleBetweenLines(c) => c.borrow_mut(),
+            ConstraintCell::Collinear(c) => c.borrow_mut(),
+            ConstraintCell::PointsCollinear(c) => c.borrow_mut(),
+            ConstraintCell::LineLength(c) => c.borrow_mut(),
+            ConstraintCell::ArcLength(c) => c.borrow_mut(),
+            ConstraintCell::DistanceBounds(c) => c.borrow_mut(),
+            ConstraintCell::RadiusBounds(c) => c.borrow_mut(),
+            ConstraintCell::AngleBetweenLinesBounds(c) => c.borrow_mut(),
+            ConstraintCell::EndpointArcEqualRadius(c) => c.borrow_mut(),
         }
     }
 
@@ -137,6 +338,36 @@ impl ConstraintCell {
             ConstraintCell::VerticalLine(c) => c.as_ptr(),
             ConstraintCell::ParallelLines(c) => c.as_ptr(),
             ConstraintCell::PerpendicularLines(c) => c.as_ptr(),
+            ConstraintCell::LineCircleTangent(c) => c.as_ptr(),
+            ConstraintCell::LineArcTangent(c) => c.as_ptr(),
+            ConstraintCell::CircleCircleTangent(c) => c.as_ptr(),
+            ConstraintCell::ArcArcTangent(c) => c.as_ptr(),
+            ConstraintCell::ArcCircleTangent(c) => c.as_ptr(),
+            ConstraintCell::PointOnLine(c) => c.as_ptr(),
+            ConstraintCell::PointOnLineSegment(c) => c.as_ptr(),
+            ConstraintCell::PointOnCircle(c) => c.as_ptr(),
+            ConstraintCell::PointOnArc(c) => c.as_ptr(),
+            ConstraintCell::DistanceBetweenPointAndLine(c) => c.as_ptr(),
+            ConstraintCell::DistanceBetweenLines(c) => c.as_ptr(),
+            ConstraintCell::DistanceBetweenPointAndCircle(c) => c.as_ptr(),
+            ConstraintCell::DistanceBetweenPointAndArc(c) => c.as_ptr(),
+            ConstraintCell::SymmetricPointsAboutLine(c) => c.as_ptr(),
+            ConstraintCell::SymmetricLinesAboutLine(c) => c.as_ptr(),
+            ConstraintCell::SymmetricArcsAboutLine(c) => c.as_ptr(),
+            ConstraintCell::LineMidPointCoincident(c) => c.as_ptr(),
+            ConstraintCell::ArcMidPointCoincident(c) => c.as_ptr(),
+            ConstraintCell::FixedRadius(c) => c.as_ptr(),
+            ConstraintCell::FixedDiameter(c) => c.as_ptr(),
+            ConstraintCell::EqualRadius(c) => c.as_ptr(),
+            ConstraintCell::AngleBetweenLines(c) => c.as_ptr(),
+            ConstraintCell::Collinear(c) => c.as_ptr(),
+            ConstraintCell::PointsCollinear(c) => c.as_ptr(),
+            ConstraintCell::LineLength(c) => c.as_ptr(),
+            ConstraintCell::ArcLength(c) => c.as_ptr(),
+            ConstraintCell::DistanceBounds(c) => c.as_ptr(),
+            ConstraintCell::RadiusBounds(c) => c.as_ptr(),
+            ConstraintCell::AngleBetweenLinesBounds(c) => c.as_ptr(),
+            ConstraintCell::EndpointArcEqualRadius(c) => c.as_ptr(),
         }
     }
 }
@@ -0,0 +1,125 @@
+use nalgebra::{DVector, DVectorView};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::{radius::RadiusPrimitive, ConstraintLike},
+    primitives::PrimitiveCell,
+};
+
+// This is a sketch constraint that makes the radii of two circles or arcs equal.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct EqualRadius {
+    primitive1: RadiusPrimitive,
+    primitive2: RadiusPrimitive,
+}
+
+impl EqualRadius {
+    pub fn new(primitive1: RadiusPrimitive, primitive2: RadiusPrimitive) -> Self {
+        Self {
+            primitive1,
+            primitive2,
+        }
+    }
+
+    pub fn primitive1(&self) -> RadiusPrimitive {
+        self.primitive1.clone()
+    }
+
+    pub fn set_primitive1(&mut self, primitive1: RadiusPrimitive) {
+        self.primitive1 = primitive1;
+    }
+
+    pub fn primitive2(&self) -> RadiusPrimitive {
+        self.primitive2.clone()
+    }
+
+    pub fn set_primitive2(&mut self, primitive2: RadiusPrimitive) {
+        self.primitive2 = primitive2;
+    }
+}
+
+impl ConstraintLike for EqualRadius {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![
+            self.primitive1.to_primitive_cell(),
+            self.primitive2.to_primitive_cell(),
+        ]
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let difference = self.primitive1.radius() - self.primitive2.radius();
+        DVector::from_row_slice(&[difference])
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let grad_from_difference = weights[0];
+        self.primitive1.add_to_radius_gradient(grad_from_difference);
+        self.primitive2
+            .add_to_radius_gradient(-grad_from_difference);
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::EqualRadius(self.clone())
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::{
+            radius::{equal_radius::EqualRadius, RadiusPrimitive},
+            ConstraintCell, ConstraintLike,
+        },
+        primitives::{arc::Arc, circle::Circle, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    #[test]
+    fn test_equal_radius() {
+        let mut sketch = Sketch::new();
+
+        let center1 = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let center2 = Rc::new(RefCell::new(Point2::new(5.0, 0.0)));
+        let circle = Rc::new(RefCell::new(Circle::new(center1.clone(), 1.0)));
+        let arc = Rc::new(RefCell::new(Arc::new(center2.clone(), 3.0, true, 0.5, 2.5)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(center1.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Point2(center2.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Circle(circle.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Arc(arc.clone()))
+            .unwrap();
+
+        let constr1 = Rc::new(RefCell::new(EqualRadius::new(
+            RadiusPrimitive::Circle(circle.clone()),
+            RadiusPrimitive::Arc(arc.clone()),
+        )));
+        sketch
+            .add_constraint(ConstraintCell::EqualRadius(constr1.clone()))
+            .unwrap();
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+        let solver = BFGSSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("circle: {:?}", circle.as_ref().borrow());
+        println!("arc: {:?}", arc.as_ref().borrow());
+
+        assert!(constr1.borrow().loss_value() < 1e-10);
+        assert!((circle.as_ref().borrow().radius() - arc.as_ref().borrow().radius()).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,119 @@
+use nalgebra::{DVector, DVectorView};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::{radius::RadiusPrimitive, ConstraintLike},
+    primitives::PrimitiveCell,
+};
+
+// This is a sketch constraint that fixes the diameter of a circle or an arc.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct FixedDiameter {
+    primitive: RadiusPrimitive,
+
+    desired_diameter: f64,
+}
+
+impl FixedDiameter {
+    pub fn new(primitive: RadiusPrimitive, desired_diameter: f64) -> Self {
+        assert!(desired_diameter.is_finite());
+        Self {
+            primitive,
+            desired_diameter,
+        }
+    }
+
+    pub fn primitive(&self) -> RadiusPrimitive {
+        self.primitive.clone()
+    }
+
+    pub fn set_primitive(&mut self, primitive: RadiusPrimitive) {
+        self.primitive = primitive;
+    }
+
+    pub fn desired_diameter(&self) -> f64 {
+        self.desired_diameter
+    }
+
+    pub fn set_desired_diameter(&mut self, desired_diameter: f64) {
+        self.desired_diameter = desired_diameter;
+    }
+
+    pub fn current_diameter(&self) -> f64 {
+        2.0 * self.primitive.radius()
+    }
+}
+
+impl ConstraintLike for FixedDiameter {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![self.primitive.to_primitive_cell()]
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let err = self.current_diameter() - self.desired_diameter;
+        DVector::from_row_slice(&[err])
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let grad_from_err = weights[0];
+        let grad_err_from_radius = 2.0;
+        self.primitive
+            .add_to_radius_gradient(grad_from_err * grad_err_from_radius);
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::FixedDiameter(self.clone())
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::{
+            radius::{fixed_diameter::FixedDiameter, RadiusPrimitive},
+            ConstraintCell, ConstraintLike,
+        },
+        primitives::{circle::Circle, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    #[test]
+    fn test_fixed_diameter() {
+        let mut sketch = Sketch::new();
+
+        let center = Rc::new(RefCell::new(Point2::new(1.0, 2.0)));
+        let circle = Rc::new(RefCell::new(Circle::new(center.clone(), 1.0)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(center.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Circle(circle.clone()))
+            .unwrap();
+
+        let constr1 = Rc::new(RefCell::new(FixedDiameter::new(
+            RadiusPrimitive::Circle(circle.clone()),
+            5.0,
+        )));
+        sketch
+            .add_constraint(ConstraintCell::FixedDiameter(constr1.clone()))
+            .unwrap();
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+        let solver = BFGSSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("circle: {:?}", circle.as_ref().borrow());
+
+        assert!(constr1.borrow().loss_value() < 1e-10);
+        assert!((circle.as_ref().borrow().radius() - 2.5).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,144 @@
+use nalgebra::{DVector, DVectorView};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::{radius::RadiusPrimitive, ConstraintLike},
+    primitives::PrimitiveCell,
+};
+
+// This is a sketch constraint that fixes the radius of a circle or an arc.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct FixedRadius {
+    primitive: RadiusPrimitive,
+
+    desired_radius: f64,
+}
+
+impl FixedRadius {
+    pub fn new(primitive: RadiusPrimitive, desired_radius: f64) -> Self {
+        assert!(desired_radius.is_finite());
+        Self {
+            primitive,
+            desired_radius,
+        }
+    }
+
+    pub fn primitive(&self) -> RadiusPrimitive {
+        self.primitive.clone()
+    }
+
+    pub fn set_primitive(&mut self, primitive: RadiusPrimitive) {
+        self.primitive = primitive;
+    }
+
+    pub fn desired_radius(&self) -> f64 {
+        self.desired_radius
+    }
+
+    pub fn set_desired_radius(&mut self, desired_radius: f64) {
+        self.desired_radius = desired_radius;
+    }
+}
+
+impl ConstraintLike for FixedRadius {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![self.primitive.to_primitive_cell()]
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let err = self.primitive.radius() - self.desired_radius;
+        DVector::from_row_slice(&[err])
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let grad_from_err = weights[0];
+        self.primitive.add_to_radius_gradient(grad_from_err);
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::FixedRadius(self.clone())
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::{
+            radius::{fixed_radius::FixedRadius, RadiusPrimitive},
+            ConstraintCell, ConstraintLike,
+        },
+        primitives::{arc::Arc, circle::Circle, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    #[test]
+    fn test_fixed_radius() {
+        let mut sketch = Sketch::new();
+
+        let center = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let circle = Rc::new(RefCell::new(Circle::new(center.clone(), 1.0)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(center.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Circle(circle.clone()))
+            .unwrap();
+
+        let constr1 = Rc::new(RefCell::new(FixedRadius::new(
+            RadiusPrimitive::Circle(circle.clone()),
+            3.0,
+        )));
+        sketch
+            .add_constraint(ConstraintCell::FixedRadius(constr1.clone()))
+            .unwrap();
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+        let solver = BFGSSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("circle: {:?}", circle.as_ref().borrow());
+
+        assert!(constr1.borrow().loss_value() < 1e-10);
+        assert!((circle.as_ref().borrow().radius() - 3.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_fixed_radius_arc() {
+        let mut sketch = Sketch::new();
+
+        let center = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let arc = Rc::new(RefCell::new(Arc::new(center.clone(), 2.0, false, 0.0, 1.0)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(center.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Arc(arc.clone()))
+            .unwrap();
+
+        let constr1 = Rc::new(RefCell::new(FixedRadius::new(
+            RadiusPrimitive::Arc(arc.clone()),
+            0.5,
+        )));
+        sketch
+            .add_constraint(ConstraintCell::FixedRadius(constr1.clone()))
+            .unwrap();
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+        let solver = BFGSSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("arc: {:?}", arc.as_ref().borrow());
+
+        assert!(constr1.borrow().loss_value() < 1e-10);
+        assert!((arc.as_ref().borrow().radius() - 0.5).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,54 @@
+use std::{cell::RefCell, rc::Rc};
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::primitives::{arc::Arc, circle::Circle, PrimitiveCell};
+
+pub mod equal_radius;
+pub mod fixed_diameter;
+pub mod fixed_radius;
+
+// A primitive that has a radius, so it can be used by the radius constraints.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum RadiusPrimitive {
+    Circle(Rc<RefCell<Circle>>),
+    Arc(Rc<RefCell<Arc>>),
+}
+
+impl RadiusPrimitive {
+    pub fn radius(&self) -> f64 {
+        match self {
+            RadiusPrimitive::Circle(circle) => circle.borrow().radius(),
+            RadiusPrimitive::Arc(arc) => arc.borrow().radius(),
+        }
+    }
+
+    pub fn to_primitive_cell(&self) -> PrimitiveCell {
+        match self {
+            RadiusPrimitive::Circle(circle) => PrimitiveCell::Circle(circle.clone()),
+            RadiusPrimitive::Arc(arc) => PrimitiveCell::Arc(arc.clone()),
+        }
+    }
+
+    // Adds the derivative of the loss with respect to the radius to the gradient of the primitive
+    pub(crate) fn add_to_radius_gradient(&self, grad_from_radius: f64) {
+        match self {
+            RadiusPrimitive::Circle(circle) => {
+                let grad_radius = circle.borrow().radius_gradient();
+                circle
+                    .borrow_mut()
+                    .add_to_gradient((grad_from_radius * grad_radius).as_view());
+            }
+            RadiusPrimitive::Arc(arc) => {
+                let grad_radius = arc.borrow().radius_gradient();
+                arc.borrow_mut()
+                    .add_to_gradient((grad_from_radius * grad_radius).as_view());
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use nalgebra::{SMatrix, Vector2};
+
+pub mod symmetric_arcs_about_line;
+pub mod symmetric_lines_about_line;
+pub mod symmetric_points_about_line;
+
+// Residuals of two points being mirrored about the infinite line through axis_start and axis_end.
+// The midpoint of the two points has to lie on the axis and their connection has to be
+// perpendicular to it, so the residuals are the distance of the midpoint from the axis and the
+// projection of the connection onto it. Returns the residuals and the gradients of their weighted
+// sum w.r.t. point1, point2, axis_start and axis_end.
+pub(crate) fn mirrored_points_residuals(
+    point1: Vector2<f64>,
+    point2: Vector2<f64>,
+    axis_start: Vector2<f64>,
+    axis_end: Vector2<f64>,
+    weights: [f64; 2],
+) -> ([f64; 2], [SMatrix<f64, 1, 2>; 4]) {
+    let u = axis_end - axis_start;
+    let length = u.norm();
+    if length < 1e-6 {
+        return ([0.0; 2], [SMatrix::<f64, 1, 2>::zeros(); 4]);
+    }
+
+    let w = 0.5 * (point1 + point2) - axis_start;
+    let d = point2 - point1;
+
+    // Distance of the midpoint to the axis
+    let midpoint_offset = (u.x * w.y - u.y * w.x) / length;
+    // Projection of the connecting segment onto the axis
+    let projection = u.dot(&d) / length;
+
+    let grad_midpoint_offset_from_u = SMatrix::<f64, 1, 2>::from_row_slice(&[w.y, -w.x]) / length
+        - midpoint_offset / (length * length) * u.transpose();
+    let grad_midpoint_offset_from_w = SMatrix::<f64, 1, 2>::from_row_slice(&[-u.y, u.x]) / length;
+    let grad_projection_from_u =
+        d.transpose() / length - projection / (length * length) * u.transpose();
+    let grad_projection_from_d = u.transpose() / length;
+
+    let [grad_from_midpoint_offset, grad_from_projection] = weights;
+    let grad_from_u = grad_from_midpoint_offset * grad_midpoint_offset_from_u
+        + grad_from_projection * grad_projection_from_u;
+    let grad_from_w = grad_from_midpoint_offset * grad_midpoint_offset_from_w;
+    let grad_from_d = grad_from_projection * grad_projection_from_d;
+
+    let grad_point1 = 0.5 * grad_from_w - grad_from_d;
+    let grad_point2 = 0.5 * grad_from_w + grad_from_d;
+    let grad_axis_start = -grad_from_u - grad_from_w;
+    let grad_axis_end = grad_from_u;
+
+    (
+        [midpoint_offset, projection],
+        [grad_point1, grad_point2, grad_axis_start, grad_axis_end],
+    )
+}
@@ -0,0 +1,238 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::{DVector, DVectorView};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::ConstraintLike,
+    primitives::{arc::Arc, line::Line, PrimitiveCell},
+};
+
+use super::mirrored_points_residuals;
+
+// This is a sketch constraint that makes two arcs symmetric about the infinite extension of a line.
+// The centers are mirror images of each other, and so are the end points. Mirroring flips the
+// orientation, so for arcs with the same direction the start point of one arc is mirrored onto the
+// end point of the other.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct SymmetricArcsAboutLine {
+    arc1: Rc<RefCell<Arc>>,
+    arc2: Rc<RefCell<Arc>>,
+    axis: Rc<RefCell<Line>>,
+}
+
+impl SymmetricArcsAboutLine {
+    pub fn new(arc1: Rc<RefCell<Arc>>, arc2: Rc<RefCell<Arc>>, axis: Rc<RefCell<Line>>) -> Self {
+        Self { arc1, arc2, axis }
+    }
+
+    pub fn arc1(&self) -> Rc<RefCell<Arc>> {
+        self.arc1.clone()
+    }
+
+    pub fn set_arc1(&mut self, arc1: Rc<RefCell<Arc>>) {
+        self.arc1 = arc1;
+    }
+
+    pub fn arc2(&self) -> Rc<RefCell<Arc>> {
+        self.arc2.clone()
+    }
+
+    pub fn set_arc2(&mut self, arc2: Rc<RefCell<Arc>>) {
+        self.arc2 = arc2;
+    }
+
+    pub fn axis(&self) -> Rc<RefCell<Line>> {
+        self.axis.clone()
+    }
+
+    pub fn set_axis(&mut self, axis: Rc<RefCell<Line>>) {
+        self.axis = axis;
+    }
+
+    fn same_direction(&self) -> bool {
+        self.arc1.borrow().clockwise() == self.arc2.borrow().clockwise()
+    }
+}
+
+impl ConstraintLike for SymmetricArcsAboutLine {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![
+            PrimitiveCell::Arc(self.arc1.clone()),
+            PrimitiveCell::Arc(self.arc2.clone()),
+            PrimitiveCell::Line(self.axis.clone()),
+        ]
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let arc1 = self.arc1.borrow();
+        let arc2 = self.arc2.borrow();
+        let axis_start = self.axis.borrow().start().borrow().data();
+        let axis_end = self.axis.borrow().end().borrow().data();
+
+        let (mirror_of_start1, mirror_of_end1) = if self.same_direction() {
+            (arc2.end_point(), arc2.start_point())
+        } else {
+            (arc2.start_point(), arc2.end_point())
+        };
+
+        let pairs = [
+            (arc1.center().borrow().data(), arc2.center().borrow().data()),
+            (arc1.start_point(), mirror_of_start1),
+            (arc1.end_point(), mirror_of_end1),
+        ];
+        DVector::from_iterator(
+            6,
+            pairs.into_iter().flat_map(|(point1, point2)| {
+                mirrored_points_residuals(point1, point2, axis_start, axis_end, [0.0; 2]).0
+            }),
+        )
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let axis_start = self.axis.borrow().start().borrow().data();
+        let axis_end = self.axis.borrow().end().borrow().data();
+
+        // Pairs of (point on arc1, its gradient, point on arc2, its gradient)
+        let pairs = {
+            let arc1 = self.arc1.borrow();
+            let arc2 = self.arc2.borrow();
+            let (mirror_of_start1, mirror_of_end1) = if self.same_direction() {
+                (
+                    (arc2.end_point(), arc2.end_point_gradient()),
+                    (arc2.start_point(), arc2.start_point_gradient()),
+                )
+            } else {
+                (
+                    (arc2.start_point(), arc2.start_point_gradient()),
+                    (arc2.end_point(), arc2.end_point_gradient()),
+                )
+            };
+            [
+                (
+                    (arc1.center().borrow().data(), arc1.center_gradient()),
+                    (arc2.center().borrow().data(), arc2.center_gradient()),
+                ),
+                (
+                    (arc1.start_point(), arc1.start_point_gradient()),
+                    mirror_of_start1,
+                ),
+                (
+                    (arc1.end_point(), arc1.end_point_gradient()),
+                    mirror_of_end1,
+                ),
+            ]
+        };
+
+        let grad_axis_start = self.axis.borrow().start_gradient();
+        let grad_axis_end = self.axis.borrow().end_gradient();
+
+        for (i, ((point1, grad_point1), (point2, grad_point2))) in pairs.into_iter().enumerate() {
+            let (_, [grad_from_point1, grad_from_point2, grad_from_axis_start, grad_from_axis_end]) =
+                mirrored_points_residuals(
+                    point1,
+                    point2,
+                    axis_start,
+                    axis_end,
+                    [weights[2 * i], weights[2 * i + 1]],
+                );
+
+            self.arc1
+                .borrow_mut()
+                .add_to_gradient((grad_from_point1 * grad_point1).as_view());
+            self.arc2
+                .borrow_mut()
+                .add_to_gradient((grad_from_point2 * grad_point2).as_view());
+            self.axis.borrow_mut().add_to_gradient(
+                (grad_from_axis_start * grad_axis_start + grad_from_axis_end * grad_axis_end)
+                    .as_view(),
+            );
+        }
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::SymmetricArcsAboutLine(self.clone())
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::{
+            symmetric_about_line::symmetric_arcs_about_line::SymmetricArcsAboutLine,
+            ConstraintCell, ConstraintLike,
+        },
+        primitives::{arc::Arc, line::Line, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    #[test]
+    fn test_symmetric_arcs_about_line() {
+        let mut sketch = Sketch::new();
+
+        let axis_start = Rc::new(RefCell::new(Point2::new(0.0, -1.0)));
+        let axis_end = Rc::new(RefCell::new(Point2::new(0.0, 1.0)));
+        let center1 = Rc::new(RefCell::new(Point2::new(1.0, 0.2)));
+        let center2 = Rc::new(RefCell::new(Point2::new(-1.5, -0.2)));
+        for point in [&axis_start, &axis_end, &center1, &center2] {
+            sketch
+                .add_primitive(PrimitiveCell::Point2(point.clone()))
+                .unwrap();
+        }
+        let axis = Rc::new(RefCell::new(Line::new(
+            axis_start.clone(),
+            axis_end.clone(),
+        )));
+        let arc1 = Rc::new(RefCell::new(Arc::new(
+            center1.clone(),
+            1.0,
+            false,
+            0.0,
+            1.5,
+        )));
+        let arc2 = Rc::new(RefCell::new(Arc::new(
+            center2.clone(),
+            0.8,
+            false,
+            1.5,
+            3.0,
+        )));
+        sketch
+            .add_primitive(PrimitiveCell::Line(axis.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Arc(arc1.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Arc(arc2.clone()))
+            .unwrap();
+
+        let constr1 = Rc::new(RefCell::new(SymmetricArcsAboutLine::new(
+            arc1.clone(),
+            arc2.clone(),
+            axis.clone(),
+        )));
+        sketch
+            .add_constraint(ConstraintCell::SymmetricArcsAboutLine(constr1.clone()))
+            .unwrap();
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+        let solver = BFGSSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("arc1: {:?}", arc1.as_ref().borrow());
+        println!("arc2: {:?}", arc2.as_ref().borrow());
+
+        assert!(constr1.borrow().loss_value() < 1e-8);
+        assert!((arc1.borrow().radius() - arc2.borrow().radius()).abs() < 1e-4);
+    }
+}
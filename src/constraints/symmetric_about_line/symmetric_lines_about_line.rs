@@ -0,0 +1,206 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::{DVector, DVectorView};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::ConstraintLike,
+    primitives::{line::Line, PrimitiveCell},
+};
+
+use super::mirrored_points_residuals;
+
+// This is a sketch constraint that makes two lines symmetric about the infinite extension of a
+// third line. The start points and the end points of both lines are mirror images of each other.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct SymmetricLinesAboutLine {
+    line1: Rc<RefCell<Line>>,
+    line2: Rc<RefCell<Line>>,
+    axis: Rc<RefCell<Line>>,
+}
+
+impl SymmetricLinesAboutLine {
+    pub fn new(
+        line1: Rc<RefCell<Line>>,
+        line2: Rc<RefCell<Line>>,
+        axis: Rc<RefCell<Line>>,
+    ) -> Self {
+        Self { line1, line2, axis }
+    }
+
+    pub fn line1(&self) -> Rc<RefCell<Line>> {
+        self.line1.clone()
+    }
+
+    pub fn set_line1(&mut self, line1: Rc<RefCell<Line>>) {
+        self.line1 = line1;
+    }
+
+    pub fn line2(&self) -> Rc<RefCell<Line>> {
+        self.line2.clone()
+    }
+
+    pub fn set_line2(&mut self, line2: Rc<RefCell<Line>>) {
+        self.line2 = line2;
+    }
+
+    pub fn axis(&self) -> Rc<RefCell<Line>> {
+        self.axis.clone()
+    }
+
+    pub fn set_axis(&mut self, axis: Rc<RefCell<Line>>) {
+        self.axis = axis;
+    }
+}
+
+impl ConstraintLike for SymmetricLinesAboutLine {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![
+            PrimitiveCell::Line(self.line1.clone()),
+            PrimitiveCell::Line(self.line2.clone()),
+            PrimitiveCell::Line(self.axis.clone()),
+        ]
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let start1 = self.line1.borrow().start().borrow().data();
+        let end1 = self.line1.borrow().end().borrow().data();
+        let start2 = self.line2.borrow().start().borrow().data();
+        let end2 = self.line2.borrow().end().borrow().data();
+        let axis_start = self.axis.borrow().start().borrow().data();
+        let axis_end = self.axis.borrow().end().borrow().data();
+
+        let (start_residuals, _) =
+            mirrored_points_residuals(start1, start2, axis_start, axis_end, [0.0; 2]);
+        let (end_residuals, _) =
+            mirrored_points_residuals(end1, end2, axis_start, axis_end, [0.0; 2]);
+        DVector::from_row_slice(&[
+            start_residuals[0],
+            start_residuals[1],
+            end_residuals[0],
+            end_residuals[1],
+        ])
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let start1 = self.line1.borrow().start().borrow().data();
+        let end1 = self.line1.borrow().end().borrow().data();
+        let start2 = self.line2.borrow().start().borrow().data();
+        let end2 = self.line2.borrow().end().borrow().data();
+        let axis_start = self.axis.borrow().start().borrow().data();
+        let axis_end = self.axis.borrow().end().borrow().data();
+
+        let (
+            _,
+            [grad_from_start1, grad_from_start2, grad_start_from_axis_start, grad_start_from_axis_end],
+        ) = mirrored_points_residuals(
+            start1,
+            start2,
+            axis_start,
+            axis_end,
+            [weights[0], weights[1]],
+        );
+        let (_, [grad_from_end1, grad_from_end2, grad_end_from_axis_start, grad_end_from_axis_end]) =
+            mirrored_points_residuals(end1, end2, axis_start, axis_end, [weights[2], weights[3]]);
+
+        let grad_start1 = self.line1.borrow().start_gradient();
+        let grad_end1 = self.line1.borrow().end_gradient();
+        let grad_start2 = self.line2.borrow().start_gradient();
+        let grad_end2 = self.line2.borrow().end_gradient();
+        let grad_axis_start = self.axis.borrow().start_gradient();
+        let grad_axis_end = self.axis.borrow().end_gradient();
+
+        self.line1.borrow_mut().add_to_gradient(
+            (grad_from_start1 * grad_start1 + grad_from_end1 * grad_end1).as_view(),
+        );
+        self.line2.borrow_mut().add_to_gradient(
+            (grad_from_start2 * grad_start2 + grad_from_end2 * grad_end2).as_view(),
+        );
+        self.axis.borrow_mut().add_to_gradient(
+            ((grad_start_from_axis_start + grad_end_from_axis_start) * grad_axis_start
+                + (grad_start_from_axis_end + grad_end_from_axis_end) * grad_axis_end)
+                .as_view(),
+        );
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::SymmetricLinesAboutLine(self.clone())
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::{
+            symmetric_about_line::symmetric_lines_about_line::SymmetricLinesAboutLine,
+            ConstraintCell, ConstraintLike,
+        },
+        primitives::{line::Line, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    #[test]
+    fn test_symmetric_lines_about_line() {
+        let mut sketch = Sketch::new();
+
+        let points = [
+            (0.0, -1.0),
+            (0.1, 2.0),
+            (1.0, 0.0),
+            (2.0, 1.0),
+            (-0.5, 0.3),
+            (-1.5, 1.2),
+        ]
+        .map(|(x, y)| Rc::new(RefCell::new(Point2::new(x, y))));
+        for point in points.iter() {
+            sketch
+                .add_primitive(PrimitiveCell::Point2(point.clone()))
+                .unwrap();
+        }
+        let axis = Rc::new(RefCell::new(Line::new(
+            points[0].clone(),
+            points[1].clone(),
+        )));
+        let line1 = Rc::new(RefCell::new(Line::new(
+            points[2].clone(),
+            points[3].clone(),
+        )));
+        let line2 = Rc::new(RefCell::new(Line::new(
+            points[4].clone(),
+            points[5].clone(),
+        )));
+        for line in [&axis, &line1, &line2] {
+            sketch
+                .add_primitive(PrimitiveCell::Line(line.clone()))
+                .unwrap();
+        }
+
+        let constr1 = Rc::new(RefCell::new(SymmetricLinesAboutLine::new(
+            line1.clone(),
+            line2.clone(),
+            axis.clone(),
+        )));
+        sketch
+            .add_constraint(ConstraintCell::SymmetricLinesAboutLine(constr1.clone()))
+            .unwrap();
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+        let solver = BFGSSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("axis: {:?}", axis.as_ref().borrow());
+        println!("line1: {:?}", line1.as_ref().borrow());
+        println!("line2: {:?}", line2.as_ref().borrow());
+
+        assert!(constr1.borrow().loss_value() < 1e-8);
+    }
+}
@@ -0,0 +1,192 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::{DVector, DVectorView};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::ConstraintLike,
+    primitives::{line::Line, point2::Point2, PrimitiveCell},
+};
+
+use super::mirrored_points_residuals;
+
+// This is a sketch constraint that makes two points symmetric about the infinite extension of a line.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct SymmetricPointsAboutLine {
+    point1: Rc<RefCell<Point2>>,
+    point2: Rc<RefCell<Point2>>,
+    axis: Rc<RefCell<Line>>,
+}
+
+impl SymmetricPointsAboutLine {
+    pub fn new(
+        point1: Rc<RefCell<Point2>>,
+        point2: Rc<RefCell<Point2>>,
+        axis: Rc<RefCell<Line>>,
+    ) -> Self {
+        Self {
+            point1,
+            point2,
+            axis,
+        }
+    }
+
+    pub fn point1(&self) -> Rc<RefCell<Point2>> {
+        self.point1.clone()
+    }
+
+    pub fn set_point1(&mut self, point1: Rc<RefCell<Point2>>) {
+        self.point1 = point1;
+    }
+
+    pub fn point2(&self) -> Rc<RefCell<Point2>> {
+        self.point2.clone()
+    }
+
+    pub fn set_point2(&mut self, point2: Rc<RefCell<Point2>>) {
+        self.point2 = point2;
+    }
+
+    pub fn axis(&self) -> Rc<RefCell<Line>> {
+        self.axis.clone()
+    }
+
+    pub fn set_axis(&mut self, axis: Rc<RefCell<Line>>) {
+        self.axis = axis;
+    }
+}
+
+impl ConstraintLike for SymmetricPointsAboutLine {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![
+            PrimitiveCell::Point2(self.point1.clone()),
+            PrimitiveCell::Point2(self.point2.clone()),
+            PrimitiveCell::Line(self.axis.clone()),
+        ]
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let point1 = self.point1.borrow().data();
+        let point2 = self.point2.borrow().data();
+        let axis_start = self.axis.borrow().start().borrow().data();
+        let axis_end = self.axis.borrow().end().borrow().data();
+
+        let (residuals, _) =
+            mirrored_points_residuals(point1, point2, axis_start, axis_end, [0.0; 2]);
+        DVector::from_row_slice(&residuals)
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let point1 = self.point1.borrow().data();
+        let point2 = self.point2.borrow().data();
+        let axis_start = self.axis.borrow().start().borrow().data();
+        let axis_end = self.axis.borrow().end().borrow().data();
+
+        let (_, [grad_from_point1, grad_from_point2, grad_from_axis_start, grad_from_axis_end]) =
+            mirrored_points_residuals(
+                point1,
+                point2,
+                axis_start,
+                axis_end,
+                [weights[0], weights[1]],
+            );
+
+        let grad_point1 = self.point1.borrow().point_gradient();
+        let grad_point2 = self.point2.borrow().point_gradient();
+        let grad_axis_start = self.axis.borrow().start_gradient();
+        let grad_axis_end = self.axis.borrow().end_gradient();
+
+        self.point1
+            .borrow_mut()
+            .add_to_gradient((grad_from_point1 * grad_point1).as_view());
+        self.point2
+            .borrow_mut()
+            .add_to_gradient((grad_from_point2 * grad_point2).as_view());
+        self.axis.borrow_mut().add_to_gradient(
+            (grad_from_axis_start * grad_axis_start + grad_from_axis_end * grad_axis_end).as_view(),
+        );
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::SymmetricPointsAboutLine(self.clone())
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use nalgebra::Vector2;
+
+    use crate::{
+        constraints::{
+            fix_point::FixPoint,
+            symmetric_about_line::symmetric_points_about_line::SymmetricPointsAboutLine,
+            ConstraintCell, ConstraintLike,
+        },
+        primitives::{line::Line, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    #[test]
+    fn test_symmetric_points_about_line() {
+        let mut sketch = Sketch::new();
+
+        let axis_start = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let axis_end = Rc::new(RefCell::new(Point2::new(1.0, 1.0)));
+        let axis = Rc::new(RefCell::new(Line::new(
+            axis_start.clone(),
+            axis_end.clone(),
+        )));
+        let point1 = Rc::new(RefCell::new(Point2::new(2.0, 0.0)));
+        let point2 = Rc::new(RefCell::new(Point2::new(0.5, 1.5)));
+        for point in [&axis_start, &axis_end, &point1, &point2] {
+            sketch
+                .add_primitive(PrimitiveCell::Point2(point.clone()))
+                .unwrap();
+        }
+        sketch
+            .add_primitive(PrimitiveCell::Line(axis.clone()))
+            .unwrap();
+
+        let constr1 = Rc::new(RefCell::new(SymmetricPointsAboutLine::new(
+            point1.clone(),
+            point2.clone(),
+            axis.clone(),
+        )));
+        sketch
+            .add_constraint(ConstraintCell::SymmetricPointsAboutLine(constr1.clone()))
+            .unwrap();
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+
+        // Fix everything but the second point, so the mirror image is well defined
+        for (point, position) in [
+            (&axis_start, Vector2::new(0.0, 0.0)),
+            (&axis_end, Vector2::new(1.0, 1.0)),
+            (&point1, Vector2::new(2.0, 0.0)),
+        ] {
+            sketch
+                .add_constraint(ConstraintCell::FixPoint(Rc::new(RefCell::new(
+                    FixPoint::new(point.clone(), position),
+                ))))
+                .unwrap();
+        }
+
+        let solver = BFGSSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("point1: {:?}", point1.as_ref().borrow());
+        println!("point2: {:?}", point2.as_ref().borrow());
+
+        assert!(constr1.borrow().loss_value() < 1e-8);
+        assert!((point2.as_ref().borrow().data() - Vector2::new(0.0, 2.0)).norm() < 1e-4);
+    }
+}
@@ -0,0 +1,189 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::{DVector, DVectorView};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::ConstraintLike,
+    primitives::{arc::Arc, PrimitiveCell},
+};
+
+// This is a sketch constraint that makes two arcs tangent to each other. External tangency
+// requires the distance between the centers to equal the sum of the radii, internal tangency
+// requires it to equal the difference of the radii.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct ArcArcTangent {
+    arc1: Rc<RefCell<Arc>>,
+    arc2: Rc<RefCell<Arc>>,
+
+    internal: bool,
+}
+
+impl ArcArcTangent {
+    pub fn new(arc1: Rc<RefCell<Arc>>, arc2: Rc<RefCell<Arc>>, internal: bool) -> Self {
+        Self {
+            arc1,
+            arc2,
+            internal,
+        }
+    }
+
+    pub fn arc1(&self) -> Rc<RefCell<Arc>> {
+        self.arc1.clone()
+    }
+
+    pub fn set_arc1(&mut self, arc1: Rc<RefCell<Arc>>) {
+        self.arc1 = arc1;
+    }
+
+    pub fn arc2(&self) -> Rc<RefCell<Arc>> {
+        self.arc2.clone()
+    }
+
+    pub fn set_arc2(&mut self, arc2: Rc<RefCell<Arc>>) {
+        self.arc2 = arc2;
+    }
+
+    pub fn internal(&self) -> bool {
+        self.internal
+    }
+
+    pub fn set_internal(&mut self, internal: bool) {
+        self.internal = internal;
+    }
+}
+
+impl ConstraintLike for ArcArcTangent {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![
+            PrimitiveCell::Arc(self.arc1.clone()),
+            PrimitiveCell::Arc(self.arc2.clone()),
+        ]
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let center1 = self.arc1.borrow().center().borrow().data();
+        let center2 = self.arc2.borrow().center().borrow().data();
+        let radius1 = self.arc1.borrow().radius();
+        let radius2 = self.arc2.borrow().radius();
+
+        let distance = (center2 - center1).norm();
+        let desired_distance = if self.internal {
+            (radius1 - radius2).abs()
+        } else {
+            radius1 + radius2
+        };
+
+        let difference = distance - desired_distance;
+        DVector::from_row_slice(&[difference])
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let center1 = self.arc1.borrow().center().borrow().data();
+        let center2 = self.arc2.borrow().center().borrow().data();
+        let radius1 = self.arc1.borrow().radius();
+        let radius2 = self.arc2.borrow().radius();
+
+        let d = center2 - center1;
+        let distance = d.norm();
+        if distance < 1e-6 {
+            return;
+        }
+
+        let grad_from_difference = weights[0];
+        let grad_distance_from_d = d.transpose() / distance;
+        let (grad_desired_from_radius1, grad_desired_from_radius2) = if self.internal {
+            let sign = (radius1 - radius2).signum();
+            (sign, -sign)
+        } else {
+            (1.0, 1.0)
+        };
+
+        let grad_center1 = self.arc1.borrow().center_gradient();
+        let grad_radius1 = self.arc1.borrow().radius_gradient();
+        let grad_center2 = self.arc2.borrow().center_gradient();
+        let grad_radius2 = self.arc2.borrow().radius_gradient();
+
+        self.arc1.borrow_mut().add_to_gradient(
+            (grad_from_difference
+                * (-grad_distance_from_d * grad_center1
+                    - grad_desired_from_radius1 * grad_radius1))
+                .as_view(),
+        );
+        self.arc2.borrow_mut().add_to_gradient(
+            (grad_from_difference
+                * (grad_distance_from_d * grad_center2 - grad_desired_from_radius2 * grad_radius2))
+                .as_view(),
+        );
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::ArcArcTangent(self.clone())
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::{tangent::arc_arc_tangent::ArcArcTangent, ConstraintCell, ConstraintLike},
+        primitives::{arc::Arc, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    #[test]
+    fn test_arc_arc_tangent() {
+        for internal in [false, true] {
+            let mut sketch = Sketch::new();
+
+            let center1 = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+            let arc1 = Rc::new(RefCell::new(Arc::new(
+                center1.clone(),
+                2.0,
+                false,
+                0.0,
+                1.0,
+            )));
+            let center2 = Rc::new(RefCell::new(Point2::new(1.5, 0.5)));
+            let arc2 = Rc::new(RefCell::new(Arc::new(center2.clone(), 0.7, true, 2.0, 0.5)));
+            sketch
+                .add_primitive(PrimitiveCell::Point2(center1.clone()))
+                .unwrap();
+            sketch
+                .add_primitive(PrimitiveCell::Arc(arc1.clone()))
+                .unwrap();
+            sketch
+                .add_primitive(PrimitiveCell::Point2(center2.clone()))
+                .unwrap();
+            sketch
+                .add_primitive(PrimitiveCell::Arc(arc2.clone()))
+                .unwrap();
+
+            let constr1 = Rc::new(RefCell::new(ArcArcTangent::new(
+                arc1.clone(),
+                arc2.clone(),
+                internal,
+            )));
+            sketch
+                .add_constraint(ConstraintCell::ArcArcTangent(constr1.clone()))
+                .unwrap();
+
+            sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+            let solver = BFGSSolver::new();
+            solver.solve(&mut sketch).unwrap();
+
+            println!("arc1: {:?}", arc1.as_ref().borrow());
+            println!("arc2: {:?}", arc2.as_ref().borrow());
+
+            assert!(constr1.as_ref().borrow().loss_value() < 1e-8);
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::{DVector, DVectorView, SMatrix};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use crate::{
+    constraints::ConstraintLike,
+    primitives::{arc::Arc, line::Line, PrimitiveCell},
+};
+
+// This is a sketch constraint that makes a line tangent to an arc, i.e. the distance between the
+// infinite line and the center of the arc equals the radius.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct LineArcTangent {
+    line: Rc<RefCell<Line>>,
+    arc: Rc<RefCell<Arc>>,
+}
+
+impl LineArcTangent {
+    pub fn new(line: Rc<RefCell<Line>>, arc: Rc<RefCell<Arc>>) -> Self {
+        Self { line, arc }
+    }
+
+    pub fn line(&self) -> Rc<RefCell<Line>> {
+        self.line.clone()
+    }
+
+    pub fn set_line(&mut self, line: Rc<RefCell<Line>>) {
+        self.line = line;
+    }
+
+    pub fn arc(&self) -> Rc<RefCell<Arc>> {
+        self.arc.clone()
+    }
+
+    pub fn set_arc(&mut self, arc: Rc<RefCell<Arc>>) {
+        self.arc = arc;
+    }
+}
+
+impl ConstraintLike for LineArcTangent {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![
+            PrimitiveCell::Line(self.line.clone()),
+            PrimitiveCell::Arc(self.arc.clone()),
+        ]
+    }
+
+    fn residuals(&self) -> DVector<f64> {
+        let start = self.line.borrow().start().borrow().data();
+        let end = self.line.borrow().end().borrow().data();
+        let center = self.arc.borrow().center().borrow().data();
+        let radius = self.arc.borrow().radius();
+
+        let u = end - start;
+        let w = center - start;
+        let length = u.norm();
+        if length < 1e-6 {
+            return DVector::zeros(1);
+        }
+
+        let cross_product = u.x * w.y - u.y * w.x;
+        let distance = cross_product.abs() / length;
+        let difference = distance - radius;
+        DVector::from_row_slice(&[difference])
+    }
+
+    fn update_residual_gradient(&mut self, weights: DVectorView<f64>) {
+        let start = self.line.borrow().start().borrow().data();
+        let end = self.line.borrow().end().borrow().data();
+        let center = self.arc.borrow().center().borrow().data();
+
+        let u = end - start;
+        let w = center - start;
+        let length = u.norm();
+        if length < 1e-6 {
+            return;
+        }
+
+        let cross_product = u.x * w.y - u.y * w.x;
+
+        let grad_from_difference = weights[0];
+        let sign = cross_product.signum();
+        let grad_cross_product_from_u = SMatrix::<f64, 1, 2>::from_row_slice(&[w.y, -w.x]);
+        let grad_cross_product_from_w = SMatrix::<f64, 1, 2>::from_row_slice(&[-u.y, u.x]);
+        let grad_distance_from_u = sign * grad_cross_product_from_u / length
+            - cross_product.abs() / (length * length * length) * u.transpose();
+        let grad_distance_from_w = sign * grad_cross_product_from_w / length;
+
+        let grad_start = self.line.borrow().start_gradient();
+        let grad_end = self.line.borrow().end_gradient();
+        let grad_center = self.arc.borrow().center_gradient();
+        let grad_radius = self.arc.borrow().radius_gradient();
+
+        self.line.borrow_mut().add_to_gradient(
+            (grad_from_difference
+                * (grad_distance_from_u * (grad_end - grad_start)
+                    - grad_distance_from_w * grad_start))
+                .as_view(),
+        );
+        self.arc.borrow_mut().add_to_gradient(
+            (grad_from_difference * (grad_distance_from_w * grad_center - grad_radius)).as_view(),
+        );
+    }
+
+    fn get_type(&self) -> crate::constraints::Constraint {
+        crate::constraints::Constraint::LineArcTangent(self.clone())
+    }
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        constraints::{tangent::line_arc_tangent::LineArcTangent, ConstraintCell, ConstraintLike},
+        primitives::{arc::Arc, line::Line, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    #[test]
+    fn test_line_arc_tangent() {
+        let mut sketch = Sketch::new();
+
+        let line_start = Rc::new(RefCell::new(Point2::new(-2.0, 1.5)));
+        let line_end = Rc::new(RefCell::new(Point2::new(2.0, 2.0)));
+        let line = Rc::new(RefCell::new(Line::new(
+            line_start.clone(),
+            line_end.clone(),
+        )));
+        let center = Rc::new(RefCell::new(Point2::new(0.2, -0.1)));
+        let arc = Rc::new(RefCell::new(Arc::new(
+            center.clone(),
+            1.0,
+            false,
+            0.0,
+            std::f64::consts::PI,
+        )));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(line_start.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Point2(line_end.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Line(line.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Point2(center.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Arc(arc.clone()))
+            .unwrap();
+
+        let constr1 = Rc::new(RefCell::new(LineArcTangent::new(line.clone(), arc.clone())));
+        sketch
+            .add_constraint(ConstraintCell::LineArcTangent(constr1.clone()))
+            .unwrap();
+
+        sketch.check_gradients(1e-6, constr1.clone(), 1e-5);
+        let solver = BFGSSolver::new();
+        solver.solve(&mut sketch).unwrap();
+
+        println!("line: {:?}", line.as_ref().borrow());
+        println!("arc: {:?}", arc.as_ref().borrow());
+
+        assert!(constr1.as_ref().borrow().loss_value() < 1e-8);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod arc_arc_tangent;
+pub mod arc_circle_tangent;
+pub mod circle_circle_tangent;
+pub mod line_arc_tangent;
+pub mod line_circle_tangent;
@@ -111,12 +111,15 @@ pub fn find_faces(sketch: &Sketch) -> (Vec<Face>, Vec<Segment>) {
 
 pub fn find_rings(sketch: &Sketch) -> (Vec<Ring>, Vec<Segment>) {
     let init_segments: Vec<Segment> = sketch
-        .primitives()
+        .non_construction_primitives()
         .values()
         .filter_map(|p| match p {
             // We don't consider circles - we'll just add them to the rings directly (right?)
             PrimitiveCell::Line(l) => Some(Segment::Line(l.borrow().clone())),
             PrimitiveCell::Arc(a) => Some(Segment::Arc(a.borrow().clone())),
+            PrimitiveCell::EllipticalArc(a) => Some(Segment::EllipticalArc(a.borrow().clone())),
+            PrimitiveCell::CubicBezier(b) => Some(Segment::CubicBezier(b.borrow().clone())),
+            PrimitiveCell::EndpointArc(a) => Some(Segment::EndpointArc(a.borrow().clone())),
             _ => None,
         })
         .collect();
@@ -178,12 +181,13 @@ pub fn find_rings(sketch: &Sketch) -> (Vec<Ring>, Vec<Segment>) {
         all_rings.push(Ring::Segments(ring_segments));
     }
 
-    // Circles are rings too
+    // Circles and ellipses are rings too
     let circles = sketch
-        .primitives()
+        .non_construction_primitives()
         .values()
         .filter_map(|s| match s {
             PrimitiveCell::Circle(c) => Some(Ring::Circle(c.borrow().clone())),
+            PrimitiveCell::Ellipse(e) => Some(Ring::Ellipse(e.borrow().clone())),
             _ => None,
         })
         .collect::<Vec<_>>();
@@ -259,6 +263,11 @@ pub fn angle_difference(mut a0: f64, mut a1: f64) -> f64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::primitives::circle::Circle;
+    use crate::primitives::cubic_bezier::CubicBezier;
+    use crate::primitives::ellipse::Ellipse;
+    use crate::primitives::elliptical_arc::EllipticalArc;
+    use crate::primitives::endpoint_arc::EndpointArc;
     use crate::primitives::line::Line;
     use crate::primitives::point2::Point2;
     use crate::primitives::PrimitiveCell;
@@ -334,7 +343,7 @@ mod tests {
         let point_e = Rc::new(RefCell::new(Point2::new(2.0, 0.0)));
         let point_f = Rc::new(RefCell::new(Point2::new(3.0, 0.0)));
 
-        for pt in vec![&point_a, &point_b, &point_c, &point_d, &point_e, &point_f] {
+        for pt in [&point_a, &point_b, &point_c, &point_d, &point_e, &point_f] {
             sketch
                 .add_primitive(PrimitiveCell::Point2(pt.clone()))
                 .unwrap();
@@ -395,4 +404,179 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn test_find_rings_ellipses() {
+        let mut sketch = Sketch::new();
+        let center = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(center.clone()))
+            .unwrap();
+
+        // A full ellipse is a ring on its own
+        let ellipse = Rc::new(RefCell::new(Ellipse::new(center.clone(), 2.0, 1.0, 0.3)));
+        sketch
+            .add_primitive(PrimitiveCell::Ellipse(ellipse.clone()))
+            .unwrap();
+
+        // A quarter of an ellipse closed by two lines through its center
+        let arc = Rc::new(RefCell::new(EllipticalArc::new(
+            center.clone(),
+            4.0,
+            3.0,
+            0.0,
+            false,
+            0.0,
+            PI / 2.0,
+        )));
+        sketch
+            .add_primitive(PrimitiveCell::EllipticalArc(arc.clone()))
+            .unwrap();
+        // Segments are only connected if their end points match exactly
+        let start = arc.borrow().start_point();
+        let end = arc.borrow().end_point();
+        let point_a = Rc::new(RefCell::new(Point2::new(start.x, start.y)));
+        let point_b = Rc::new(RefCell::new(Point2::new(end.x, end.y)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(point_a.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Point2(point_b.clone()))
+            .unwrap();
+        for (start, end) in [(point_b, center.clone()), (center.clone(), point_a)] {
+            let line = Rc::new(RefCell::new(Line::new(start, end)));
+            sketch.add_primitive(PrimitiveCell::Line(line)).unwrap();
+        }
+
+        let (rings, unused_segments) = find_rings(&sketch);
+        assert_eq!(rings.len(), 2);
+        assert!(unused_segments.is_empty());
+        assert!(rings.contains(&Ring::Ellipse(ellipse.borrow().clone())));
+        assert!(rings
+            .iter()
+            .any(|r| matches!(r, Ring::Segments(segments) if segments.len() == 3)));
+    }
+
+    #[test]
+    fn test_find_rings_cubic_bezier() {
+        let mut sketch = Sketch::new();
+        let point_a = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let point_b = Rc::new(RefCell::new(Point2::new(2.0, 0.0)));
+        let control_1 = Rc::new(RefCell::new(Point2::new(2.5, 1.0)));
+        let control_2 = Rc::new(RefCell::new(Point2::new(0.5, 2.0)));
+
+        for pt in [&point_a, &point_b, &control_1, &control_2] {
+            sketch
+                .add_primitive(PrimitiveCell::Point2(pt.clone()))
+                .unwrap();
+        }
+
+        // A line closed by a curve bulging upwards
+        let line = Rc::new(RefCell::new(Line::new(point_a.clone(), point_b.clone())));
+        let bezier = Rc::new(RefCell::new(CubicBezier::new(
+            point_b.clone(),
+            control_1.clone(),
+            control_2.clone(),
+            point_a.clone(),
+        )));
+        sketch.add_primitive(PrimitiveCell::Line(line)).unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::CubicBezier(bezier.clone()))
+            .unwrap();
+
+        let (rings, unused_segments) = find_rings(&sketch);
+        assert!(unused_segments.is_empty());
+        assert!(rings.iter().any(|r| match r {
+            Ring::Segments(segments) => {
+                segments.contains(&Segment::CubicBezier(bezier.borrow().clone()))
+            }
+            _ => false,
+        }));
+    }
+
+    #[test]
+    fn test_find_rings_endpoint_arc() {
+        let mut sketch = Sketch::new();
+        let point_a = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let point_b = Rc::new(RefCell::new(Point2::new(2.0, 0.0)));
+        let point_c = Rc::new(RefCell::new(Point2::new(2.0, 2.0)));
+        let point_d = Rc::new(RefCell::new(Point2::new(0.0, 2.0)));
+        let center = Rc::new(RefCell::new(Point2::new(1.0, 2.0)));
+
+        for pt in [&point_a, &point_b, &point_c, &point_d, &center] {
+            sketch
+                .add_primitive(PrimitiveCell::Point2(pt.clone()))
+                .unwrap();
+        }
+
+        // A square whose top edge is replaced by an arc sharing the corner points
+        for (start, end) in [
+            (point_d.clone(), point_a.clone()),
+            (point_a.clone(), point_b.clone()),
+            (point_b.clone(), point_c.clone()),
+        ] {
+            let line = Rc::new(RefCell::new(Line::new(start, end)));
+            sketch.add_primitive(PrimitiveCell::Line(line)).unwrap();
+        }
+        let arc = Rc::new(RefCell::new(EndpointArc::new(
+            point_c.clone(),
+            point_d.clone(),
+            center.clone(),
+            false,
+        )));
+        sketch
+            .add_primitive(PrimitiveCell::EndpointArc(arc.clone()))
+            .unwrap();
+
+        let (rings, unused_segments) = find_rings(&sketch);
+        assert!(unused_segments.is_empty());
+        assert!(rings.iter().any(|r| match r {
+            Ring::Segments(segments) => {
+                segments.len() == 4
+                    && segments.contains(&Segment::EndpointArc(arc.borrow().clone()))
+            }
+            _ => false,
+        }));
+    }
+
+    #[test]
+    fn test_find_rings_ignores_construction() {
+        let mut sketch = Sketch::new();
+        let point_a = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let point_b = Rc::new(RefCell::new(Point2::new(1.0, 0.0)));
+        let point_c = Rc::new(RefCell::new(Point2::new(1.0, 1.0)));
+
+        for pt in [&point_a, &point_b, &point_c] {
+            sketch
+                .add_primitive(PrimitiveCell::Point2(pt.clone()))
+                .unwrap();
+        }
+
+        for (start, end) in [
+            (point_a.clone(), point_b.clone()),
+            (point_b.clone(), point_c.clone()),
+            (point_c.clone(), point_a.clone()),
+        ] {
+            let line = Rc::new(RefCell::new(Line::new(start, end)));
+            sketch.add_primitive(PrimitiveCell::Line(line)).unwrap();
+        }
+
+        // A helper circle and a helper line which would otherwise split the triangle
+        let circle = Rc::new(RefCell::new(Circle::new(point_a.clone(), 0.1)));
+        let circle_id = sketch
+            .add_primitive_with_construction(PrimitiveCell::Circle(circle), true)
+            .unwrap();
+        let helper = Rc::new(RefCell::new(Line::new(point_a.clone(), point_c.clone())));
+        sketch
+            .add_primitive_with_construction(PrimitiveCell::Line(helper), true)
+            .unwrap();
+
+        let (rings, unused_segments) = find_rings(&sketch);
+        assert_eq!(rings.len(), 1);
+        assert!(unused_segments.is_empty());
+
+        sketch.set_construction(circle_id, false).unwrap();
+        let (rings, _) = find_rings(&sketch);
+        assert_eq!(rings.len(), 2);
+    }
 }
@@ -1,12 +1,18 @@
 use nalgebra::Vector2;
 use serde::{Deserialize, Serialize};
 
-use crate::primitives::{arc::Arc, line::Line};
+use crate::primitives::{
+    arc::Arc, cubic_bezier::CubicBezier, elliptical_arc::EllipticalArc, endpoint_arc::EndpointArc,
+    line::Line,
+};
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub enum Segment {
     Line(Line),
     Arc(Arc),
+    EllipticalArc(EllipticalArc),
+    CubicBezier(CubicBezier),
+    EndpointArc(EndpointArc),
 }
 
 impl Segment {
@@ -14,6 +20,9 @@ impl Segment {
         match self {
             Segment::Line(line) => line.start().borrow().data(),
             Segment::Arc(arc) => arc.start_point(),
+            Segment::EllipticalArc(arc) => arc.start_point(),
+            Segment::CubicBezier(bezier) => bezier.start().borrow().data(),
+            Segment::EndpointArc(arc) => arc.start_point(),
         }
     }
 
@@ -21,6 +30,23 @@ impl Segment {
         match self {
             Segment::Line(line) => line.end().borrow().data(),
             Segment::Arc(arc) => arc.end_point(),
+            Segment::EllipticalArc(arc) => arc.end_point(),
+            Segment::CubicBezier(bezier) => bezier.end().borrow().data(),
+            Segment::EndpointArc(arc) => arc.end_point(),
+        }
+    }
+
+    // The start point followed by points along the segment, without the end point. Curves that are
+    // not arcs are sampled so that rings made of them still enclose an area.
+    pub fn polyline(&self) -> Vec<Vector2<f64>> {
+        match self {
+            Segment::CubicBezier(bezier) => {
+                let num_pts = 16;
+                (0..num_pts)
+                    .map(|i| bezier.point_at(i as f64 / num_pts as f64))
+                    .collect()
+            }
+            _ => vec![self.get_start()],
         }
     }
 
@@ -30,6 +56,9 @@ impl Segment {
                 Segment::Line(Line::new(line.end().clone(), line.start().clone()))
             }
             Segment::Arc(arc) => Segment::Arc(arc.reverse()),
+            Segment::EllipticalArc(arc) => Segment::EllipticalArc(arc.reverse()),
+            Segment::CubicBezier(bezier) => Segment::CubicBezier(bezier.reverse()),
+            Segment::EndpointArc(arc) => Segment::EndpointArc(arc.reverse()),
         }
     }
 
@@ -51,6 +80,17 @@ impl Segment {
                 (end.y - start.y).atan2(end.x - start.x)
             }
             Segment::Arc(arc) => arc.start_angle(),
+            Segment::EndpointArc(arc) => arc.start_angle(),
+            Segment::EllipticalArc(arc) => {
+                let center = arc.center().borrow().data();
+                let start = arc.start_point();
+                (start.y - center.y).atan2(start.x - center.x)
+            }
+            Segment::CubicBezier(bezier) => {
+                // Like for lines, this is the direction the curve leaves its start point in
+                let tangent = bezier.start_tangent();
+                tangent.y.atan2(tangent.x)
+            }
         }
     }
 
@@ -58,6 +98,16 @@ impl Segment {
         match self {
             Segment::Line(_line) => self.start_angle(),
             Segment::Arc(arc) => arc.end_angle(),
+            Segment::EndpointArc(arc) => arc.end_angle(),
+            Segment::EllipticalArc(arc) => {
+                let center = arc.center().borrow().data();
+                let end = arc.end_point();
+                (end.y - center.y).atan2(end.x - center.x)
+            }
+            Segment::CubicBezier(bezier) => {
+                let tangent = bezier.end_tangent();
+                tangent.y.atan2(tangent.x)
+            }
         }
     }
 
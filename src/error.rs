@@ -13,4 +13,34 @@ pub enum ISOTopeError {
     ConstraintAlreadyInSketch,
     #[error("No such constraint in the sketch")]
     ConstraintNotFound,
+    #[error("The point is not a corner between two lines or a line and an arc")]
+    NotACorner,
+    #[error("The fillet or chamfer does not fit on the primitives at the corner")]
+    CornerOperationDoesNotFit,
+    #[error("The operation is not supported for this primitive")]
+    UnsupportedPrimitive,
+    #[error("The position is not on the primitive")]
+    PositionNotOnPrimitive,
+    #[error("No intersection found to extend the primitive to")]
+    NoIntersection,
+    #[error("The primitive is degenerate, e.g. a line of zero length")]
+    DegeneratePrimitive,
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(&'static str),
+    #[error("The bounds must be finite, ordered and at least one of them has to be set")]
+    InvalidBounds,
+}
+
+#[derive(Error, Debug)]
+pub enum SolverError {
+    #[error("The gradient contains non-finite values")]
+    NonFiniteGradient,
+    #[error("The search direction contains non-finite values")]
+    NonFiniteSearchDirection,
+    #[error("The search direction is not a descent direction")]
+    NotDescentDirection,
+    #[error("The line search could not find a suitable step size")]
+    LineSearchFailed,
+    #[error("The linear system of the solver step could not be solved: {0}")]
+    LinearSystemFailed(&'static str),
 }
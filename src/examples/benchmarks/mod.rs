@@ -18,8 +18,8 @@ pub trait Benchmark {
 mod tests {
     use crate::solvers::{
         bfgs_solver::BFGSSolver, gauss_newton_solver::GaussNewtonSolver,
-        gradient_based_solver::GradientBasedSolver, levenberg_marquardt::LevenbergMarquardtSolver,
-        Solver,
+        gradient_based_solver::GradientBasedSolver, lbfgs_solver::LBFGSSolver,
+        levenberg_marquardt::LevenbergMarquardtSolver, Solver,
     };
     use std::ops::DerefMut;
 
@@ -53,6 +53,7 @@ mod tests {
                 Box::new(LevenbergMarquardtSolver::new()),
             ),
             ("BFGSSolver              ", Box::new(BFGSSolver::new())),
+            ("LBFGSSolver             ", Box::new(LBFGSSolver::new())),
         ];
 
         for (benchmark_name, benchmark) in benchmarks.iter() {
@@ -96,6 +97,7 @@ mod tests {
         let solvers: Vec<(&str, Box<dyn Solver>)> = vec![
             ("GradientBasedSolver", Box::new(GradientBasedSolver::new())),
             ("BFGSSolver         ", Box::new(BFGSSolver::new())),
+            ("LBFGSSolver        ", Box::new(LBFGSSolver::new())),
         ];
 
         for (benchmark_name, benchmark) in benchmarks.iter() {
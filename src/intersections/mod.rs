@@ -1,10 +1,344 @@
+use std::f64::consts::TAU;
+
 use nalgebra::Vector2;
 
+use crate::error::ISOTopeError;
 use crate::primitives::Primitive;
 
-pub fn intersections(_a: Primitive, _b: Primitive) -> Vec<Vector2<f64>> {
+// Intersections closer than this to the end of a primitive still count
+const TOLERANCE: f64 = 1e-9;
+
+// Returns an error if one of the primitives is a curve that intersections are not supported for,
+// e.g. an ellipse, so it can be told apart from two primitives that do not intersect
+pub fn intersections(a: Primitive, b: Primitive) -> Result<Vec<Vector2<f64>>, ISOTopeError> {
     // We only deal with point intersections for now
     // In case two lines do overlap in parallel, we just treat the whole sketch as invalid
     // In case of two arcs or circles with same radius in same position, we treat the whole sketch as invalid, etc.
-    todo!("Find all intersections between two primitives")
+    match (Curve::from_primitive(&a)?, Curve::from_primitive(&b)?) {
+        (Some(a), Some(b)) => Ok(a.intersections(&b)),
+        _ => Ok(vec![]),
+    }
+}
+
+// The part of a circle covered by an arc
+#[derive(Debug, Clone, Copy)]
+struct AngleRange {
+    start_angle: f64,
+    sweep_angle: f64,
+    clockwise: bool,
+}
+
+impl AngleRange {
+    fn contains(&self, angle: f64) -> bool {
+        let offset = if self.clockwise {
+            (self.start_angle - angle).rem_euclid(TAU)
+        } else {
+            (angle - self.start_angle).rem_euclid(TAU)
+        };
+        offset <= self.sweep_angle + TOLERANCE || offset >= TAU - TOLERANCE
+    }
+}
+
+// The primitives that can intersect, as straight or circular curves
+#[derive(Debug, Clone, Copy)]
+enum Curve {
+    // The points base + t * direction for t between t_min and t_max, the direction has unit length
+    Straight {
+        base: Vector2<f64>,
+        direction: Vector2<f64>,
+        t_min: f64,
+        t_max: f64,
+    },
+    // The points on the circle, optionally limited to a range of angles around the center
+    Circular {
+        center: Vector2<f64>,
+        radius: f64,
+        range: Option<AngleRange>,
+    },
+}
+
+impl Curve {
+    // Points and degenerate lines have no intersections and give None
+    fn from_primitive(primitive: &Primitive) -> Result<Option<Self>, ISOTopeError> {
+        let curve = match primitive {
+            Primitive::Point2(_) => None,
+            Primitive::Line(line) => {
+                let start = line.start().borrow().data();
+                let end = line.end().borrow().data();
+                let length = (end - start).norm();
+                if length < 1e-12 {
+                    return Ok(None);
+                }
+                Some(Curve::Straight {
+                    base: start,
+                    direction: (end - start) / length,
+                    t_min: 0.0,
+                    t_max: length,
+                })
+            }
+            Primitive::InfiniteLine(line) => Some(Curve::Straight {
+                base: line.point().borrow().data(),
+                direction: line.direction(),
+                t_min: f64::NEG_INFINITY,
+                t_max: f64::INFINITY,
+            }),
+            Primitive::Ray(ray) => Some(Curve::Straight {
+                base: ray.origin().borrow().data(),
+                direction: ray.direction(),
+                t_min: 0.0,
+                t_max: f64::INFINITY,
+            }),
+            Primitive::Circle(circle) => Some(Curve::Circular {
+                center: circle.center().borrow().data(),
+                radius: circle.radius(),
+                range: None,
+            }),
+            Primitive::Arc(arc) => Some(Curve::Circular {
+                center: arc.center().borrow().data(),
+                radius: arc.radius(),
+                range: Some(AngleRange {
+                    start_angle: arc.start_angle(),
+                    sweep_angle: arc.sweep_angle(),
+                    clockwise: arc.clockwise(),
+                }),
+            }),
+            Primitive::EndpointArc(arc) => Some(Curve::Circular {
+                center: arc.center().borrow().data(),
+                radius: arc.radius(),
+                range: Some(AngleRange {
+                    start_angle: arc.start_angle(),
+                    sweep_angle: arc.sweep_angle(),
+                    clockwise: arc.clockwise(),
+                }),
+            }),
+            Primitive::Ellipse(_)
+            | Primitive::EllipticalArc(_)
+            | Primitive::BSpline(_)
+            | Primitive::CubicBezier(_) => return Err(ISOTopeError::UnsupportedPrimitive),
+        };
+        Ok(curve)
+    }
+
+    fn contains(&self, point: &Vector2<f64>) -> bool {
+        match self {
+            Curve::Straight {
+                base,
+                direction,
+                t_min,
+                t_max,
+            } => {
+                let t = direction.dot(&(point - base));
+                t >= t_min - TOLERANCE && t <= t_max + TOLERANCE
+            }
+            Curve::Circular { center, range, .. } => range.is_none_or(|range| {
+                let d = point - center;
+                range.contains(d.y.atan2(d.x))
+            }),
+        }
+    }
+
+    fn intersections(&self, other: &Curve) -> Vec<Vector2<f64>> {
+        let candidates = match (self, other) {
+            (
+                Curve::Straight {
+                    base: base1,
+                    direction: direction1,
+                    ..
+                },
+                Curve::Straight {
+                    base: base2,
+                    direction: direction2,
+                    ..
+                },
+            ) => {
+                let cross = direction1.x * direction2.y - direction1.y * direction2.x;
+                if cross.abs() < 1e-12 {
+                    // Parallel lines don't have a point intersection
+                    return vec![];
+                }
+                let offset = base2 - base1;
+                let t = (offset.x * direction2.y - offset.y * direction2.x) / cross;
+                vec![base1 + t * direction1]
+            }
+            (
+                Curve::Straight {
+                    base, direction, ..
+                },
+                Curve::Circular { center, radius, .. },
+            )
+            | (
+                Curve::Circular { center, radius, .. },
+                Curve::Straight {
+                    base, direction, ..
+                },
+            ) => line_circle_intersections(*base, *direction, *center, *radius),
+            (
+                Curve::Circular {
+                    center: center1,
+                    radius: radius1,
+                    ..
+                },
+                Curve::Circular {
+                    center: center2,
+                    radius: radius2,
+                    ..
+                },
+            ) => circle_circle_intersections(*center1, *radius1, *center2, *radius2),
+        };
+
+        candidates
+            .into_iter()
+            .filter(|point| self.contains(point) && other.contains(point))
+            .collect()
+    }
+}
+
+// The points where the line through the base point in the unit direction meets the circle, a
+// tangent line touches it in a single point
+pub(crate) fn line_circle_intersections(
+    base: Vector2<f64>,
+    direction: Vector2<f64>,
+    center: Vector2<f64>,
+    radius: f64,
+) -> Vec<Vector2<f64>> {
+    // Solve |base + t * direction - center| = radius for t
+    let offset = base - center;
+    let b = direction.dot(&offset);
+    let c = offset.norm_squared() - radius * radius;
+    let discriminant = b * b - c;
+    if discriminant < 0.0 {
+        return vec![];
+    }
+    let root = discriminant.sqrt();
+    if root < 1e-12 {
+        return vec![base - b * direction];
+    }
+    vec![
+        base + (-b - root) * direction,
+        base + (-b + root) * direction,
+    ]
+}
+
+// The points where two circles meet, circles touching each other meet in a single point and
+// identical circles are treated as not intersecting
+pub(crate) fn circle_circle_intersections(
+    center1: Vector2<f64>,
+    radius1: f64,
+    center2: Vector2<f64>,
+    radius2: f64,
+) -> Vec<Vector2<f64>> {
+    let offset = center2 - center1;
+    let distance = offset.norm();
+    if distance < 1e-12 || distance > radius1 + radius2 || distance < (radius1 - radius2).abs() {
+        return vec![];
+    }
+    // Distance from the first center to the chord through the intersections
+    let a = (radius1 * radius1 - radius2 * radius2 + distance * distance) / (2.0 * distance);
+    let h = (radius1 * radius1 - a * a).max(0.0).sqrt();
+    let along = offset / distance;
+    let across = Vector2::new(-along.y, along.x);
+    if h < 1e-12 {
+        return vec![center1 + a * along];
+    }
+    vec![
+        center1 + a * along + h * across,
+        center1 + a * along - h * across,
+    ]
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, f64::consts::PI, rc::Rc};
+
+    use nalgebra::Vector2;
+
+    use crate::{
+        error::ISOTopeError,
+        primitives::{
+            arc::Arc, circle::Circle, ellipse::Ellipse, infinite_line::InfiniteLine, line::Line,
+            point2::Point2, Primitive,
+        },
+    };
+
+    use super::intersections;
+
+    fn point(x: f64, y: f64) -> Rc<RefCell<Point2>> {
+        Rc::new(RefCell::new(Point2::new(x, y)))
+    }
+
+    #[test]
+    fn test_line_line_intersections() {
+        let line1 = Line::new(point(0.0, 0.0), point(2.0, 2.0));
+        let line2 = Line::new(point(0.0, 2.0), point(2.0, 0.0));
+        let result = intersections(Primitive::Line(line1.clone()), Primitive::Line(line2)).unwrap();
+        assert_eq!(result.len(), 1);
+        assert!((result[0] - Vector2::new(1.0, 1.0)).norm() < 1e-9);
+
+        // The segments do not reach each other, but the infinite line does
+        let line3 = Line::new(point(3.0, 0.0), point(4.0, -1.0));
+        assert!(
+            intersections(Primitive::Line(line1.clone()), Primitive::Line(line3))
+                .unwrap()
+                .is_empty()
+        );
+        let infinite = InfiniteLine::new(point(3.0, 0.0), -PI / 4.0);
+        let result =
+            intersections(Primitive::Line(line1), Primitive::InfiniteLine(infinite)).unwrap();
+        assert_eq!(result.len(), 1);
+        assert!((result[0] - Vector2::new(1.5, 1.5)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_line_circle_intersections() {
+        let circle = Circle::new(point(0.0, 0.0), 1.0);
+        let line = Line::new(point(-2.0, 0.0), point(2.0, 0.0));
+        let result =
+            intersections(Primitive::Line(line), Primitive::Circle(circle.clone())).unwrap();
+        assert_eq!(result.len(), 2);
+        assert!(result
+            .iter()
+            .any(|p| (p - Vector2::new(1.0, 0.0)).norm() < 1e-9));
+        assert!(result
+            .iter()
+            .any(|p| (p - Vector2::new(-1.0, 0.0)).norm() < 1e-9));
+
+        // A tangent line touches the circle once
+        let tangent = Line::new(point(-2.0, 1.0), point(2.0, 1.0));
+        let result = intersections(Primitive::Line(tangent), Primitive::Circle(circle)).unwrap();
+        assert_eq!(result.len(), 1);
+        assert!((result[0] - Vector2::new(0.0, 1.0)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_arc_intersections() {
+        // Only the upper half of the circle is part of the arc
+        let arc = Arc::new(point(0.0, 0.0), 1.0, false, 0.0, PI);
+        let line = Line::new(point(0.5, -2.0), point(0.5, 2.0));
+        let result = intersections(Primitive::Arc(arc.clone()), Primitive::Line(line)).unwrap();
+        assert_eq!(result.len(), 1);
+        assert!(result[0].y > 0.0);
+
+        let circle = Circle::new(point(1.0, 0.0), 1.0);
+        let result = intersections(Primitive::Arc(arc), Primitive::Circle(circle)).unwrap();
+        assert_eq!(result.len(), 1);
+        assert!((result[0] - Vector2::new(0.5, 0.75f64.sqrt())).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_unsupported_intersections() {
+        let line = Line::new(point(-2.0, 0.0), point(2.0, 0.0));
+        let ellipse = Ellipse::new(point(0.0, 0.0), 2.0, 1.0, 0.0);
+        assert!(matches!(
+            intersections(Primitive::Line(line.clone()), Primitive::Ellipse(ellipse)),
+            Err(ISOTopeError::UnsupportedPrimitive)
+        ));
+
+        // Points are not curves and never intersect anything
+        let result = intersections(
+            Primitive::Line(line),
+            Primitive::Point2(Point2::new(0.0, 0.0)),
+        );
+        assert!(result.unwrap().is_empty());
+    }
 }
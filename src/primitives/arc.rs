@@ -1,4 +1,5 @@
 use std::cell::RefCell;
+use std::f64::consts::TAU;
 use std::rc::Rc;
 
 use nalgebra::{DVectorView, SMatrix, SMatrixView, SVector, Vector2};
@@ -103,6 +104,25 @@ impl Arc {
         self.clockwise = clockwise;
     }
 
+    // The angle swept from the start angle to the end angle in the direction of the arc, in [0, 2pi)
+    pub fn sweep_angle(&self) -> f64 {
+        if self.clockwise {
+            (self.start_angle() - self.end_angle()).rem_euclid(TAU)
+        } else {
+            (self.end_angle() - self.start_angle()).rem_euclid(TAU)
+        }
+    }
+
+    // Checks whether a direction given as an angle around the center lies within the arc
+    pub fn contains_angle(&self, angle: f64) -> bool {
+        let offset = if self.clockwise {
+            (self.start_angle() - angle).rem_euclid(TAU)
+        } else {
+            (angle - self.start_angle()).rem_euclid(TAU)
+        };
+        offset <= self.sweep_angle()
+    }
+
     pub fn start_point(&self) -> Vector2<f64> {
         let center = self.center();
         let radius = self.radius();
@@ -158,6 +178,43 @@ impl Arc {
         ])
     }
 
+    // The angle halfway between the start and end angle in the direction of the arc
+    pub fn mid_angle(&self) -> f64 {
+        if self.clockwise {
+            self.start_angle() - 0.5 * self.sweep_angle()
+        } else {
+            self.start_angle() + 0.5 * self.sweep_angle()
+        }
+    }
+
+    pub fn mid_point(&self) -> Vector2<f64> {
+        let center = self.center();
+        let radius = self.radius();
+        let angle = self.mid_angle();
+        let x = center.borrow().x() + radius * angle.cos();
+        let y = center.borrow().y() + radius * angle.sin();
+        Vector2::new(x, y)
+    }
+
+    pub fn mid_point_gradient(&self) -> SMatrix<f64, 2, 5> {
+        let radius = self.radius();
+        let angle = self.mid_angle();
+
+        // The mid angle moves by half of the change of the start and end angle
+        SMatrix::<f64, 2, 5>::from_row_slice(&[
+            1.0,
+            0.0,
+            angle.cos(),
+            -0.5 * radius * angle.sin(),
+            -0.5 * radius * angle.sin(),
+            0.0,
+            1.0,
+            angle.sin(),
+            0.5 * radius * angle.cos(),
+            0.5 * radius * angle.cos(),
+        ])
+    }
+
     pub fn add_to_gradient(&mut self, gradient: SMatrixView<f64, 1, 5>) {
         // Panic if nan or inf is encountered
         assert!(gradient.iter().all(|x| x.is_finite()));
@@ -177,11 +234,11 @@ impl PrimitiveLike for Arc {
         self.gradient = SVector::<f64, 3>::zeros();
     }
 
-    fn get_data(&self) -> DVectorView<f64> {
+    fn get_data(&self) -> DVectorView<'_, f64> {
         self.data.as_view()
     }
 
-    fn get_gradient(&self) -> DVectorView<f64> {
+    fn get_gradient(&self) -> DVectorView<'_, f64> {
         self.gradient.as_view()
     }
 
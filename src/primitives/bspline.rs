@@ -0,0 +1,300 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use nalgebra::{DMatrix, DMatrixView, DVector, DVectorView, SMatrix, SVector, Vector2};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use super::point2::Point2;
+use super::{PrimitiveCell, PrimitiveLike};
+
+// A B-spline curve of the given degree defined by referenced control points. With weights it is a
+// NURBS curve. Without an explicit knot vector a clamped uniform one is used, so the curve starts at
+// the first and ends at the last control point. Like a line, the curve has no parameters of its own,
+// all gradients are passed on to the control points.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct BSpline {
+    control_points: Vec<Rc<RefCell<Point2>>>,
+    weights: Option<Vec<f64>>,
+    knots: Vec<f64>,
+    degree: usize,
+    empty: SVector<f64, 0>,
+}
+
+impl BSpline {
+    pub fn new(
+        control_points: Vec<Rc<RefCell<Point2>>>,
+        degree: usize,
+        weights: Option<Vec<f64>>,
+        knots: Option<Vec<f64>>,
+    ) -> Self {
+        let n = control_points.len();
+        assert!(degree >= 1 && n > degree);
+        if let Some(weights) = &weights {
+            assert!(weights.len() == n);
+            assert!(weights.iter().all(|w| w.is_finite() && *w > 0.0));
+        }
+        let knots = knots.unwrap_or_else(|| clamped_uniform_knots(n, degree));
+        assert!(knots.len() == n + degree + 1);
+        assert!(knots.iter().all(|k| k.is_finite()));
+        assert!(knots.windows(2).all(|k| k[0] <= k[1]));
+        assert!(knots[degree] < knots[n]);
+
+        Self {
+            control_points,
+            weights,
+            knots,
+            degree,
+            empty: SVector::<f64, 0>::zeros(),
+        }
+    }
+
+    pub fn control_points(&self) -> Vec<Rc<RefCell<Point2>>> {
+        self.control_points.clone()
+    }
+
+    pub fn set_control_point(&mut self, index: usize, point: Rc<RefCell<Point2>>) {
+        self.control_points[index] = point;
+    }
+
+    pub fn weights(&self) -> Option<Vec<f64>> {
+        self.weights.clone()
+    }
+
+    pub fn knots(&self) -> Vec<f64> {
+        self.knots.clone()
+    }
+
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    // The range of valid curve parameters
+    pub fn domain(&self) -> (f64, f64) {
+        (
+            self.knots[self.degree],
+            self.knots[self.control_points.len()],
+        )
+    }
+
+    pub fn point_at(&self, u: f64) -> Vector2<f64> {
+        let (basis, _) = self.rational_basis(u);
+        self.combine(&basis)
+    }
+
+    pub fn derivative_at(&self, u: f64) -> Vector2<f64> {
+        let (_, basis_derivative) = self.rational_basis(u);
+        self.combine(&basis_derivative)
+    }
+
+    // The gradient of the point at the fixed parameter u with respect to the control points, two
+    // columns per control point
+    pub fn point_at_gradient(&self, u: f64) -> DMatrix<f64> {
+        let (basis, _) = self.rational_basis(u);
+        self.expand(&basis)
+    }
+
+    pub fn derivative_at_gradient(&self, u: f64) -> DMatrix<f64> {
+        let (_, basis_derivative) = self.rational_basis(u);
+        self.expand(&basis_derivative)
+    }
+
+    pub fn start_point(&self) -> Vector2<f64> {
+        self.point_at(self.domain().0)
+    }
+
+    pub fn end_point(&self) -> Vector2<f64> {
+        self.point_at(self.domain().1)
+    }
+
+    pub fn start_tangent(&self) -> Vector2<f64> {
+        self.derivative_at(self.domain().0)
+    }
+
+    pub fn end_tangent(&self) -> Vector2<f64> {
+        self.derivative_at(self.domain().1)
+    }
+
+    pub fn add_to_gradient(&mut self, gradient: DMatrixView<f64>) {
+        assert!(gradient.nrows() == 1 && gradient.ncols() == 2 * self.control_points.len());
+        // Panic if nan or inf is encountered
+        assert!(gradient.iter().all(|x| x.is_finite()));
+        for (i, point) in self.control_points.iter().enumerate() {
+            let point_gradient: SMatrix<f64, 1, 2> =
+                gradient.fixed_view::<1, 2>(0, 2 * i).into_owned();
+            point.borrow_mut().add_to_gradient(point_gradient.as_view());
+        }
+    }
+
+    fn combine(&self, basis: &DVector<f64>) -> Vector2<f64> {
+        self.control_points
+            .iter()
+            .zip(basis.iter())
+            .map(|(point, b)| *b * point.borrow().data())
+            .sum()
+    }
+
+    fn expand(&self, basis: &DVector<f64>) -> DMatrix<f64> {
+        let mut gradient = DMatrix::zeros(2, 2 * self.control_points.len());
+        for (i, b) in basis.iter().enumerate() {
+            gradient[(0, 2 * i)] = *b;
+            gradient[(1, 2 * i + 1)] = *b;
+        }
+        gradient
+    }
+
+    // The values and derivatives of the (rational) basis functions of all control points at u
+    fn rational_basis(&self, u: f64) -> (DVector<f64>, DVector<f64>) {
+        let (basis, basis_derivative) = self.basis(u);
+        let Some(weights) = &self.weights else {
+            return (basis, basis_derivative);
+        };
+
+        let weights = DVector::from_column_slice(weights);
+        let weighted = basis.component_mul(&weights);
+        let weighted_derivative = basis_derivative.component_mul(&weights);
+        let w = weighted.sum();
+        let w_derivative = weighted_derivative.sum();
+
+        let rational = &weighted / w;
+        let rational_derivative = (weighted_derivative * w - weighted * w_derivative) / (w * w);
+        (rational, rational_derivative)
+    }
+
+    // The values and derivatives of the B-spline basis functions at u using the Cox-de Boor recursion
+    fn basis(&self, u: f64) -> (DVector<f64>, DVector<f64>) {
+        let n = self.control_points.len();
+        let p = self.degree;
+        let knots = &self.knots;
+        let (u_min, u_max) = self.domain();
+        let u = u.clamp(u_min, u_max);
+
+        // Degree zero, the last non-empty span also includes its end so that u_max is valid
+        let mut values = DVector::<f64>::zeros(knots.len() - 1);
+        let span = (p..n)
+            .rev()
+            .find(|&i| knots[i] <= u && knots[i] < knots[i + 1])
+            .unwrap_or(p);
+        values[span] = 1.0;
+
+        let ratio = |numerator: f64, denominator: f64| {
+            if denominator == 0.0 {
+                0.0
+            } else {
+                numerator / denominator
+            }
+        };
+
+        let mut previous = values.clone();
+        for k in 1..=p {
+            previous = values.clone();
+            values = DVector::zeros(knots.len() - 1 - k);
+            for i in 0..values.len() {
+                values[i] = ratio(u - knots[i], knots[i + k] - knots[i]) * previous[i]
+                    + ratio(knots[i + k + 1] - u, knots[i + k + 1] - knots[i + 1])
+                        * previous[i + 1];
+            }
+        }
+
+        let mut derivatives = DVector::<f64>::zeros(n);
+        for i in 0..n {
+            derivatives[i] = ratio(p as f64, knots[i + p] - knots[i]) * previous[i]
+                - ratio(p as f64, knots[i + p + 1] - knots[i + 1]) * previous[i + 1];
+        }
+
+        (values, derivatives)
+    }
+}
+
+fn clamped_uniform_knots(n: usize, degree: usize) -> Vec<f64> {
+    let spans = n - degree;
+    (0..n + degree + 1)
+        .map(|i| {
+            if i <= degree {
+                0.0
+            } else if i >= n {
+                1.0
+            } else {
+                (i - degree) as f64 / spans as f64
+            }
+        })
+        .collect()
+}
+
+impl PrimitiveLike for BSpline {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        self.control_points
+            .iter()
+            .map(|point| PrimitiveCell::Point2(point.clone()))
+            .collect()
+    }
+
+    fn zero_gradient(&mut self) {
+        // Referenced points will zero their gradients automatically as they are part of the sketch
+    }
+
+    fn get_data(&self) -> DVectorView<'_, f64> {
+        // empty vector
+        self.empty.as_view()
+    }
+
+    fn set_data(&mut self, _data: DVectorView<f64>) {
+        // Do nothing
+    }
+
+    fn get_gradient(&self) -> DVectorView<'_, f64> {
+        // empty vector
+        self.empty.as_view()
+    }
+
+    fn to_primitive(&self) -> super::Primitive {
+        super::Primitive::BSpline(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::primitives::{bspline::BSpline, point2::Point2};
+
+    fn control_points() -> Vec<Rc<RefCell<Point2>>> {
+        [(0.0, 0.0), (1.0, 2.0), (3.0, 2.5), (4.0, 0.5), (5.0, 1.0)]
+            .iter()
+            .map(|(x, y)| Rc::new(RefCell::new(Point2::new(*x, *y))))
+            .collect()
+    }
+
+    #[test]
+    fn test_bspline_evaluation() {
+        for weights in [None, Some(vec![1.0, 0.5, 2.0, 1.0, 1.5])] {
+            let points = control_points();
+            let spline = BSpline::new(points.clone(), 3, weights, None);
+
+            // A clamped spline interpolates its first and last control point
+            assert!((spline.start_point() - points[0].borrow().data()).norm() < 1e-12);
+            assert!((spline.end_point() - points[4].borrow().data()).norm() < 1e-12);
+
+            // Compare the derivative to finite differences
+            for u in [0.1, 0.3, 0.5, 0.8] {
+                let eps = 1e-6;
+                let numerical = (spline.point_at(u + eps) - spline.point_at(u - eps)) / (2.0 * eps);
+                println!("{:?} {:?}", spline.derivative_at(u), numerical);
+                assert!((spline.derivative_at(u) - numerical).norm() < 1e-6);
+            }
+
+            // Moving a control point moves the curve according to the gradient
+            let u = 0.4;
+            let gradient = spline.point_at_gradient(u);
+            let before = spline.point_at(u);
+            points[2].borrow_mut().set_y(2.5 + 1e-3);
+            let after = spline.point_at(u);
+            let expected = gradient.column(5) * 1e-3;
+            assert!((after - before - expected).norm() < 1e-9);
+        }
+    }
+}
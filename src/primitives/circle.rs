@@ -70,7 +70,7 @@ impl PrimitiveLike for Circle {
         self.gradient = SVector::<f64, 1>::zeros();
     }
 
-    fn get_data(&self) -> DVectorView<f64> {
+    fn get_data(&self) -> DVectorView<'_, f64> {
         self.data.as_view()
     }
 
@@ -79,7 +79,7 @@ impl PrimitiveLike for Circle {
         self.data.copy_from(&data);
     }
 
-    fn get_gradient(&self) -> DVectorView<f64> {
+    fn get_gradient(&self) -> DVectorView<'_, f64> {
         self.gradient.as_view()
     }
 
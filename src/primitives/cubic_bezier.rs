@@ -0,0 +1,189 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use nalgebra::{DVectorView, SMatrix, SMatrixView, SVector, Vector2};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use super::point2::Point2;
+use super::{PrimitiveCell, PrimitiveLike};
+
+// A cubic Bezier curve from the start to the end point, shaped by two control points.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct CubicBezier {
+    start: Rc<RefCell<Point2>>,
+    control1: Rc<RefCell<Point2>>,
+    control2: Rc<RefCell<Point2>>,
+    end: Rc<RefCell<Point2>>,
+    empty: SVector<f64, 0>,
+}
+
+impl CubicBezier {
+    pub fn new(
+        start: Rc<RefCell<Point2>>,
+        control1: Rc<RefCell<Point2>>,
+        control2: Rc<RefCell<Point2>>,
+        end: Rc<RefCell<Point2>>,
+    ) -> Self {
+        Self {
+            start,
+            control1,
+            control2,
+            end,
+            empty: SVector::<f64, 0>::zeros(),
+        }
+    }
+
+    pub fn reverse(&self) -> Self {
+        CubicBezier::new(
+            self.end.clone(),
+            self.control2.clone(),
+            self.control1.clone(),
+            self.start.clone(),
+        )
+    }
+
+    pub fn start(&self) -> Rc<RefCell<Point2>> {
+        self.start.clone()
+    }
+
+    pub fn set_start(&mut self, start: Rc<RefCell<Point2>>) {
+        self.start = start;
+    }
+
+    pub fn start_gradient(&self) -> SMatrix<f64, 2, 8> {
+        point_selection(0)
+    }
+
+    pub fn control1(&self) -> Rc<RefCell<Point2>> {
+        self.control1.clone()
+    }
+
+    pub fn set_control1(&mut self, control1: Rc<RefCell<Point2>>) {
+        self.control1 = control1;
+    }
+
+    pub fn control1_gradient(&self) -> SMatrix<f64, 2, 8> {
+        point_selection(1)
+    }
+
+    pub fn control2(&self) -> Rc<RefCell<Point2>> {
+        self.control2.clone()
+    }
+
+    pub fn set_control2(&mut self, control2: Rc<RefCell<Point2>>) {
+        self.control2 = control2;
+    }
+
+    pub fn control2_gradient(&self) -> SMatrix<f64, 2, 8> {
+        point_selection(2)
+    }
+
+    pub fn end(&self) -> Rc<RefCell<Point2>> {
+        self.end.clone()
+    }
+
+    pub fn set_end(&mut self, end: Rc<RefCell<Point2>>) {
+        self.end = end;
+    }
+
+    pub fn end_gradient(&self) -> SMatrix<f64, 2, 8> {
+        point_selection(3)
+    }
+
+    pub fn point_at(&self, t: f64) -> Vector2<f64> {
+        let [b0, b1, b2, b3] = bernstein(t);
+        b0 * self.start.borrow().data()
+            + b1 * self.control1.borrow().data()
+            + b2 * self.control2.borrow().data()
+            + b3 * self.end.borrow().data()
+    }
+
+    pub fn point_at_gradient(&self, t: f64) -> SMatrix<f64, 2, 8> {
+        let [b0, b1, b2, b3] = bernstein(t);
+        b0 * self.start_gradient()
+            + b1 * self.control1_gradient()
+            + b2 * self.control2_gradient()
+            + b3 * self.end_gradient()
+    }
+
+    // The derivative of the curve at the start, pointing from the start to the first control point
+    pub fn start_tangent(&self) -> Vector2<f64> {
+        3.0 * (self.control1.borrow().data() - self.start.borrow().data())
+    }
+
+    pub fn start_tangent_gradient(&self) -> SMatrix<f64, 2, 8> {
+        3.0 * (self.control1_gradient() - self.start_gradient())
+    }
+
+    // The derivative of the curve at the end, pointing from the second control point to the end
+    pub fn end_tangent(&self) -> Vector2<f64> {
+        3.0 * (self.end.borrow().data() - self.control2.borrow().data())
+    }
+
+    pub fn end_tangent_gradient(&self) -> SMatrix<f64, 2, 8> {
+        3.0 * (self.end_gradient() - self.control2_gradient())
+    }
+
+    pub fn add_to_gradient(&mut self, gradient: SMatrixView<f64, 1, 8>) {
+        // Panic if nan or inf is encountered
+        assert!(gradient.iter().all(|x| x.is_finite()));
+        for (i, point) in [&self.start, &self.control1, &self.control2, &self.end]
+            .iter()
+            .enumerate()
+        {
+            point
+                .borrow_mut()
+                .add_to_gradient(gradient.fixed_view::<1, 2>(0, 2 * i));
+        }
+    }
+}
+
+fn point_selection(index: usize) -> SMatrix<f64, 2, 8> {
+    let mut gradient = SMatrix::<f64, 2, 8>::zeros();
+    gradient[(0, 2 * index)] = 1.0;
+    gradient[(1, 2 * index + 1)] = 1.0;
+    gradient
+}
+
+fn bernstein(t: f64) -> [f64; 4] {
+    let s = 1.0 - t;
+    [s * s * s, 3.0 * s * s * t, 3.0 * s * t * t, t * t * t]
+}
+
+impl PrimitiveLike for CubicBezier {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![
+            PrimitiveCell::Point2(self.start.clone()),
+            PrimitiveCell::Point2(self.control1.clone()),
+            PrimitiveCell::Point2(self.control2.clone()),
+            PrimitiveCell::Point2(self.end.clone()),
+        ]
+    }
+
+    fn zero_gradient(&mut self) {
+        // Referenced points will zero their gradients automatically as they are part of the sketch
+    }
+
+    fn get_data(&self) -> DVectorView<'_, f64> {
+        // empty vector
+        self.empty.as_view()
+    }
+
+    fn set_data(&mut self, _data: DVectorView<f64>) {
+        // Do nothing
+    }
+
+    fn get_gradient(&self) -> DVectorView<'_, f64> {
+        // empty vector
+        self.empty.as_view()
+    }
+
+    fn to_primitive(&self) -> super::Primitive {
+        super::Primitive::CubicBezier(self.clone())
+    }
+}
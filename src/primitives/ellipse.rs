@@ -0,0 +1,212 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use nalgebra::{DVectorView, SMatrix, SMatrixView, SVector, Vector2};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use super::{point2::Point2, PrimitiveCell, PrimitiveLike};
+
+// An ellipse with the semi-major axis along the rotation angle and the semi-minor axis perpendicular
+// to it. Points on the ellipse are given by the parameter t as
+// center + R(rotation) * (semi_major * cos(t), semi_minor * sin(t)).
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct Ellipse {
+    center: Rc<RefCell<Point2>>,
+    data: SVector<f64, 3>,
+    gradient: SVector<f64, 3>,
+}
+
+impl Ellipse {
+    pub fn new(
+        center: Rc<RefCell<Point2>>,
+        semi_major: f64,
+        semi_minor: f64,
+        rotation: f64,
+    ) -> Self {
+        Self {
+            center,
+            data: SVector::<f64, 3>::from_row_slice(&[semi_major, semi_minor, rotation]),
+            gradient: SVector::<f64, 3>::zeros(),
+        }
+    }
+
+    pub fn center(&self) -> Rc<RefCell<Point2>> {
+        self.center.clone()
+    }
+
+    pub fn set_center(&mut self, center: Rc<RefCell<Point2>>) {
+        self.center = center;
+    }
+
+    pub fn center_gradient(&self) -> SMatrix<f64, 2, 5> {
+        SMatrix::<f64, 2, 5>::from_row_slice(&[1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0])
+    }
+
+    pub fn semi_major(&self) -> f64 {
+        self.data[0]
+    }
+
+    pub fn set_semi_major(&mut self, semi_major: f64) {
+        self.data[0] = semi_major;
+    }
+
+    pub fn semi_major_gradient(&self) -> SMatrix<f64, 1, 5> {
+        SMatrix::<f64, 1, 5>::from_row_slice(&[0.0, 0.0, 1.0, 0.0, 0.0])
+    }
+
+    pub fn semi_minor(&self) -> f64 {
+        self.data[1]
+    }
+
+    pub fn set_semi_minor(&mut self, semi_minor: f64) {
+        self.data[1] = semi_minor;
+    }
+
+    pub fn semi_minor_gradient(&self) -> SMatrix<f64, 1, 5> {
+        SMatrix::<f64, 1, 5>::from_row_slice(&[0.0, 0.0, 0.0, 1.0, 0.0])
+    }
+
+    pub fn rotation(&self) -> f64 {
+        self.data[2]
+    }
+
+    pub fn set_rotation(&mut self, rotation: f64) {
+        self.data[2] = rotation;
+    }
+
+    pub fn rotation_gradient(&self) -> SMatrix<f64, 1, 5> {
+        SMatrix::<f64, 1, 5>::from_row_slice(&[0.0, 0.0, 0.0, 0.0, 1.0])
+    }
+
+    pub fn point_at(&self, t: f64) -> Vector2<f64> {
+        ellipse_point(
+            self.center.borrow().data(),
+            self.semi_major(),
+            self.semi_minor(),
+            self.rotation(),
+            t,
+        )
+    }
+
+    // The gradient of the point at the fixed parameter t with respect to the ellipse
+    pub fn point_at_gradient(&self, t: f64) -> SMatrix<f64, 2, 5> {
+        let grad = ellipse_point_gradient(self.semi_major(), self.semi_minor(), self.rotation(), t);
+        grad.fixed_view::<2, 5>(0, 0).into_owned()
+    }
+
+    pub fn area(&self) -> f64 {
+        std::f64::consts::PI * self.semi_major() * self.semi_minor()
+    }
+
+    pub fn add_to_gradient(&mut self, gradient: SMatrixView<f64, 1, 5>) {
+        // Panic if nan or inf is encountered
+        assert!(gradient.iter().all(|x| x.is_finite()));
+        self.center
+            .borrow_mut()
+            .add_to_gradient(gradient.fixed_view::<1, 2>(0, 0));
+        self.gradient += gradient.fixed_view::<1, 3>(0, 2).transpose();
+    }
+}
+
+pub(crate) fn ellipse_point(
+    center: Vector2<f64>,
+    semi_major: f64,
+    semi_minor: f64,
+    rotation: f64,
+    t: f64,
+) -> Vector2<f64> {
+    let (sin_rotation, cos_rotation) = rotation.sin_cos();
+    let (sin_t, cos_t) = t.sin_cos();
+    let u = semi_major * cos_t;
+    let v = semi_minor * sin_t;
+    center
+        + Vector2::new(
+            cos_rotation * u - sin_rotation * v,
+            sin_rotation * u + cos_rotation * v,
+        )
+}
+
+// The gradient of a point on an ellipse with respect to the center, the semi-axes, the rotation and
+// the parameter t, in this order
+pub(crate) fn ellipse_point_gradient(
+    semi_major: f64,
+    semi_minor: f64,
+    rotation: f64,
+    t: f64,
+) -> SMatrix<f64, 2, 6> {
+    let (sin_rotation, cos_rotation) = rotation.sin_cos();
+    let (sin_t, cos_t) = t.sin_cos();
+    let u = semi_major * cos_t;
+    let v = semi_minor * sin_t;
+    SMatrix::<f64, 2, 6>::from_row_slice(&[
+        1.0,
+        0.0,
+        cos_rotation * cos_t,
+        -sin_rotation * sin_t,
+        -sin_rotation * u - cos_rotation * v,
+        -cos_rotation * semi_major * sin_t - sin_rotation * semi_minor * cos_t,
+        0.0,
+        1.0,
+        sin_rotation * cos_t,
+        cos_rotation * sin_t,
+        cos_rotation * u - sin_rotation * v,
+        -sin_rotation * semi_major * sin_t + cos_rotation * semi_minor * cos_t,
+    ])
+}
+
+impl PrimitiveLike for Ellipse {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![PrimitiveCell::Point2(self.center.clone())]
+    }
+
+    fn zero_gradient(&mut self) {
+        self.gradient = SVector::<f64, 3>::zeros();
+    }
+
+    fn get_data(&self) -> DVectorView<'_, f64> {
+        self.data.as_view()
+    }
+
+    fn set_data(&mut self, data: DVectorView<f64>) {
+        assert!(data.iter().all(|x| x.is_finite()));
+        self.data.copy_from(&data);
+    }
+
+    fn get_gradient(&self) -> DVectorView<'_, f64> {
+        self.gradient.as_view()
+    }
+
+    fn to_primitive(&self) -> super::Primitive {
+        super::Primitive::Ellipse(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{SVector, Vector2};
+
+    use crate::primitives::ellipse::{ellipse_point, ellipse_point_gradient};
+
+    #[test]
+    fn test_ellipse_point_gradient() {
+        // Center, semi-axes, rotation and parameter
+        let x = SVector::<f64, 6>::from_row_slice(&[1.0, -2.0, 3.0, 1.5, 0.7, 2.2]);
+        let point =
+            |x: &SVector<f64, 6>| ellipse_point(Vector2::new(x[0], x[1]), x[2], x[3], x[4], x[5]);
+        let gradient = ellipse_point_gradient(x[2], x[3], x[4], x[5]);
+
+        // Compare each column to central differences
+        let eps = 1e-6;
+        for i in 0..6 {
+            let mut step = SVector::<f64, 6>::zeros();
+            step[i] = eps;
+            let numerical = (point(&(x + step)) - point(&(x - step))) / (2.0 * eps);
+            assert!((gradient.column(i) - numerical).norm() < 1e-8);
+        }
+    }
+}
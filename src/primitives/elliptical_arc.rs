@@ -0,0 +1,321 @@
+use std::cell::RefCell;
+use std::f64::consts::TAU;
+use std::rc::Rc;
+
+use nalgebra::{DVectorView, SMatrix, SMatrixView, SVector, Vector2};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use super::{
+    ellipse::{ellipse_point, ellipse_point_gradient},
+    point2::Point2,
+    PrimitiveCell, PrimitiveLike,
+};
+
+// A part of an ellipse between a start and an end parameter, see Ellipse for the parametrization.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct EllipticalArc {
+    center: Rc<RefCell<Point2>>,
+    data: SVector<f64, 5>,
+    gradient: SVector<f64, 5>,
+
+    clockwise: bool,
+}
+
+impl EllipticalArc {
+    pub fn new(
+        center: Rc<RefCell<Point2>>,
+        semi_major: f64,
+        semi_minor: f64,
+        rotation: f64,
+        clockwise: bool,
+        start_parameter: f64,
+        end_parameter: f64,
+    ) -> Self {
+        Self {
+            center,
+            data: SVector::<f64, 5>::from_row_slice(&[
+                semi_major,
+                semi_minor,
+                rotation,
+                start_parameter,
+                end_parameter,
+            ]),
+            gradient: SVector::<f64, 5>::zeros(),
+
+            clockwise,
+        }
+    }
+
+    pub fn reverse(&self) -> Self {
+        EllipticalArc::new(
+            self.center.clone(),
+            self.semi_major(),
+            self.semi_minor(),
+            self.rotation(),
+            !self.clockwise,
+            self.end_parameter(),
+            self.start_parameter(),
+        )
+    }
+
+    pub fn center(&self) -> Rc<RefCell<Point2>> {
+        self.center.clone()
+    }
+
+    pub fn set_center(&mut self, center: Rc<RefCell<Point2>>) {
+        self.center = center;
+    }
+
+    pub fn center_gradient(&self) -> SMatrix<f64, 2, 7> {
+        SMatrix::<f64, 2, 7>::from_row_slice(&[
+            1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+        ])
+    }
+
+    pub fn semi_major(&self) -> f64 {
+        self.data[0]
+    }
+
+    pub fn set_semi_major(&mut self, semi_major: f64) {
+        self.data[0] = semi_major;
+    }
+
+    pub fn semi_major_gradient(&self) -> SMatrix<f64, 1, 7> {
+        SMatrix::<f64, 1, 7>::from_row_slice(&[0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0])
+    }
+
+    pub fn semi_minor(&self) -> f64 {
+        self.data[1]
+    }
+
+    pub fn set_semi_minor(&mut self, semi_minor: f64) {
+        self.data[1] = semi_minor;
+    }
+
+    pub fn semi_minor_gradient(&self) -> SMatrix<f64, 1, 7> {
+        SMatrix::<f64, 1, 7>::from_row_slice(&[0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0])
+    }
+
+    pub fn rotation(&self) -> f64 {
+        self.data[2]
+    }
+
+    pub fn set_rotation(&mut self, rotation: f64) {
+        self.data[2] = rotation;
+    }
+
+    pub fn rotation_gradient(&self) -> SMatrix<f64, 1, 7> {
+        SMatrix::<f64, 1, 7>::from_row_slice(&[0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0])
+    }
+
+    pub fn start_parameter(&self) -> f64 {
+        self.data[3]
+    }
+
+    pub fn set_start_parameter(&mut self, start_parameter: f64) {
+        self.data[3] = start_parameter;
+    }
+
+    pub fn start_parameter_gradient(&self) -> SMatrix<f64, 1, 7> {
+        SMatrix::<f64, 1, 7>::from_row_slice(&[0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0])
+    }
+
+    pub fn end_parameter(&self) -> f64 {
+        self.data[4]
+    }
+
+    pub fn set_end_parameter(&mut self, end_parameter: f64) {
+        self.data[4] = end_parameter;
+    }
+
+    pub fn end_parameter_gradient(&self) -> SMatrix<f64, 1, 7> {
+        SMatrix::<f64, 1, 7>::from_row_slice(&[0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0])
+    }
+
+    pub fn clockwise(&self) -> bool {
+        self.clockwise
+    }
+
+    pub fn set_clockwise(&mut self, clockwise: bool) {
+        self.clockwise = clockwise;
+    }
+
+    // The parameter range covered from the start to the end parameter, in [0, 2pi)
+    pub fn sweep_parameter(&self) -> f64 {
+        if self.clockwise {
+            (self.start_parameter() - self.end_parameter()).rem_euclid(TAU)
+        } else {
+            (self.end_parameter() - self.start_parameter()).rem_euclid(TAU)
+        }
+    }
+
+    pub fn point_at(&self, t: f64) -> Vector2<f64> {
+        ellipse_point(
+            self.center.borrow().data(),
+            self.semi_major(),
+            self.semi_minor(),
+            self.rotation(),
+            t,
+        )
+    }
+
+    pub fn start_point(&self) -> Vector2<f64> {
+        self.point_at(self.start_parameter())
+    }
+
+    pub fn start_point_gradient(&self) -> SMatrix<f64, 2, 7> {
+        let grad = ellipse_point_gradient(
+            self.semi_major(),
+            self.semi_minor(),
+            self.rotation(),
+            self.start_parameter(),
+        );
+        let mut gradient = SMatrix::<f64, 2, 7>::zeros();
+        gradient.fixed_view_mut::<2, 6>(0, 0).copy_from(&grad);
+        gradient
+    }
+
+    pub fn end_point(&self) -> Vector2<f64> {
+        self.point_at(self.end_parameter())
+    }
+
+    pub fn end_point_gradient(&self) -> SMatrix<f64, 2, 7> {
+        let grad = ellipse_point_gradient(
+            self.semi_major(),
+            self.semi_minor(),
+            self.rotation(),
+            self.end_parameter(),
+        );
+        let mut gradient = SMatrix::<f64, 2, 7>::zeros();
+        gradient
+            .fixed_view_mut::<2, 5>(0, 0)
+            .copy_from(&grad.fixed_view::<2, 5>(0, 0));
+        gradient
+            .fixed_view_mut::<2, 1>(0, 6)
+            .copy_from(&grad.fixed_view::<2, 1>(0, 5));
+        gradient
+    }
+
+    pub fn add_to_gradient(&mut self, gradient: SMatrixView<f64, 1, 7>) {
+        // Panic if nan or inf is encountered
+        assert!(gradient.iter().all(|x| x.is_finite()));
+        self.center
+            .borrow_mut()
+            .add_to_gradient(gradient.fixed_view::<1, 2>(0, 0));
+        self.gradient += gradient.fixed_view::<1, 5>(0, 2).transpose();
+    }
+}
+
+impl PrimitiveLike for EllipticalArc {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![PrimitiveCell::Point2(self.center.clone())]
+    }
+
+    fn zero_gradient(&mut self) {
+        self.gradient = SVector::<f64, 5>::zeros();
+    }
+
+    fn get_data(&self) -> DVectorView<'_, f64> {
+        self.data.as_view()
+    }
+
+    fn set_data(&mut self, data: DVectorView<f64>) {
+        assert!(data.iter().all(|x| x.is_finite()));
+        self.data.copy_from(&data);
+    }
+
+    fn get_gradient(&self) -> DVectorView<'_, f64> {
+        self.gradient.as_view()
+    }
+
+    fn to_primitive(&self) -> super::Primitive {
+        super::Primitive::EllipticalArc(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use nalgebra::{SMatrix, SVector, Vector2};
+
+    use crate::{
+        primitives::{
+            ellipse::Ellipse, elliptical_arc::EllipticalArc, point2::Point2, PrimitiveCell,
+        },
+        sketch::Sketch,
+    };
+
+    // Center, semi-axes, rotation, start and end parameter
+    fn elliptical_arc(x: &SVector<f64, 7>) -> EllipticalArc {
+        let center = Rc::new(RefCell::new(Point2::new(x[0], x[1])));
+        EllipticalArc::new(center, x[2], x[3], x[4], false, x[5], x[6])
+    }
+
+    // Compares each column of the gradient to central differences of the point
+    fn check_gradient(
+        point: impl Fn(&EllipticalArc) -> Vector2<f64>,
+        gradient: impl Fn(&EllipticalArc) -> SMatrix<f64, 2, 7>,
+    ) {
+        let x = SVector::<f64, 7>::from_row_slice(&[1.0, -2.0, 3.0, 1.5, 0.7, 0.4, 2.2]);
+        let gradient = gradient(&elliptical_arc(&x));
+        let eps = 1e-6;
+        for i in 0..7 {
+            let mut step = SVector::<f64, 7>::zeros();
+            step[i] = eps;
+            let numerical = (point(&elliptical_arc(&(x + step)))
+                - point(&elliptical_arc(&(x - step))))
+                / (2.0 * eps);
+            assert!((gradient.column(i) - numerical).norm() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_elliptical_arc_end_point_gradients() {
+        check_gradient(
+            EllipticalArc::start_point,
+            EllipticalArc::start_point_gradient,
+        );
+        check_gradient(EllipticalArc::end_point, EllipticalArc::end_point_gradient);
+    }
+
+    #[test]
+    fn test_elliptical_primitives_serde_round_trip() {
+        let mut sketch = Sketch::new();
+        let center = Rc::new(RefCell::new(Point2::new(1.0, 2.0)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(center.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Ellipse(Rc::new(RefCell::new(Ellipse::new(
+                center.clone(),
+                3.0,
+                1.5,
+                0.7,
+            )))))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::EllipticalArc(Rc::new(RefCell::new(
+                EllipticalArc::new(center, 2.0, 1.0, -0.3, true, 0.4, 2.2),
+            ))))
+            .unwrap();
+
+        let json = serde_json::to_string(&sketch).unwrap();
+        let deserialized: Sketch = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(deserialized.get_data(), sketch.get_data());
+        let primitives = deserialized.primitives();
+        assert_eq!(primitives.len(), 3);
+        for (id, primitive) in sketch.primitives() {
+            assert_eq!(
+                primitives[&id].borrow().to_primitive(),
+                primitive.borrow().to_primitive()
+            );
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use std::cell::RefCell;
+use std::f64::consts::TAU;
+use std::rc::Rc;
+
+use nalgebra::{DVectorView, SMatrix, SMatrixView, SVector, Vector2};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use super::point2::Point2;
+use super::{PrimitiveCell, PrimitiveLike};
+
+// An arc defined by referenced start, end and center points, so it can share its end points with
+// lines and other arcs. Both end points have to be at the same distance from the center, the sketch
+// adds an EndpointArcEqualRadius constraint for this whenever such an arc is added.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct EndpointArc {
+    start: Rc<RefCell<Point2>>,
+    end: Rc<RefCell<Point2>>,
+    center: Rc<RefCell<Point2>>,
+    clockwise: bool,
+    empty: SVector<f64, 0>,
+}
+
+impl EndpointArc {
+    pub fn new(
+        start: Rc<RefCell<Point2>>,
+        end: Rc<RefCell<Point2>>,
+        center: Rc<RefCell<Point2>>,
+        clockwise: bool,
+    ) -> Self {
+        Self {
+            start,
+            end,
+            center,
+            clockwise,
+            empty: SVector::<f64, 0>::zeros(),
+        }
+    }
+
+    pub fn reverse(&self) -> Self {
+        EndpointArc::new(
+            self.end.clone(),
+            self.start.clone(),
+            self.center.clone(),
+            !self.clockwise,
+        )
+    }
+
+    pub fn start(&self) -> Rc<RefCell<Point2>> {
+        self.start.clone()
+    }
+
+    pub fn set_start(&mut self, start: Rc<RefCell<Point2>>) {
+        self.start = start;
+    }
+
+    pub fn start_gradient(&self) -> SMatrix<f64, 2, 6> {
+        SMatrix::<f64, 2, 6>::from_row_slice(&[
+            1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0,
+        ])
+    }
+
+    pub fn end(&self) -> Rc<RefCell<Point2>> {
+        self.end.clone()
+    }
+
+    pub fn set_end(&mut self, end: Rc<RefCell<Point2>>) {
+        self.end = end;
+    }
+
+    pub fn end_gradient(&self) -> SMatrix<f64, 2, 6> {
+        SMatrix::<f64, 2, 6>::from_row_slice(&[
+            0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0,
+        ])
+    }
+
+    pub fn center(&self) -> Rc<RefCell<Point2>> {
+        self.center.clone()
+    }
+
+    pub fn set_center(&mut self, center: Rc<RefCell<Point2>>) {
+        self.center = center;
+    }
+
+    pub fn center_gradient(&self) -> SMatrix<f64, 2, 6> {
+        SMatrix::<f64, 2, 6>::from_row_slice(&[
+            0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+        ])
+    }
+
+    pub fn clockwise(&self) -> bool {
+        self.clockwise
+    }
+
+    pub fn set_clockwise(&mut self, clockwise: bool) {
+        self.clockwise = clockwise;
+    }
+
+    pub fn start_point(&self) -> Vector2<f64> {
+        self.start.borrow().data()
+    }
+
+    pub fn end_point(&self) -> Vector2<f64> {
+        self.end.borrow().data()
+    }
+
+    // The distance from the center to the start point
+    pub fn radius(&self) -> f64 {
+        (self.start_point() - self.center.borrow().data()).norm()
+    }
+
+    pub fn start_angle(&self) -> f64 {
+        let d = self.start_point() - self.center.borrow().data();
+        d.y.atan2(d.x)
+    }
+
+    pub fn end_angle(&self) -> f64 {
+        let d = self.end_point() - self.center.borrow().data();
+        d.y.atan2(d.x)
+    }
+
+    // The angle covered from the start to the end point in the direction of the arc, in [0, 2pi)
+    pub fn sweep_angle(&self) -> f64 {
+        if self.clockwise {
+            (self.start_angle() - self.end_angle()).rem_euclid(TAU)
+        } else {
+            (self.end_angle() - self.start_angle()).rem_euclid(TAU)
+        }
+    }
+
+    pub fn add_to_gradient(&mut self, gradient: SMatrixView<f64, 1, 6>) {
+        // Panic if nan or inf is encountered
+        assert!(gradient.iter().all(|x| x.is_finite()));
+        self.start
+            .borrow_mut()
+            .add_to_gradient(gradient.fixed_view::<1, 2>(0, 0));
+        self.end
+            .borrow_mut()
+            .add_to_gradient(gradient.fixed_view::<1, 2>(0, 2));
+        self.center
+            .borrow_mut()
+            .add_to_gradient(gradient.fixed_view::<1, 2>(0, 4));
+    }
+}
+
+impl PrimitiveLike for EndpointArc {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![
+            PrimitiveCell::Point2(self.start.clone()),
+            PrimitiveCell::Point2(self.end.clone()),
+            PrimitiveCell::Point2(self.center.clone()),
+        ]
+    }
+
+    fn zero_gradient(&mut self) {
+        // Referenced points will zero their gradients automatically as they are part of the sketch
+    }
+
+    fn get_data(&self) -> DVectorView<'_, f64> {
+        // empty vector
+        self.empty.as_view()
+    }
+
+    fn set_data(&mut self, _data: DVectorView<f64>) {
+        // Do nothing
+    }
+
+    fn get_gradient(&self) -> DVectorView<'_, f64> {
+        // empty vector
+        self.empty.as_view()
+    }
+
+    fn to_primitive(&self) -> super::Primitive {
+        super::Primitive::EndpointArc(self.clone())
+    }
+}
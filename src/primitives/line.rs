@@ -76,7 +76,7 @@ impl PrimitiveLike for Line {
         // Referenced points will zero their gradients automatically as they are part of the sketch
     }
 
-    fn get_data(&self) -> DVectorView<f64> {
+    fn get_data(&self) -> DVectorView<'_, f64> {
         // empty vector
         self.empty.as_view()
     }
@@ -85,7 +85,7 @@ impl PrimitiveLike for Line {
         // Do nothing
     }
 
-    fn get_gradient(&self) -> DVectorView<f64> {
+    fn get_gradient(&self) -> DVectorView<'_, f64> {
         // empty vector
         self.empty.as_view()
     }
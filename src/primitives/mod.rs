@@ -10,17 +10,24 @@ use serde::{Deserialize, Serialize};
 use tsify::Tsify;
 
 pub mod arc;
+pub mod bspline;
 pub mod circle;
+pub mod cubic_bezier;
+pub mod ellipse;
+pub mod elliptical_arc;
+pub mod endpoint_arc;
+pub mod infinite_line;
 pub mod line;
 pub mod point2;
+pub mod ray;
 
 // A trait that defines a parametric object, meaning a SketchPrimitive that can be defined by a fixed number of parameters that can be used for gradient descent.
 pub trait PrimitiveLike: Debug {
     fn references(&self) -> Vec<PrimitiveCell>;
     fn zero_gradient(&mut self);
-    fn get_data(&self) -> DVectorView<f64>;
+    fn get_data(&self) -> DVectorView<'_, f64>;
     fn set_data(&mut self, data: DVectorView<f64>);
-    fn get_gradient(&self) -> DVectorView<f64>;
+    fn get_gradient(&self) -> DVectorView<'_, f64>;
     fn to_primitive(&self) -> Primitive;
 }
 
@@ -32,6 +39,13 @@ pub enum Primitive {
     Line(line::Line),
     Arc(arc::Arc),
     Circle(circle::Circle),
+    Ellipse(ellipse::Ellipse),
+    EllipticalArc(elliptical_arc::EllipticalArc),
+    BSpline(bspline::BSpline),
+    CubicBezier(cubic_bezier::CubicBezier),
+    EndpointArc(endpoint_arc::EndpointArc),
+    InfiniteLine(infinite_line::InfiniteLine),
+    Ray(ray::Ray),
 }
 
 impl Primitive {
@@ -41,6 +55,13 @@ impl Primitive {
             Primitive::Line(l) => l,
             Primitive::Arc(a) => a,
             Primitive::Circle(c) => c,
+            Primitive::Ellipse(e) => e,
+            Primitive::EllipticalArc(e) => e,
+            Primitive::BSpline(b) => b,
+            Primitive::CubicBezier(b) => b,
+            Primitive::EndpointArc(a) => a,
+            Primitive::InfiniteLine(l) => l,
+            Primitive::Ray(r) => r,
         }
     }
 }
@@ -53,24 +74,45 @@ pub enum PrimitiveCell {
     Line(Rc<RefCell<line::Line>>),
     Arc(Rc<RefCell<arc::Arc>>),
     Circle(Rc<RefCell<circle::Circle>>),
+    Ellipse(Rc<RefCell<ellipse::Ellipse>>),
+    EllipticalArc(Rc<RefCell<elliptical_arc::EllipticalArc>>),
+    BSpline(Rc<RefCell<bspline::BSpline>>),
+    CubicBezier(Rc<RefCell<cubic_bezier::CubicBezier>>),
+    EndpointArc(Rc<RefCell<endpoint_arc::EndpointArc>>),
+    InfiniteLine(Rc<RefCell<infinite_line::InfiniteLine>>),
+    Ray(Rc<RefCell<ray::Ray>>),
 }
 
 impl PrimitiveCell {
-    pub fn borrow(&self) -> Ref<dyn PrimitiveLike> {
+    pub fn borrow(&self) -> Ref<'_, dyn PrimitiveLike> {
         match self {
             PrimitiveCell::Point2(p) => p.borrow(),
             PrimitiveCell::Line(l) => l.borrow(),
             PrimitiveCell::Arc(a) => a.borrow(),
             PrimitiveCell::Circle(c) => c.borrow(),
+            PrimitiveCell::Ellipse(e) => e.borrow(),
+            PrimitiveCell::EllipticalArc(e) => e.borrow(),
+            PrimitiveCell::BSpline(b) => b.borrow(),
+            PrimitiveCell::CubicBezier(b) => b.borrow(),
+            PrimitiveCell::EndpointArc(a) => a.borrow(),
+            PrimitiveCell::InfiniteLine(l) => l.borrow(),
+            PrimitiveCell::Ray(r) => r.borrow(),
         }
     }
 
-    pub fn borrow_mut(&self) -> RefMut<dyn PrimitiveLike> {
+    pub fn borrow_mut(&self) -> RefMut<'_, dyn PrimitiveLike> {
         match self {
             PrimitiveCell::Point2(p) => p.borrow_mut(),
             PrimitiveCell::Line(l) => l.borrow_mut(),
             PrimitiveCell::Arc(a) => a.borrow_mut(),
             PrimitiveCell::Circle(c) => c.borrow_mut(),
+            PrimitiveCell::Ellipse(e) => e.borrow_mut(),
+            PrimitiveCell::EllipticalArc(e) => e.borrow_mut(),
+            PrimitiveCell::BSpline(b) => b.borrow_mut(),
+            PrimitiveCell::CubicBezier(b) => b.borrow_mut(),
+            PrimitiveCell::EndpointArc(a) => a.borrow_mut(),
+            PrimitiveCell::InfiniteLine(l) => l.borrow_mut(),
+            PrimitiveCell::Ray(r) => r.borrow_mut(),
         }
     }
 
@@ -80,6 +122,13 @@ impl PrimitiveCell {
             PrimitiveCell::Line(l) => l.as_ptr(),
             PrimitiveCell::Arc(a) => a.as_ptr(),
             PrimitiveCell::Circle(c) => c.as_ptr(),
+            PrimitiveCell::Ellipse(e) => e.as_ptr(),
+            PrimitiveCell::EllipticalArc(e) => e.as_ptr(),
+            PrimitiveCell::BSpline(b) => b.as_ptr(),
+            PrimitiveCell::CubicBezier(b) => b.as_ptr(),
+            PrimitiveCell::EndpointArc(a) => a.as_ptr(),
+            PrimitiveCell::InfiniteLine(l) => l.as_ptr(),
+            PrimitiveCell::Ray(r) => r.as_ptr(),
         }
     }
 }
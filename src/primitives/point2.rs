@@ -64,7 +64,7 @@ impl PrimitiveLike for Point2 {
         self.gradient = Vector2::zeros();
     }
 
-    fn get_data(&self) -> DVectorView<f64> {
+    fn get_data(&self) -> DVectorView<'_, f64> {
         self.data.as_view()
     }
 
@@ -73,7 +73,7 @@ impl PrimitiveLike for Point2 {
         self.data = Vector2::from_row_slice(data.as_slice());
     }
 
-    fn get_gradient(&self) -> DVectorView<f64> {
+    fn get_gradient(&self) -> DVectorView<'_, f64> {
         self.gradient.as_view()
     }
 
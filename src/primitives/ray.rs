@@ -0,0 +1,106 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use nalgebra::{DVectorView, SMatrix, SMatrixView, SVector, Vector2};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tsify")]
+use tsify::Tsify;
+
+use super::point2::Point2;
+use super::{PrimitiveCell, PrimitiveLike};
+
+// A half-line starting at a point and going in the direction of an angle.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(Tsify))]
+#[cfg_attr(feature = "tsify", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct Ray {
+    origin: Rc<RefCell<Point2>>,
+    data: SVector<f64, 1>,
+    gradient: SVector<f64, 1>,
+}
+
+impl Ray {
+    pub fn new(origin: Rc<RefCell<Point2>>, angle: f64) -> Self {
+        Self {
+            origin,
+            data: SVector::<f64, 1>::from_row_slice(&[angle]),
+            gradient: SVector::<f64, 1>::zeros(),
+        }
+    }
+
+    pub fn origin(&self) -> Rc<RefCell<Point2>> {
+        self.origin.clone()
+    }
+
+    pub fn set_origin(&mut self, origin: Rc<RefCell<Point2>>) {
+        self.origin = origin;
+    }
+
+    pub fn origin_gradient(&self) -> SMatrix<f64, 2, 3> {
+        SMatrix::<f64, 2, 3>::from_row_slice(&[1.0, 0.0, 0.0, 0.0, 1.0, 0.0])
+    }
+
+    pub fn angle(&self) -> f64 {
+        self.data[0]
+    }
+
+    pub fn set_angle(&mut self, angle: f64) {
+        self.data[0] = angle;
+    }
+
+    pub fn angle_gradient(&self) -> SMatrix<f64, 1, 3> {
+        SMatrix::<f64, 1, 3>::from_row_slice(&[0.0, 0.0, 1.0])
+    }
+
+    // The unit direction vector of the ray
+    pub fn direction(&self) -> Vector2<f64> {
+        Vector2::new(self.angle().cos(), self.angle().sin())
+    }
+
+    pub fn direction_gradient(&self) -> SMatrix<f64, 2, 3> {
+        let angle = self.angle();
+        SMatrix::<f64, 2, 3>::from_row_slice(&[0.0, 0.0, -angle.sin(), 0.0, 0.0, angle.cos()])
+    }
+
+    // The point at the distance t from the origin along the direction, t is expected to be non negative
+    pub fn point_at(&self, t: f64) -> Vector2<f64> {
+        self.origin.borrow().data() + t * self.direction()
+    }
+
+    pub fn add_to_gradient(&mut self, gradient: SMatrixView<f64, 1, 3>) {
+        // Panic if nan or inf is encountered
+        assert!(gradient.iter().all(|x| x.is_finite()));
+        self.origin
+            .borrow_mut()
+            .add_to_gradient(gradient.fixed_view::<1, 2>(0, 0));
+        self.gradient += gradient.fixed_view::<1, 1>(0, 2).transpose();
+    }
+}
+
+impl PrimitiveLike for Ray {
+    fn references(&self) -> Vec<PrimitiveCell> {
+        vec![PrimitiveCell::Point2(self.origin.clone())]
+    }
+
+    fn zero_gradient(&mut self) {
+        self.gradient = SVector::<f64, 1>::zeros();
+    }
+
+    fn get_data(&self) -> DVectorView<'_, f64> {
+        self.data.as_view()
+    }
+
+    fn set_data(&mut self, data: DVectorView<f64>) {
+        assert!(data.iter().all(|x| x.is_finite()));
+        self.data.copy_from(&data);
+    }
+
+    fn get_gradient(&self) -> DVectorView<'_, f64> {
+        self.gradient.as_view()
+    }
+
+    fn to_primitive(&self) -> super::Primitive {
+        super::Primitive::Ray(self.clone())
+    }
+}
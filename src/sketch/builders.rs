@@ -0,0 +1,598 @@
+use std::cell::RefCell;
+use std::f64::consts::{FRAC_PI_2, TAU};
+use std::rc::Rc;
+
+use nalgebra::Vector2;
+
+use crate::constraints::angle_between_points::AngleBetweenPoints;
+use crate::constraints::coincident::point_on_circle::PointOnCircle;
+use crate::constraints::lines::equal_length::EqualLength;
+use crate::constraints::lines::horizontal_line::HorizontalLine;
+use crate::constraints::lines::parallel_lines::ParallelLines;
+use crate::constraints::lines::perpendicular_lines::PerpendicularLines;
+use crate::constraints::lines::vertical_line::VerticalLine;
+use crate::constraints::ConstraintCell;
+use crate::error::ISOTopeError;
+use crate::primitives::circle::Circle;
+use crate::primitives::endpoint_arc::EndpointArc;
+use crate::primitives::line::Line;
+use crate::primitives::point2::Point2;
+use crate::primitives::PrimitiveCell;
+
+use super::Sketch;
+
+// Lines connecting consecutive points, the last line goes back to the first point if it is closed
+#[derive(Debug, Clone)]
+pub struct Polyline {
+    pub points: Vec<Rc<RefCell<Point2>>>,
+    pub lines: Vec<Rc<RefCell<Line>>>,
+}
+
+// The corners and edges of a rectangle in counterclockwise order, starting at the given corner
+#[derive(Debug, Clone)]
+pub struct Rectangle {
+    pub points: [Rc<RefCell<Point2>>; 4],
+    pub lines: [Rc<RefCell<Line>>; 4],
+    pub constraints: Vec<ConstraintCell>,
+}
+
+// The vertices of a regular polygon lie on a construction circle around its center
+#[derive(Debug, Clone)]
+pub struct RegularPolygon {
+    pub center: Rc<RefCell<Point2>>,
+    pub circle: Rc<RefCell<Circle>>,
+    pub points: Vec<Rc<RefCell<Point2>>>,
+    pub lines: Vec<Rc<RefCell<Line>>>,
+    pub constraints: Vec<ConstraintCell>,
+}
+
+// Two parallel lines closed by half circles around the ends of a construction centerline. The
+// points go counterclockwise, starting at the right of the first center when looking along the
+// centerline.
+#[derive(Debug, Clone)]
+pub struct Slot {
+    pub centers: [Rc<RefCell<Point2>>; 2],
+    pub centerline: Rc<RefCell<Line>>,
+    pub points: [Rc<RefCell<Point2>>; 4],
+    pub lines: [Rc<RefCell<Line>>; 2],
+    pub arcs: [Rc<RefCell<EndpointArc>>; 2],
+    pub constraints: Vec<ConstraintCell>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CenterPointArc {
+    pub center: Rc<RefCell<Point2>>,
+    pub start: Rc<RefCell<Point2>>,
+    pub end: Rc<RefCell<Point2>>,
+    pub arc: Rc<RefCell<EndpointArc>>,
+}
+
+impl Sketch {
+    fn add_new_point(
+        &mut self,
+        position: Vector2<f64>,
+    ) -> Result<Rc<RefCell<Point2>>, ISOTopeError> {
+        let point = Rc::new(RefCell::new(Point2::new(position.x, position.y)));
+        self.add_primitive(PrimitiveCell::Point2(point.clone()))?;
+        Ok(point)
+    }
+
+    fn add_new_line(
+        &mut self,
+        start: Rc<RefCell<Point2>>,
+        end: Rc<RefCell<Point2>>,
+        construction: bool,
+    ) -> Result<Rc<RefCell<Line>>, ISOTopeError> {
+        let line = Rc::new(RefCell::new(Line::new(start, end)));
+        self.add_primitive_with_construction(PrimitiveCell::Line(line.clone()), construction)?;
+        Ok(line)
+    }
+
+    fn add_new_constraints(
+        &mut self,
+        constraints: Vec<ConstraintCell>,
+    ) -> Result<Vec<ConstraintCell>, ISOTopeError> {
+        for constraint in constraints.iter() {
+            self.add_constraint(constraint.clone())?;
+        }
+        Ok(constraints)
+    }
+
+    pub fn add_polyline(
+        &mut self,
+        positions: &[Vector2<f64>],
+        closed: bool,
+    ) -> Result<Polyline, ISOTopeError> {
+        if positions.len() < 2 || (closed && positions.len() < 3) {
+            return Err(ISOTopeError::InvalidArgument(
+                "a polyline needs two points, or three if it is closed",
+            ));
+        }
+
+        let points = positions
+            .iter()
+            .map(|position| self.add_new_point(*position))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let num_lines = if closed {
+            points.len()
+        } else {
+            points.len() - 1
+        };
+        let lines = (0..num_lines)
+            .map(|i| {
+                self.add_new_line(
+                    points[i].clone(),
+                    points[(i + 1) % points.len()].clone(),
+                    false,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Polyline { points, lines })
+    }
+
+    // Adds a closed polyline through the four corners, the constraints are left to the caller
+    fn add_quadrilateral(&mut self, corners: [Vector2<f64>; 4]) -> Result<Rectangle, ISOTopeError> {
+        let polyline = self.add_polyline(&corners, true)?;
+        Ok(Rectangle {
+            points: [0, 1, 2, 3].map(|i| polyline.points[i].clone()),
+            lines: [0, 1, 2, 3].map(|i| polyline.lines[i].clone()),
+            constraints: vec![],
+        })
+    }
+
+    // A rectangle with horizontal and vertical edges
+    pub fn add_rectangle(
+        &mut self,
+        corner: Vector2<f64>,
+        width: f64,
+        height: f64,
+    ) -> Result<Rectangle, ISOTopeError> {
+        check_rectangle_arguments(corner, width, height)?;
+        let rectangle = self.add_quadrilateral([
+            corner,
+            corner + Vector2::new(width, 0.0),
+            corner + Vector2::new(width, height),
+            corner + Vector2::new(0.0, height),
+        ])?;
+
+        let [bottom, right, top, left] = rectangle.lines.clone();
+        let constraints = self.add_new_constraints(vec![
+            ConstraintCell::HorizontalLine(Rc::new(RefCell::new(HorizontalLine::new(bottom)))),
+            ConstraintCell::VerticalLine(Rc::new(RefCell::new(VerticalLine::new(right)))),
+            ConstraintCell::HorizontalLine(Rc::new(RefCell::new(HorizontalLine::new(top)))),
+            ConstraintCell::VerticalLine(Rc::new(RefCell::new(VerticalLine::new(left)))),
+        ])?;
+
+        Ok(Rectangle {
+            constraints,
+            ..rectangle
+        })
+    }
+
+    // A rectangle whose first edge starts at the corner and points in the direction of the angle
+    pub fn add_rotated_rectangle(
+        &mut self,
+        corner: Vector2<f64>,
+        width: f64,
+        height: f64,
+        angle: f64,
+    ) -> Result<Rectangle, ISOTopeError> {
+        check_rectangle_arguments(corner, width, height)?;
+        if !angle.is_finite() {
+            return Err(ISOTopeError::InvalidArgument(
+                "the angle of a rectangle must be finite",
+            ));
+        }
+        let u = Vector2::new(angle.cos(), angle.sin());
+        let v = Vector2::new(-angle.sin(), angle.cos());
+        let rectangle = self.add_quadrilateral([
+            corner,
+            corner + width * u,
+            corner + width * u + height * v,
+            corner + height * v,
+        ])?;
+
+        // Three right angles are enough, the fourth one follows from them
+        let constraints = self.add_new_constraints(
+            (0..3)
+                .map(|i| {
+                    ConstraintCell::PerpendicularLines(Rc::new(RefCell::new(
+                        PerpendicularLines::new(
+                            rectangle.lines[i].clone(),
+                            rectangle.lines[i + 1].clone(),
+                        ),
+                    )))
+                })
+                .collect(),
+        )?;
+
+        Ok(Rectangle {
+            constraints,
+            ..rectangle
+        })
+    }
+
+    pub fn add_regular_polygon(
+        &mut self,
+        center: Vector2<f64>,
+        radius: f64,
+        num_sides: usize,
+        angle: f64,
+    ) -> Result<RegularPolygon, ISOTopeError> {
+        if num_sides < 3 {
+            return Err(ISOTopeError::InvalidArgument(
+                "a regular polygon needs at least three sides",
+            ));
+        }
+        if !(radius.is_finite() && radius > 0.0) {
+            return Err(ISOTopeError::InvalidArgument(
+                "the radius of a regular polygon must be positive",
+            ));
+        }
+
+        let center_point = self.add_new_point(center)?;
+        let circle = Rc::new(RefCell::new(Circle::new(center_point.clone(), radius)));
+        self.add_primitive_with_construction(PrimitiveCell::Circle(circle.clone()), true)?;
+
+        let positions = (0..num_sides)
+            .map(|i| {
+                let vertex_angle = angle + TAU * i as f64 / num_sides as f64;
+                center + radius * Vector2::new(vertex_angle.cos(), vertex_angle.sin())
+            })
+            .collect::<Vec<_>>();
+        let Polyline { points, lines } = self.add_polyline(&positions, true)?;
+
+        // All vertices on the circle and all edges of the same length
+        let mut constraints = points
+            .iter()
+            .map(|point| {
+                ConstraintCell::PointOnCircle(Rc::new(RefCell::new(PointOnCircle::new(
+                    point.clone(),
+                    circle.clone(),
+                ))))
+            })
+            .collect::<Vec<_>>();
+        constraints.extend(lines.windows(2).map(|pair| {
+            ConstraintCell::EqualLength(Rc::new(RefCell::new(EqualLength::new(
+                pair[0].clone(),
+                pair[1].clone(),
+            ))))
+        }));
+        let constraints = self.add_new_constraints(constraints)?;
+
+        Ok(RegularPolygon {
+            center: center_point,
+            circle,
+            points,
+            lines,
+            constraints,
+        })
+    }
+
+    pub fn add_slot(
+        &mut self,
+        center1: Vector2<f64>,
+        center2: Vector2<f64>,
+        radius: f64,
+    ) -> Result<Slot, ISOTopeError> {
+        if !(radius.is_finite() && radius > 0.0) {
+            return Err(ISOTopeError::InvalidArgument(
+                "the radius of a slot must be positive",
+            ));
+        }
+        let direction = center2 - center1;
+        if direction.norm() <= 1e-6 {
+            return Err(ISOTopeError::InvalidArgument(
+                "the centers of a slot must not coincide",
+            ));
+        }
+        // Pointing to the left of the centerline
+        let normal = Vector2::new(-direction.y, direction.x).normalize();
+
+        let centers = [self.add_new_point(center1)?, self.add_new_point(center2)?];
+        let centerline = self.add_new_line(centers[0].clone(), centers[1].clone(), true)?;
+
+        let points = [
+            self.add_new_point(center1 - radius * normal)?,
+            self.add_new_point(center2 - radius * normal)?,
+            self.add_new_point(center2 + radius * normal)?,
+            self.add_new_point(center1 + radius * normal)?,
+        ];
+        let lines = [
+            self.add_new_line(points[0].clone(), points[1].clone(), false)?,
+            self.add_new_line(points[2].clone(), points[3].clone(), false)?,
+        ];
+        let arcs = [
+            Rc::new(RefCell::new(EndpointArc::new(
+                points[3].clone(),
+                points[0].clone(),
+                centers[0].clone(),
+                false,
+            ))),
+            Rc::new(RefCell::new(EndpointArc::new(
+                points[1].clone(),
+                points[2].clone(),
+                centers[1].clone(),
+                false,
+            ))),
+        ];
+        for arc in arcs.iter() {
+            self.add_primitive(PrimitiveCell::EndpointArc(arc.clone()))?;
+        }
+
+        // The end points of the arcs are perpendicular to the centerline as seen from their centers,
+        // which makes the arcs half circles tangent to the lines once the lines are parallel to the
+        // centerline
+        let mut constraints = [(0, 0, 1), (3, 0, 1), (1, 1, 0), (2, 1, 0)]
+            .iter()
+            .map(|&(point, center, other_center)| {
+                ConstraintCell::AngleBetweenPoints(Rc::new(RefCell::new(AngleBetweenPoints::new(
+                    points[point].clone(),
+                    centers[other_center].clone(),
+                    centers[center].clone(),
+                    FRAC_PI_2,
+                ))))
+            })
+            .collect::<Vec<_>>();
+        constraints.push(ConstraintCell::ParallelLines(Rc::new(RefCell::new(
+            ParallelLines::new(lines[0].clone(), centerline.clone()),
+        ))));
+        let constraints = self.add_new_constraints(constraints)?;
+
+        Ok(Slot {
+            centers,
+            centerline,
+            points,
+            lines,
+            arcs,
+            constraints,
+        })
+    }
+
+    // An arc around the center from the start position to the direction of the end position. The
+    // end point is placed at the distance of the start point from the center.
+    pub fn add_center_point_arc(
+        &mut self,
+        center: Vector2<f64>,
+        start: Vector2<f64>,
+        end: Vector2<f64>,
+        clockwise: bool,
+    ) -> Result<CenterPointArc, ISOTopeError> {
+        let radius = (start - center).norm();
+        if !(radius > 1e-6 && (end - center).norm() > 1e-6) {
+            return Err(ISOTopeError::InvalidArgument(
+                "the start and end of an arc must not coincide with its center",
+            ));
+        }
+
+        let center_point = self.add_new_point(center)?;
+        let start_point = self.add_new_point(start)?;
+        let end_point = self.add_new_point(center + radius * (end - center).normalize())?;
+        let arc = Rc::new(RefCell::new(EndpointArc::new(
+            start_point.clone(),
+            end_point.clone(),
+            center_point.clone(),
+            clockwise,
+        )));
+        // The sketch adds the equal radius constraint of the arc
+        self.add_primitive(PrimitiveCell::EndpointArc(arc.clone()))?;
+
+        Ok(CenterPointArc {
+            center: center_point,
+            start: start_point,
+            end: end_point,
+            arc,
+        })
+    }
+}
+
+// Rectangles need a finite corner and finite sizes that are not zero, negative sizes flip them
+fn check_rectangle_arguments(
+    corner: Vector2<f64>,
+    width: f64,
+    height: f64,
+) -> Result<(), ISOTopeError> {
+    if !corner.iter().all(|x| x.is_finite()) {
+        return Err(ISOTopeError::InvalidArgument(
+            "the corner of a rectangle must be finite",
+        ));
+    }
+    if !(width.is_finite() && height.is_finite() && width != 0.0 && height != 0.0) {
+        return Err(ISOTopeError::InvalidArgument(
+            "the width and height of a rectangle must be finite and not zero",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector2;
+
+    use crate::{
+        decompose::find_rings,
+        error::ISOTopeError,
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    #[test]
+    fn test_polyline() {
+        let mut sketch = Sketch::new();
+        let positions = [
+            Vector2::new(0.0, 0.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(1.0, 1.0),
+        ];
+
+        let open = sketch.add_polyline(&positions, false).unwrap();
+        assert_eq!(open.points.len(), 3);
+        assert_eq!(open.lines.len(), 2);
+        assert!(find_rings(&sketch).0.is_empty());
+
+        let closed = sketch.add_polyline(&positions, true).unwrap();
+        assert_eq!(closed.lines.len(), 3);
+        assert!(std::rc::Rc::ptr_eq(
+            &closed.lines[2].borrow().end(),
+            &closed.points[0]
+        ));
+        assert_eq!(find_rings(&sketch).0.len(), 1);
+        assert_eq!(sketch.get_num_constraints(), 0);
+    }
+
+    #[test]
+    fn test_rectangles() {
+        let mut sketch = Sketch::new();
+        let rectangle = sketch
+            .add_rectangle(Vector2::new(1.0, 1.0), 2.0, 1.0)
+            .unwrap();
+        let rotated = sketch
+            .add_rotated_rectangle(Vector2::new(5.0, 0.0), 2.0, 1.0, 0.5)
+            .unwrap();
+        assert_eq!(sketch.get_num_constraints(), 7);
+
+        // Disturb one corner of each rectangle and solve again
+        rectangle.points[2].borrow_mut().set_x(3.5);
+        rotated.points[2].borrow_mut().set_y(3.0);
+        BFGSSolver::new().solve(&mut sketch).unwrap();
+
+        for constraint in sketch.constraints() {
+            assert!(constraint.borrow().loss_value() < 1e-6);
+        }
+        let [a, b, c, d] = rotated.points.clone().map(|p| p.borrow().data());
+        assert!((b - a).dot(&(c - b)).abs() < 1e-2);
+        assert!(((c - b) - (d - a)).norm() < 1e-2);
+    }
+
+    #[test]
+    fn test_regular_polygon() {
+        let mut sketch = Sketch::new();
+        let polygon = sketch
+            .add_regular_polygon(Vector2::new(1.0, 2.0), 3.0, 6, 0.1)
+            .unwrap();
+        assert_eq!(polygon.points.len(), 6);
+        assert_eq!(polygon.lines.len(), 6);
+        assert_eq!(polygon.constraints.len(), 6 + 5);
+        for constraint in polygon.constraints.iter() {
+            assert!(constraint.borrow().loss_value() < 1e-12);
+        }
+
+        // The construction circle is no face of its own
+        let (rings, unused_segments) = find_rings(&sketch);
+        assert_eq!(rings.len(), 1);
+        assert!(unused_segments.is_empty());
+
+        polygon.points[0].borrow_mut().set_x(5.0);
+        BFGSSolver::new().solve(&mut sketch).unwrap();
+        let lengths = polygon
+            .lines
+            .iter()
+            .map(|l| (l.borrow().end().borrow().data() - l.borrow().start().borrow().data()).norm())
+            .collect::<Vec<_>>();
+        for length in lengths.iter() {
+            assert!((length - lengths[0]).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_slot() {
+        let mut sketch = Sketch::new();
+        let slot = sketch
+            .add_slot(Vector2::new(0.0, 0.0), Vector2::new(3.0, 1.0), 0.5)
+            .unwrap();
+        // Four right angles, one parallel constraint and the radius constraints of the arcs
+        assert_eq!(slot.constraints.len(), 5);
+        assert_eq!(sketch.get_num_constraints(), 7);
+        for constraint in sketch.constraints() {
+            assert!(constraint.borrow().loss_value() < 1e-12);
+        }
+
+        let (rings, unused_segments) = find_rings(&sketch);
+        assert_eq!(rings.len(), 1);
+        assert!(unused_segments.is_empty());
+
+        slot.points[2].borrow_mut().set_y(2.5);
+        BFGSSolver::new().solve(&mut sketch).unwrap();
+        for constraint in sketch.constraints() {
+            assert!(constraint.borrow().loss_value() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_center_point_arc() {
+        let mut sketch = Sketch::new();
+        let arc = sketch
+            .add_center_point_arc(
+                Vector2::new(1.0, 1.0),
+                Vector2::new(3.0, 1.0),
+                Vector2::new(1.0, 5.0),
+                false,
+            )
+            .unwrap();
+
+        assert_eq!(arc.end.borrow().data(), Vector2::new(1.0, 3.0));
+        assert_eq!(arc.arc.borrow().radius(), 2.0);
+        assert!((arc.arc.borrow().sweep_angle() - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+        assert_eq!(sketch.get_num_constraints(), 1);
+    }
+
+    #[test]
+    fn test_invalid_builder_arguments() {
+        let mut sketch = Sketch::new();
+        let origin = Vector2::new(0.0, 0.0);
+        let position = Vector2::new(1.0, 0.0);
+        assert!(matches!(
+            sketch.add_polyline(&[origin], false),
+            Err(ISOTopeError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            sketch.add_polyline(&[origin, position], true),
+            Err(ISOTopeError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            sketch.add_regular_polygon(origin, 1.0, 2, 0.0),
+            Err(ISOTopeError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            sketch.add_regular_polygon(origin, -1.0, 6, 0.0),
+            Err(ISOTopeError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            sketch.add_slot(origin, position, 0.0),
+            Err(ISOTopeError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            sketch.add_slot(origin, origin, 1.0),
+            Err(ISOTopeError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            sketch.add_center_point_arc(origin, origin, position, false),
+            Err(ISOTopeError::InvalidArgument(_))
+        ));
+
+        for (corner, width, height) in [
+            (origin, 0.0, 1.0),
+            (origin, 1.0, f64::NAN),
+            (Vector2::new(f64::INFINITY, 0.0), 1.0, 1.0),
+        ] {
+            assert!(matches!(
+                sketch.add_rectangle(corner, width, height),
+                Err(ISOTopeError::InvalidArgument(_))
+            ));
+            assert!(matches!(
+                sketch.add_rotated_rectangle(corner, width, height, 0.3),
+                Err(ISOTopeError::InvalidArgument(_))
+            ));
+        }
+        assert!(matches!(
+            sketch.add_rotated_rectangle(origin, 1.0, 1.0, f64::NAN),
+            Err(ISOTopeError::InvalidArgument(_))
+        ));
+
+        // Nothing is added to the sketch
+        assert!(sketch.primitives().is_empty());
+        assert_eq!(sketch.get_num_constraints(), 0);
+    }
+}
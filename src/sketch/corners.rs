@@ -0,0 +1,620 @@
+use std::cell::RefCell;
+use std::f64::consts::FRAC_PI_2;
+use std::rc::Rc;
+
+use nalgebra::Vector2;
+
+use crate::constraints::angle_between_points::AngleBetweenPoints;
+use crate::constraints::coincident::point_on_line::PointOnLine;
+use crate::constraints::distance::euclidian_distance_between_points::EuclidianDistanceBetweenPoints;
+use crate::constraints::lines::points_collinear::PointsCollinear;
+use crate::constraints::ConstraintCell;
+use crate::error::ISOTopeError;
+use crate::intersections::{circle_circle_intersections, line_circle_intersections};
+use crate::primitives::endpoint_arc::EndpointArc;
+use crate::primitives::line::Line;
+use crate::primitives::point2::Point2;
+use crate::primitives::PrimitiveCell;
+
+use super::Sketch;
+
+// The arc that rounds a corner and the points where it meets the primitives of the corner
+#[derive(Debug, Clone)]
+pub struct Fillet {
+    pub center: Rc<RefCell<Point2>>,
+    pub points: [Rc<RefCell<Point2>>; 2],
+    pub arc: Rc<RefCell<EndpointArc>>,
+    pub constraints: Vec<ConstraintCell>,
+}
+
+// The line that cuts off a corner and the points where it meets the primitives of the corner
+#[derive(Debug, Clone)]
+pub struct Chamfer {
+    pub points: [Rc<RefCell<Point2>>; 2],
+    pub line: Rc<RefCell<Line>>,
+    pub constraints: Vec<ConstraintCell>,
+}
+
+// One of the two primitives meeting at a corner
+#[derive(Debug, Clone)]
+enum CornerEdge {
+    Line(Rc<RefCell<Line>>),
+    Arc(Rc<RefCell<EndpointArc>>),
+}
+
+impl CornerEdge {
+    // The unit direction in which the edge leaves the corner
+    fn direction(&self, corner: &Rc<RefCell<Point2>>) -> Vector2<f64> {
+        let corner_position = corner.borrow().data();
+        match self {
+            CornerEdge::Line(line) => {
+                let line = line.borrow();
+                let other = if Rc::ptr_eq(&line.start(), corner) {
+                    line.end()
+                } else {
+                    line.start()
+                };
+                let other_position = other.borrow().data();
+                (other_position - corner_position).normalize()
+            }
+            CornerEdge::Arc(arc) => {
+                let arc = arc.borrow();
+                let radial = corner_position - arc.center().borrow().data();
+                let counterclockwise = Vector2::new(-radial.y, radial.x).normalize();
+                // Leaving from the start follows the arc, leaving from the end goes against it
+                let at_start = Rc::ptr_eq(&arc.start(), corner);
+                if at_start != arc.clockwise() {
+                    counterclockwise
+                } else {
+                    -counterclockwise
+                }
+            }
+        }
+    }
+
+    // The length of a line, the edge can't be cut back further than that
+    fn max_cut_back(&self) -> f64 {
+        match self {
+            CornerEdge::Line(line) => {
+                let line = line.borrow();
+                let start = line.start().borrow().data();
+                let end = line.end().borrow().data();
+                (end - start).norm()
+            }
+            CornerEdge::Arc(arc) => {
+                let arc = arc.borrow();
+                arc.radius() * arc.sweep_angle()
+            }
+        }
+    }
+
+    // Lets the edge end at the new point instead of the corner
+    fn replace_corner(&self, corner: &Rc<RefCell<Point2>>, point: Rc<RefCell<Point2>>) {
+        match self {
+            CornerEdge::Line(line) => {
+                if Rc::ptr_eq(&line.borrow().start(), corner) {
+                    line.borrow_mut().set_start(point);
+                } else {
+                    line.borrow_mut().set_end(point);
+                }
+            }
+            CornerEdge::Arc(arc) => {
+                if Rc::ptr_eq(&arc.borrow().start(), corner) {
+                    arc.borrow_mut().set_start(point);
+                } else {
+                    arc.borrow_mut().set_end(point);
+                }
+            }
+        }
+    }
+}
+
+impl Sketch {
+    // Finds the line and the line or arc that meet at the corner, the line always comes first
+    fn corner_edges(
+        &self,
+        corner: &Rc<RefCell<Point2>>,
+    ) -> Result<(Rc<RefCell<Line>>, CornerEdge), ISOTopeError> {
+        let mut edges = self
+            .non_construction_primitives()
+            .values()
+            .filter_map(|primitive| match primitive {
+                PrimitiveCell::Line(line)
+                    if Rc::ptr_eq(&line.borrow().start(), corner)
+                        || Rc::ptr_eq(&line.borrow().end(), corner) =>
+                {
+                    Some(CornerEdge::Line(line.clone()))
+                }
+                PrimitiveCell::EndpointArc(arc)
+                    if Rc::ptr_eq(&arc.borrow().start(), corner)
+                        || Rc::ptr_eq(&arc.borrow().end(), corner) =>
+                {
+                    Some(CornerEdge::Arc(arc.clone()))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if edges.len() != 2 {
+            return Err(ISOTopeError::NotACorner);
+        }
+        if let CornerEdge::Arc(_) = edges[0] {
+            edges.swap(0, 1);
+        }
+        let second = edges.remove(1);
+        match edges.remove(0) {
+            CornerEdge::Line(line) => Ok((line, second)),
+            CornerEdge::Arc(_) => Err(ISOTopeError::NotACorner),
+        }
+    }
+
+    fn add_corner_point(
+        &mut self,
+        position: Vector2<f64>,
+    ) -> Result<Rc<RefCell<Point2>>, ISOTopeError> {
+        let point = Rc::new(RefCell::new(Point2::new(position.x, position.y)));
+        self.add_primitive(PrimitiveCell::Point2(point.clone()))?;
+        Ok(point)
+    }
+
+    // Keeps the corner as the intersection of the two edges after they have been cut back. For an
+    // arc the cut off part stays as construction geometry, which keeps the corner on its circle.
+    fn keep_corner(
+        &mut self,
+        corner: &Rc<RefCell<Point2>>,
+        line: &Rc<RefCell<Line>>,
+        edge: &CornerEdge,
+        edge_point: &Rc<RefCell<Point2>>,
+    ) -> Result<Vec<ConstraintCell>, ISOTopeError> {
+        let mut constraints = vec![ConstraintCell::PointOnLine(Rc::new(RefCell::new(
+            PointOnLine::new(corner.clone(), line.clone()),
+        )))];
+        match edge {
+            CornerEdge::Line(other) => {
+                constraints.push(ConstraintCell::PointOnLine(Rc::new(RefCell::new(
+                    PointOnLine::new(corner.clone(), other.clone()),
+                ))));
+            }
+            CornerEdge::Arc(arc) => {
+                let (center, clockwise, at_start) = {
+                    let arc = arc.borrow();
+                    (
+                        arc.center(),
+                        arc.clockwise(),
+                        Rc::ptr_eq(&arc.start(), edge_point),
+                    )
+                };
+                let cut_off = if at_start {
+                    EndpointArc::new(corner.clone(), edge_point.clone(), center, clockwise)
+                } else {
+                    EndpointArc::new(edge_point.clone(), corner.clone(), center, clockwise)
+                };
+                self.add_primitive_with_construction(
+                    PrimitiveCell::EndpointArc(Rc::new(RefCell::new(cut_off))),
+                    true,
+                )?;
+            }
+        }
+        Ok(constraints)
+    }
+
+    fn add_corner_constraints(
+        &mut self,
+        constraints: Vec<ConstraintCell>,
+    ) -> Result<Vec<ConstraintCell>, ISOTopeError> {
+        for constraint in constraints.iter() {
+            self.add_constraint(constraint.clone())?;
+        }
+        Ok(constraints)
+    }
+
+    // Rounds the corner between two lines or a line and an arc with an arc of the given radius
+    pub fn fillet(
+        &mut self,
+        corner: &Rc<RefCell<Point2>>,
+        radius: f64,
+    ) -> Result<Fillet, ISOTopeError> {
+        if !(radius.is_finite() && radius > 0.0) {
+            return Err(ISOTopeError::InvalidArgument(
+                "the fillet radius must be positive",
+            ));
+        }
+        let (line, edge) = self.corner_edges(corner)?;
+        let line_edge = CornerEdge::Line(line.clone());
+
+        let corner_position = corner.borrow().data();
+        let d1 = line_edge.direction(corner);
+        let d2 = edge.direction(corner);
+        let half_angle = 0.5 * d1.dot(&d2).clamp(-1.0, 1.0).acos();
+        if half_angle < 1e-6 || FRAC_PI_2 - half_angle < 1e-6 {
+            return Err(ISOTopeError::NotACorner);
+        }
+
+        // The fillet between the line and the tangent of the edge at the corner
+        let cut_back = radius / half_angle.tan();
+        let mut center = corner_position + radius / half_angle.sin() * (d1 + d2).normalize();
+        let mut point2 = corner_position + cut_back * d2;
+        if let CornerEdge::Arc(arc) = &edge {
+            // Move the center along the offset line until it has the right distance to the arc
+            let arc_center = arc.borrow().center().borrow().data();
+            let arc_radius = arc.borrow().radius();
+            let normal = Vector2::new(-d1.y, d1.x);
+            let base = corner_position
+                + radius * normal * normal.dot(&(center - corner_position)).signum();
+            center = [arc_radius + radius, (arc_radius - radius).abs()]
+                .iter()
+                .flat_map(|distance| line_circle_intersections(base, d1, arc_center, *distance))
+                .min_by(|a, b| (a - center).norm().total_cmp(&(b - center).norm()))
+                .ok_or(ISOTopeError::CornerOperationDoesNotFit)?;
+            let radial = (center - arc_center).normalize();
+            point2 = [
+                arc_center + arc_radius * radial,
+                arc_center - arc_radius * radial,
+            ]
+            .into_iter()
+            .min_by(|a, b| {
+                ((a - center).norm() - radius)
+                    .abs()
+                    .total_cmp(&((b - center).norm() - radius).abs())
+            })
+            .ok_or(ISOTopeError::CornerOperationDoesNotFit)?;
+        }
+        let point1 = corner_position + d1.dot(&(center - corner_position)) * d1;
+
+        let cut_back1 = (point1 - corner_position).norm();
+        let cut_back2 = (point2 - corner_position).norm();
+        if d1.dot(&(point1 - corner_position)) <= 0.0
+            || cut_back1 >= line_edge.max_cut_back()
+            || cut_back2 >= edge.max_cut_back()
+        {
+            return Err(ISOTopeError::CornerOperationDoesNotFit);
+        }
+
+        let center_point = self.add_corner_point(center)?;
+        let points = [
+            self.add_corner_point(point1)?,
+            self.add_corner_point(point2)?,
+        ];
+        line_edge.replace_corner(corner, points[0].clone());
+        edge.replace_corner(corner, points[1].clone());
+
+        let clockwise = {
+            let r1 = point1 - center;
+            let r2 = point2 - center;
+            r1.x * r2.y - r1.y * r2.x < 0.0
+        };
+        let arc = Rc::new(RefCell::new(EndpointArc::new(
+            points[0].clone(),
+            points[1].clone(),
+            center_point.clone(),
+            clockwise,
+        )));
+        self.add_primitive(PrimitiveCell::EndpointArc(arc.clone()))?;
+
+        let far_point = {
+            let line = line.borrow();
+            if Rc::ptr_eq(&line.start(), &points[0]) {
+                line.end()
+            } else {
+                line.start()
+            }
+        };
+        // The radius of the fillet is perpendicular to the line where they meet
+        let mut constraints = vec![
+            ConstraintCell::EuclideanDistance(Rc::new(RefCell::new(
+                EuclidianDistanceBetweenPoints::new(
+                    center_point.clone(),
+                    points[0].clone(),
+                    radius,
+                ),
+            ))),
+            ConstraintCell::AngleBetweenPoints(Rc::new(RefCell::new(AngleBetweenPoints::new(
+                far_point,
+                center_point.clone(),
+                points[0].clone(),
+                FRAC_PI_2,
+            )))),
+        ];
+        match &edge {
+            CornerEdge::Line(other) => {
+                let far_point = {
+                    let other = other.borrow();
+                    if Rc::ptr_eq(&other.start(), &points[1]) {
+                        other.end()
+                    } else {
+                        other.start()
+                    }
+                };
+                constraints.push(ConstraintCell::AngleBetweenPoints(Rc::new(RefCell::new(
+                    AngleBetweenPoints::new(
+                        far_point,
+                        center_point.clone(),
+                        points[1].clone(),
+                        FRAC_PI_2,
+                    ),
+                ))));
+            }
+            CornerEdge::Arc(arc) => {
+                // Tangent circles touch on the line through their centers
+                constraints.push(ConstraintCell::PointsCollinear(Rc::new(RefCell::new(
+                    PointsCollinear::new(vec![
+                        center_point.clone(),
+                        arc.borrow().center(),
+                        points[1].clone(),
+                    ]),
+                ))));
+            }
+        }
+        constraints.extend(self.keep_corner(corner, &line, &edge, &points[1])?);
+        let constraints = self.add_corner_constraints(constraints)?;
+
+        Ok(Fillet {
+            center: center_point,
+            points,
+            arc,
+            constraints,
+        })
+    }
+
+    // Cuts off the corner between two lines or a line and an arc with a line. The distances are
+    // measured from the corner along the line and to the point on the other primitive, the second
+    // one defaults to the first.
+    pub fn chamfer(
+        &mut self,
+        corner: &Rc<RefCell<Point2>>,
+        distance1: f64,
+        distance2: Option<f64>,
+    ) -> Result<Chamfer, ISOTopeError> {
+        let distance2 = distance2.unwrap_or(distance1);
+        if !(distance1.is_finite() && distance1 > 0.0 && distance2.is_finite() && distance2 > 0.0) {
+            return Err(ISOTopeError::InvalidArgument(
+                "the chamfer distances must be positive",
+            ));
+        }
+        let (line, edge) = self.corner_edges(corner)?;
+        let line_edge = CornerEdge::Line(line.clone());
+
+        let corner_position = corner.borrow().data();
+        let d1 = line_edge.direction(corner);
+        let d2 = edge.direction(corner);
+        let point1 = corner_position + distance1 * d1;
+        let mut point2 = corner_position + distance2 * d2;
+        if let CornerEdge::Arc(arc) = &edge {
+            // The point on the arc at the given distance from the corner
+            let arc_center = arc.borrow().center().borrow().data();
+            let arc_radius = arc.borrow().radius();
+            point2 =
+                circle_circle_intersections(corner_position, distance2, arc_center, arc_radius)
+                    .into_iter()
+                    .min_by(|a, b| (a - point2).norm().total_cmp(&(b - point2).norm()))
+                    .ok_or(ISOTopeError::CornerOperationDoesNotFit)?;
+        }
+        if distance1 >= line_edge.max_cut_back() || distance2 >= edge.max_cut_back() {
+            return Err(ISOTopeError::CornerOperationDoesNotFit);
+        }
+
+        let points = [
+            self.add_corner_point(point1)?,
+            self.add_corner_point(point2)?,
+        ];
+        line_edge.replace_corner(corner, points[0].clone());
+        edge.replace_corner(corner, points[1].clone());
+
+        let chamfer_line = Rc::new(RefCell::new(Line::new(
+            points[0].clone(),
+            points[1].clone(),
+        )));
+        self.add_primitive(PrimitiveCell::Line(chamfer_line.clone()))?;
+
+        let mut constraints = vec![
+            ConstraintCell::EuclideanDistance(Rc::new(RefCell::new(
+                EuclidianDistanceBetweenPoints::new(corner.clone(), points[0].clone(), distance1),
+            ))),
+            ConstraintCell::EuclideanDistance(Rc::new(RefCell::new(
+                EuclidianDistanceBetweenPoints::new(corner.clone(), points[1].clone(), distance2),
+            ))),
+        ];
+        constraints.extend(self.keep_corner(corner, &line, &edge, &points[1])?);
+        let constraints = self.add_corner_constraints(constraints)?;
+
+        Ok(Chamfer {
+            points,
+            line: chamfer_line,
+            constraints,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use nalgebra::Vector2;
+
+    use crate::{
+        decompose::{find_rings, ring::Ring, segment::Segment},
+        error::ISOTopeError,
+        primitives::{endpoint_arc::EndpointArc, line::Line, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    fn assert_solved(sketch: &Sketch, tolerance: f64) {
+        for constraint in sketch.constraints() {
+            assert!(constraint.borrow().loss_value() < tolerance);
+        }
+    }
+
+    fn ring_segments(sketch: &Sketch) -> Vec<Segment> {
+        let (rings, unused_segments) = find_rings(sketch);
+        assert_eq!(rings.len(), 1);
+        assert!(unused_segments.is_empty());
+        match &rings[0] {
+            Ring::Segments(segments) => segments.clone(),
+            _ => panic!("expected a ring of segments"),
+        }
+    }
+
+    #[test]
+    fn test_fillet_lines() {
+        let mut sketch = Sketch::new();
+        let rectangle = sketch
+            .add_rectangle(Vector2::new(0.0, 0.0), 2.0, 1.0)
+            .unwrap();
+        let corner = rectangle.points[2].clone();
+
+        let fillet = sketch.fillet(&corner, 0.3).unwrap();
+        assert_solved(&sketch, 1e-12);
+        assert!((fillet.points[0].borrow().data() - Vector2::new(2.0, 0.7)).norm() < 1e-12);
+        assert!((fillet.points[1].borrow().data() - Vector2::new(1.7, 1.0)).norm() < 1e-12);
+        assert!((fillet.center.borrow().data() - Vector2::new(1.7, 0.7)).norm() < 1e-12);
+        assert!(!fillet.arc.borrow().clockwise());
+
+        let segments = ring_segments(&sketch);
+        assert_eq!(segments.len(), 5);
+        assert!(segments.contains(&Segment::EndpointArc(fillet.arc.borrow().clone())));
+
+        // The fillet follows when the rectangle changes
+        corner.borrow_mut().set_x(3.0);
+        corner.borrow_mut().set_y(1.5);
+        BFGSSolver::new().solve(&mut sketch).unwrap();
+        assert_solved(&sketch, 1e-6);
+        assert!((fillet.arc.borrow().radius() - 0.3).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_chamfer_lines() {
+        let mut sketch = Sketch::new();
+        let rectangle = sketch
+            .add_rectangle(Vector2::new(0.0, 0.0), 2.0, 1.0)
+            .unwrap();
+        let corner = rectangle.points[0].clone();
+
+        let chamfer = sketch.chamfer(&corner, 0.2, Some(0.4)).unwrap();
+        assert_solved(&sketch, 1e-12);
+        assert!((chamfer.points[0].borrow().data() - Vector2::new(0.2, 0.0)).norm() < 1e-12);
+        assert!((chamfer.points[1].borrow().data() - Vector2::new(0.0, 0.4)).norm() < 1e-12);
+        assert_eq!(ring_segments(&sketch).len(), 5);
+
+        rectangle.points[2].borrow_mut().set_x(4.0);
+        BFGSSolver::new().solve(&mut sketch).unwrap();
+        assert_solved(&sketch, 1e-6);
+    }
+
+    #[test]
+    fn test_fillet_and_chamfer_line_and_arc() {
+        for use_fillet in [true, false] {
+            let mut sketch = Sketch::new();
+            let polyline = sketch
+                .add_polyline(
+                    &[
+                        Vector2::new(2.0, 0.0),
+                        Vector2::new(2.0, 2.0),
+                        Vector2::new(-2.0, 2.0),
+                        Vector2::new(-2.0, 0.0),
+                        Vector2::new(0.0, 0.0),
+                    ],
+                    false,
+                )
+                .unwrap();
+            let corner = polyline.points[4].clone();
+            let center = Rc::new(RefCell::new(Point2::new(1.0, 0.0)));
+            sketch
+                .add_primitive(PrimitiveCell::Point2(center.clone()))
+                .unwrap();
+            // A half circle below the x axis from the corner to the first point of the polyline
+            let arc = Rc::new(RefCell::new(EndpointArc::new(
+                corner.clone(),
+                polyline.points[0].clone(),
+                center.clone(),
+                false,
+            )));
+            sketch
+                .add_primitive(PrimitiveCell::EndpointArc(arc.clone()))
+                .unwrap();
+
+            let (points, added) = if use_fillet {
+                let fillet = sketch.fillet(&corner, 0.2).unwrap();
+                assert!(
+                    (fillet.center.borrow().data() - Vector2::new(1.0 - 1.4f64.sqrt(), -0.2))
+                        .norm()
+                        < 1e-12
+                );
+                (fillet.points, PrimitiveCell::EndpointArc(fillet.arc))
+            } else {
+                let chamfer = sketch.chamfer(&corner, 0.3, None).unwrap();
+                (chamfer.points, PrimitiveCell::Line(chamfer.line))
+            };
+            assert_solved(&sketch, 1e-12);
+            assert!(Rc::ptr_eq(&arc.borrow().start(), &points[1]));
+            assert!((arc.borrow().radius() - 1.0).abs() < 1e-12);
+            assert!(sketch.get_primitive_id(&added).is_some());
+            // The cut off part of the arc is construction geometry
+            assert_eq!(ring_segments(&sketch).len(), 6);
+
+            polyline.points[2].borrow_mut().set_y(3.0);
+            center.borrow_mut().set_x(1.2);
+            BFGSSolver::new().solve(&mut sketch).unwrap();
+            assert_solved(&sketch, 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_not_a_corner() {
+        let mut sketch = Sketch::new();
+        let start = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let end = Rc::new(RefCell::new(Point2::new(1.0, 0.0)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(start.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Point2(end.clone()))
+            .unwrap();
+        let line = Rc::new(RefCell::new(Line::new(start.clone(), end.clone())));
+        sketch.add_primitive(PrimitiveCell::Line(line)).unwrap();
+
+        assert!(matches!(
+            sketch.fillet(&end, 0.1),
+            Err(ISOTopeError::NotACorner)
+        ));
+        assert!(matches!(
+            sketch.chamfer(&end, 0.1, None),
+            Err(ISOTopeError::NotACorner)
+        ));
+    }
+
+    #[test]
+    fn test_fillet_does_not_fit() {
+        let mut sketch = Sketch::new();
+        let rectangle = sketch
+            .add_rectangle(Vector2::new(0.0, 0.0), 2.0, 1.0)
+            .unwrap();
+        assert!(matches!(
+            sketch.fillet(&rectangle.points[1], 1.5),
+            Err(ISOTopeError::CornerOperationDoesNotFit)
+        ));
+    }
+
+    #[test]
+    fn test_invalid_corner_arguments() {
+        let mut sketch = Sketch::new();
+        let rectangle = sketch
+            .add_rectangle(Vector2::new(0.0, 0.0), 2.0, 1.0)
+            .unwrap();
+        for radius in [0.0, -0.5, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                sketch.fillet(&rectangle.points[1], radius),
+                Err(ISOTopeError::InvalidArgument(_))
+            ));
+        }
+        for (distance1, distance2) in [(0.0, None), (0.3, Some(-0.2)), (f64::NAN, Some(0.3))] {
+            assert!(matches!(
+                sketch.chamfer(&rectangle.points[1], distance1, distance2),
+                Err(ISOTopeError::InvalidArgument(_))
+            ));
+        }
+
+        // The sketch is left untouched
+        assert_eq!(sketch.primitives().len(), 8);
+        assert_eq!(ring_segments(&sketch).len(), 4);
+    }
+}
@@ -0,0 +1,1089 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::f64::consts::TAU;
+use std::rc::Rc;
+
+use nalgebra::Vector2;
+
+use crate::constraints::coincident::arc_end_point_coincident::ArcEndPointCoincident;
+use crate::constraints::coincident::arc_start_point_coincident::ArcStartPointCoincident;
+use crate::constraints::coincident::point_on_arc::PointOnArc;
+use crate::constraints::lines::collinear::Collinear;
+use crate::constraints::lines::LinePrimitive;
+use crate::constraints::radius::equal_radius::EqualRadius;
+use crate::constraints::radius::RadiusPrimitive;
+use crate::constraints::tangent::arc_arc_tangent::ArcArcTangent;
+use crate::constraints::tangent::arc_circle_tangent::ArcCircleTangent;
+use crate::constraints::tangent::line_arc_tangent::LineArcTangent;
+use crate::constraints::ConstraintCell;
+use crate::error::ISOTopeError;
+use crate::intersections::intersections;
+use crate::primitives::arc::Arc;
+use crate::primitives::circle::Circle;
+use crate::primitives::infinite_line::InfiniteLine;
+use crate::primitives::line::Line;
+use crate::primitives::point2::Point2;
+use crate::primitives::{Primitive, PrimitiveCell};
+
+use super::Sketch;
+
+// Pieces closer than this to each other or to the ends of a primitive are treated as touching
+const TOLERANCE: f64 = 1e-9;
+
+// A primitive that can be trimmed, extended and split. Positions on it are described by a
+// parameter, which goes from 0 to 1 along a line, and is the angle covered from the start of an arc
+// or from the positive x axis around a circle.
+#[derive(Debug, Clone)]
+enum EditablePrimitive {
+    Line(Rc<RefCell<Line>>),
+    Arc(Rc<RefCell<Arc>>),
+    Circle(Rc<RefCell<Circle>>),
+}
+
+// The primitives replacing an edited one
+enum Pieces {
+    Lines(Vec<Rc<RefCell<Line>>>),
+    Arcs(Vec<Rc<RefCell<Arc>>>),
+}
+
+impl EditablePrimitive {
+    fn from_primitive_cell(primitive: &PrimitiveCell) -> Result<Self, ISOTopeError> {
+        match primitive {
+            PrimitiveCell::Line(line) => Ok(EditablePrimitive::Line(line.clone())),
+            PrimitiveCell::Arc(arc) => Ok(EditablePrimitive::Arc(arc.clone())),
+            PrimitiveCell::Circle(circle) => Ok(EditablePrimitive::Circle(circle.clone())),
+            _ => Err(ISOTopeError::UnsupportedPrimitive),
+        }
+    }
+
+    fn to_primitive_cell(&self) -> PrimitiveCell {
+        match self {
+            EditablePrimitive::Line(line) => PrimitiveCell::Line(line.clone()),
+            EditablePrimitive::Arc(arc) => PrimitiveCell::Arc(arc.clone()),
+            EditablePrimitive::Circle(circle) => PrimitiveCell::Circle(circle.clone()),
+        }
+    }
+
+    fn to_primitive(&self) -> Primitive {
+        match self {
+            EditablePrimitive::Line(line) => Primitive::Line(line.borrow().clone()),
+            EditablePrimitive::Arc(arc) => Primitive::Arc(arc.borrow().clone()),
+            EditablePrimitive::Circle(circle) => Primitive::Circle(circle.borrow().clone()),
+        }
+    }
+
+    // The whole line or circle the primitive is a part of. A line of zero length has no direction
+    // and gives None.
+    fn to_unbounded_primitive(&self) -> Option<Primitive> {
+        let primitive = match self {
+            EditablePrimitive::Line(line) => {
+                let start = line.borrow().start().borrow().data();
+                let end = line.borrow().end().borrow().data();
+                let d = end - start;
+                if d.norm() < TOLERANCE {
+                    return None;
+                }
+                Primitive::InfiniteLine(InfiniteLine::new(
+                    Rc::new(RefCell::new(Point2::new(start.x, start.y))),
+                    d.y.atan2(d.x),
+                ))
+            }
+            EditablePrimitive::Arc(arc) => {
+                let arc = arc.borrow();
+                Primitive::Circle(Circle::new(arc.center(), arc.radius()))
+            }
+            EditablePrimitive::Circle(circle) => Primitive::Circle(circle.borrow().clone()),
+        };
+        Some(primitive)
+    }
+
+    // The parameter at the end of the primitive, a full turn for circles
+    fn end_parameter(&self) -> f64 {
+        match self {
+            EditablePrimitive::Line(_) => 1.0,
+            EditablePrimitive::Arc(arc) => arc.borrow().sweep_angle(),
+            EditablePrimitive::Circle(_) => TAU,
+        }
+    }
+
+    // The parameter of the closest position on the unbounded line or circle, for arcs it is in
+    // [0, 2pi) and only on the arc if it is below the sweep angle. A line of zero length has no
+    // parameters.
+    fn parameter(&self, position: Vector2<f64>) -> Result<f64, ISOTopeError> {
+        let parameter = match self {
+            EditablePrimitive::Line(line) => {
+                let start = line.borrow().start().borrow().data();
+                let end = line.borrow().end().borrow().data();
+                let d = end - start;
+                if d.norm() < TOLERANCE {
+                    return Err(ISOTopeError::DegeneratePrimitive);
+                }
+                d.dot(&(position - start)) / d.norm_squared()
+            }
+            EditablePrimitive::Arc(arc) => {
+                let arc = arc.borrow();
+                let d = position - arc.center().borrow().data();
+                let angle = d.y.atan2(d.x);
+                let offset = if arc.clockwise() {
+                    (arc.start_angle() - angle).rem_euclid(TAU)
+                } else {
+                    (angle - arc.start_angle()).rem_euclid(TAU)
+                };
+                // Positions just before the start are at the start
+                if offset > TAU - TOLERANCE {
+                    0.0
+                } else {
+                    offset
+                }
+            }
+            EditablePrimitive::Circle(circle) => {
+                let d = position - circle.borrow().center().borrow().data();
+                d.y.atan2(d.x).rem_euclid(TAU)
+            }
+        };
+        Ok(parameter)
+    }
+
+    fn position(&self, parameter: f64) -> Vector2<f64> {
+        match self {
+            EditablePrimitive::Line(line) => {
+                let start = line.borrow().start().borrow().data();
+                let end = line.borrow().end().borrow().data();
+                start + parameter * (end - start)
+            }
+            EditablePrimitive::Arc(_) | EditablePrimitive::Circle(_) => {
+                let (center, radius) = self.center_and_radius();
+                let angle = self.angle(parameter);
+                center + radius * Vector2::new(angle.cos(), angle.sin())
+            }
+        }
+    }
+
+    fn center_and_radius(&self) -> (Vector2<f64>, f64) {
+        match self {
+            EditablePrimitive::Line(_) => (Vector2::zeros(), 0.0),
+            EditablePrimitive::Arc(arc) => {
+                let arc = arc.borrow();
+                (arc.center().borrow().data(), arc.radius())
+            }
+            EditablePrimitive::Circle(circle) => {
+                let circle = circle.borrow();
+                (circle.center().borrow().data(), circle.radius())
+            }
+        }
+    }
+
+    // The angle around the center at the parameter of an arc or circle
+    fn angle(&self, parameter: f64) -> f64 {
+        match self {
+            EditablePrimitive::Arc(arc) => {
+                let arc = arc.borrow();
+                if arc.clockwise() {
+                    arc.start_angle() - parameter
+                } else {
+                    arc.start_angle() + parameter
+                }
+            }
+            _ => parameter,
+        }
+    }
+
+    // The parameters where other primitives of the sketch cross this one. Fails if a primitive of
+    // the sketch is a curve that intersections are not supported for. A line of zero length has no
+    // unbounded intersections.
+    fn intersection_parameters(
+        &self,
+        sketch: &Sketch,
+        unbounded: bool,
+    ) -> Result<Vec<f64>, ISOTopeError> {
+        let original = self.to_primitive_cell();
+        let own = if unbounded {
+            match self.to_unbounded_primitive() {
+                Some(own) => own,
+                None => return Ok(vec![]),
+            }
+        } else {
+            self.to_primitive()
+        };
+        let mut parameters = vec![];
+        for primitive in sketch.non_construction_primitives().values() {
+            if *primitive == original {
+                continue;
+            }
+            for position in intersections(own.clone(), primitive.borrow().to_primitive())? {
+                parameters.push(self.parameter(position)?);
+            }
+        }
+        parameters.sort_by(|a, b| a.total_cmp(b));
+        Ok(parameters)
+    }
+}
+
+impl Sketch {
+    fn editable_primitive(&self, id: u64) -> Result<EditablePrimitive, ISOTopeError> {
+        let primitive = self
+            .get_primitive_by_id(id)
+            .ok_or(ISOTopeError::PrimitiveNotFound(id))?;
+        EditablePrimitive::from_primitive_cell(primitive)
+    }
+
+    // Removes the part of the primitive around the position up to the nearest intersections with
+    // other primitives on both sides. Without intersections the whole primitive is removed.
+    // Returns the IDs of the remaining pieces.
+    pub fn trim(&mut self, id: u64, position: Vector2<f64>) -> Result<Vec<u64>, ISOTopeError> {
+        let primitive = self.editable_primitive(id)?;
+        let end = primitive.end_parameter();
+        let picked = primitive.parameter(position)?;
+        let cuts = match primitive {
+            // A circle has no ends, so it can be cut anywhere
+            EditablePrimitive::Circle(_) => {
+                let mut cuts = primitive.intersection_parameters(self, false)?;
+                cuts.dedup_by(|a, b| (*a - *b).abs() < TOLERANCE);
+                cuts
+            }
+            _ => primitive
+                .intersection_parameters(self, false)?
+                .into_iter()
+                .filter(|t| *t > TOLERANCE && *t < end - TOLERANCE)
+                .collect::<Vec<_>>(),
+        };
+
+        let intervals = match primitive {
+            EditablePrimitive::Circle(_) => {
+                // A circle needs to be cut twice to keep anything of it
+                if cuts.len() < 2 {
+                    vec![]
+                } else {
+                    let before = cuts.iter().rev().find(|t| **t < picked);
+                    let after = cuts.iter().find(|t| **t > picked);
+                    let (before, after) = match (before, after) {
+                        (Some(before), Some(after)) => (*before, *after),
+                        (None, Some(after)) => (cuts[cuts.len() - 1] - TAU, *after),
+                        (Some(before), None) => (*before, cuts[0] + TAU),
+                        (None, None) => return Err(ISOTopeError::PositionNotOnPrimitive),
+                    };
+                    vec![(after, before + TAU)]
+                }
+            }
+            _ => {
+                if picked < -TOLERANCE || picked > end + TOLERANCE {
+                    return Err(ISOTopeError::PositionNotOnPrimitive);
+                }
+                let before = cuts.iter().rev().find(|t| **t < picked).copied();
+                let after = cuts.iter().find(|t| **t > picked).copied();
+                let mut intervals = vec![];
+                if let Some(before) = before {
+                    intervals.push((0.0, before));
+                }
+                if let Some(after) = after {
+                    intervals.push((after, end));
+                }
+                intervals
+            }
+        };
+
+        self.replace_with_pieces(id, &primitive, &intervals)
+    }
+
+    // Extends the end of a line or arc closest to the position up to the nearest primitive it hits.
+    // Returns the ID of the extended primitive.
+    pub fn extend(&mut self, id: u64, position: Vector2<f64>) -> Result<u64, ISOTopeError> {
+        let primitive = self.editable_primitive(id)?;
+        let end = primitive.end_parameter();
+        let at_end = (position - primitive.position(end)).norm()
+            < (position - primitive.position(0.0)).norm();
+        let parameters = primitive.intersection_parameters(self, true)?;
+
+        let interval = match primitive {
+            EditablePrimitive::Line(_) => {
+                if at_end {
+                    parameters
+                        .iter()
+                        .find(|t| **t > end + TOLERANCE)
+                        .map(|t| (0.0, *t))
+                } else {
+                    parameters
+                        .iter()
+                        .rev()
+                        .find(|t| **t < -TOLERANCE)
+                        .map(|t| (*t, end))
+                }
+            }
+            EditablePrimitive::Arc(_) => {
+                // The arc can grow into the gap between its end and its start
+                let gap = parameters
+                    .iter()
+                    .filter(|t| **t > end + TOLERANCE && **t < TAU - TOLERANCE);
+                if at_end {
+                    gap.min_by(|a, b| a.total_cmp(b)).map(|t| (0.0, *t))
+                } else {
+                    gap.max_by(|a, b| a.total_cmp(b)).map(|t| (*t - TAU, end))
+                }
+            }
+            EditablePrimitive::Circle(_) => return Err(ISOTopeError::UnsupportedPrimitive),
+        }
+        .ok_or(ISOTopeError::NoIntersection)?;
+
+        let ids = self.replace_with_pieces(id, &primitive, &[interval])?;
+        ids.first().copied().ok_or(ISOTopeError::NoIntersection)
+    }
+
+    // Splits the primitive at the positions, a circle has to be split at two positions at least to
+    // become arcs. Returns the IDs of the pieces in order along the primitive.
+    pub fn split(&mut self, id: u64, positions: &[Vector2<f64>]) -> Result<Vec<u64>, ISOTopeError> {
+        let primitive = self.editable_primitive(id)?;
+        let end = primitive.end_parameter();
+        let mut cuts = positions
+            .iter()
+            .map(|position| primitive.parameter(*position))
+            .collect::<Result<Vec<_>, _>>()?;
+        cuts.sort_by(|a, b| a.total_cmp(b));
+        cuts.dedup_by(|a, b| (*a - *b).abs() < TOLERANCE);
+
+        let intervals = match primitive {
+            EditablePrimitive::Circle(_) => {
+                if cuts.len() < 2 {
+                    return Err(ISOTopeError::PositionNotOnPrimitive);
+                }
+                let mut intervals = cuts.windows(2).map(|w| (w[0], w[1])).collect::<Vec<_>>();
+                intervals.push((cuts[cuts.len() - 1], cuts[0] + TAU));
+                intervals
+            }
+            _ => {
+                if cuts.is_empty() || cuts.iter().any(|t| *t < TOLERANCE || *t > end - TOLERANCE) {
+                    return Err(ISOTopeError::PositionNotOnPrimitive);
+                }
+                let mut bounds = vec![0.0];
+                bounds.extend(cuts);
+                bounds.push(end);
+                bounds.windows(2).map(|w| (w[0], w[1])).collect()
+            }
+        };
+
+        self.replace_with_pieces(id, &primitive, &intervals)
+    }
+
+    // Replaces the primitive by pieces covering the intervals of its parameter, keeps touching
+    // pieces connected and moves or drops the constraints of the original
+    fn replace_with_pieces(
+        &mut self,
+        id: u64,
+        primitive: &EditablePrimitive,
+        intervals: &[(f64, f64)],
+    ) -> Result<Vec<u64>, ISOTopeError> {
+        let construction = self.is_construction(id)?;
+        let intervals = intervals
+            .iter()
+            .filter(|(start, end)| end - start > TOLERANCE)
+            .copied()
+            .collect::<Vec<_>>();
+
+        let mut ids = vec![];
+        let mut added_constraints = vec![];
+        let pieces = match primitive {
+            EditablePrimitive::Line(line) => {
+                // Keep the end points of the line and share new points between touching pieces
+                let mut points: Vec<(f64, Rc<RefCell<Point2>>)> =
+                    vec![(0.0, line.borrow().start()), (1.0, line.borrow().end())];
+                let mut lines = vec![];
+                for (start, end) in intervals.iter() {
+                    let mut ends = vec![];
+                    for parameter in [*start, *end] {
+                        let existing = points
+                            .iter()
+                            .find(|(t, _)| (t - parameter).abs() < TOLERANCE)
+                            .map(|(_, point)| point.clone());
+                        let point = match existing {
+                            Some(point) => point,
+                            None => {
+                                let position = primitive.position(parameter);
+                                let point =
+                                    Rc::new(RefCell::new(Point2::new(position.x, position.y)));
+                                self.add_primitive(PrimitiveCell::Point2(point.clone()))?;
+                                points.push((parameter, point.clone()));
+                                point
+                            }
+                        };
+                        ends.push(point);
+                    }
+                    let piece = Rc::new(RefCell::new(Line::new(ends[0].clone(), ends[1].clone())));
+                    ids.push(self.add_primitive_with_construction(
+                        PrimitiveCell::Line(piece.clone()),
+                        construction,
+                    )?);
+                    lines.push(piece);
+                }
+                // The pieces stay on the original line
+                for pair in lines.windows(2) {
+                    added_constraints.push(ConstraintCell::Collinear(Rc::new(RefCell::new(
+                        Collinear::new(pair[0].clone(), pair[1].clone()),
+                    ))));
+                }
+                Pieces::Lines(lines)
+            }
+            EditablePrimitive::Arc(_) | EditablePrimitive::Circle(_) => {
+                let (center, clockwise) = match primitive {
+                    EditablePrimitive::Arc(arc) => {
+                        (arc.borrow().center(), arc.borrow().clockwise())
+                    }
+                    EditablePrimitive::Circle(circle) => (circle.borrow().center(), false),
+                    EditablePrimitive::Line(_) => return Err(ISOTopeError::UnsupportedPrimitive),
+                };
+                let (_, radius) = primitive.center_and_radius();
+                let mut arcs = vec![];
+                for (start, end) in intervals.iter() {
+                    let piece = Rc::new(RefCell::new(Arc::new(
+                        center.clone(),
+                        radius,
+                        clockwise,
+                        primitive.angle(*start),
+                        primitive.angle(*end),
+                    )));
+                    ids.push(self.add_primitive_with_construction(
+                        PrimitiveCell::Arc(piece.clone()),
+                        construction,
+                    )?);
+                    arcs.push(piece);
+                }
+                // Touching pieces share a point, the others keep the same radius
+                let num_pairs = match primitive {
+                    EditablePrimitive::Circle(_) => arcs.len(),
+                    _ => arcs.len().saturating_sub(1),
+                };
+                for i in 0..num_pairs {
+                    let j = (i + 1) % arcs.len();
+                    if i == j {
+                        continue;
+                    }
+                    let gap = (intervals[j].0 - intervals[i].1).rem_euclid(TAU);
+                    if gap < TOLERANCE || TAU - gap < TOLERANCE {
+                        let position = primitive.position(intervals[i].1);
+                        let point = Rc::new(RefCell::new(Point2::new(position.x, position.y)));
+                        self.add_primitive(PrimitiveCell::Point2(point.clone()))?;
+                        added_constraints.push(ConstraintCell::ArcEndPointCoincident(Rc::new(
+                            RefCell::new(ArcEndPointCoincident::new(
+                                arcs[i].clone(),
+                                point.clone(),
+                            )),
+                        )));
+                        added_constraints.push(ConstraintCell::ArcStartPointCoincident(Rc::new(
+                            RefCell::new(ArcStartPointCoincident::new(arcs[j].clone(), point)),
+                        )));
+                    } else {
+                        added_constraints.push(ConstraintCell::EqualRadius(Rc::new(RefCell::new(
+                            EqualRadius::new(
+                                RadiusPrimitive::Arc(arcs[i].clone()),
+                                RadiusPrimitive::Arc(arcs[j].clone()),
+                            ),
+                        ))));
+                    }
+                }
+                Pieces::Arcs(arcs)
+            }
+        };
+
+        // Constraints of the original move to the pieces or are dropped if they no longer apply
+        let original = primitive.to_primitive_cell();
+        let mut constraints = VecDeque::new();
+        for constraint in self.constraints.iter() {
+            if !constraint.borrow().references().contains(&original) {
+                constraints.push_back(constraint.clone());
+            } else if let Some(migrated) = migrate_constraint(constraint, primitive, &pieces) {
+                constraints.push_back(migrated);
+            }
+        }
+        constraints.extend(added_constraints);
+        self.constraints = constraints;
+
+        self.delete_primitive(id)?;
+
+        // End points of the original line that no piece kept are removed as well, unless something
+        // else still uses them
+        if let EditablePrimitive::Line(line) = primitive {
+            for point in [line.borrow().start(), line.borrow().end()] {
+                let point = PrimitiveCell::Point2(point);
+                let referenced = self
+                    .primitives
+                    .values()
+                    .any(|p| p.borrow().references().contains(&point))
+                    || self
+                        .constraints
+                        .iter()
+                        .any(|c| c.borrow().references().contains(&point));
+                if !referenced {
+                    if let Some(point_id) = self.get_primitive_id(&point) {
+                        self.delete_primitive(point_id)?;
+                    }
+                }
+            }
+        }
+        Ok(ids)
+    }
+}
+
+// Moves a constraint of the original primitive to the pieces that replace it. Returns None if the
+// constraint no longer applies, e.g. because it fixes the length of the original.
+fn migrate_constraint(
+    constraint: &ConstraintCell,
+    original: &EditablePrimitive,
+    pieces: &Pieces,
+) -> Option<ConstraintCell> {
+    match (original, pieces) {
+        (EditablePrimitive::Line(line), Pieces::Lines(lines)) => {
+            // The pieces stay on the line, so constraints on the direction or position of the line
+            // can move to any of them
+            let first = lines.first()?.clone();
+            let is_original = |other: &Rc<RefCell<Line>>| Rc::ptr_eq(other, line);
+            let is_original_primitive =
+                |other: &LinePrimitive| matches!(other, LinePrimitive::Line(l) if is_original(l));
+            match constraint {
+                ConstraintCell::HorizontalLine(c) => c.borrow_mut().set_line(first),
+                ConstraintCell::VerticalLine(c) => c.borrow_mut().set_line(first),
+                ConstraintCell::PointOnLine(c) => c.borrow_mut().set_line(first),
+                ConstraintCell::ParallelLines(c) => {
+                    if is_original_primitive(&c.borrow().line1()) {
+                        c.borrow_mut().set_line1(first.clone());
+                    }
+                    if is_original_primitive(&c.borrow().line2()) {
+                        c.borrow_mut().set_line2(first);
+                    }
+                }
+                ConstraintCell::PerpendicularLines(c) => {
+                    if is_original_primitive(&c.borrow().line1()) {
+                        c.borrow_mut().set_line1(first.clone());
+                    }
+                    if is_original_primitive(&c.borrow().line2()) {
+                        c.borrow_mut().set_line2(first);
+                    }
+                }
+                ConstraintCell::Collinear(c) => {
+                    if is_original(&c.borrow().line1()) {
+                        c.borrow_mut().set_line1(first.clone());
+                    }
+                    if is_original(&c.borrow().line2()) {
+                        c.borrow_mut().set_line2(first);
+                    }
+                }
+                ConstraintCell::AngleBetweenLines(c) => {
+                    if is_original(&c.borrow().line1()) {
+                        c.borrow_mut().set_line1(first.clone());
+                    }
+                    if is_original(&c.borrow().line2()) {
+                        c.borrow_mut().set_line2(first);
+                    }
+                }
+                ConstraintCell::AngleBetweenLinesBounds(c) => {
+                    if is_original(&c.borrow().line1()) {
+                        c.borrow_mut().set_line1(first.clone());
+                    }
+                    if is_original(&c.borrow().line2()) {
+                        c.borrow_mut().set_line2(first);
+                    }
+                }
+                ConstraintCell::DistanceBetweenPointAndLine(c) => c.borrow_mut().set_line(first),
+                ConstraintCell::DistanceBetweenLines(c) => {
+                    if is_original(&c.borrow().line1()) {
+                        c.borrow_mut().set_line1(first.clone());
+                    }
+                    if is_original(&c.borrow().line2()) {
+                        c.borrow_mut().set_line2(first);
+                    }
+                }
+                // The tangency only depends on the unbounded line, but it belongs to the piece
+                // that touches the circle if there is one
+                ConstraintCell::LineCircleTangent(c) => {
+                    let center = c.borrow().circle().borrow().center().borrow().data();
+                    let piece = line_piece_containing(lines, center).unwrap_or(first);
+                    c.borrow_mut().set_line(piece);
+                }
+                ConstraintCell::LineArcTangent(c) => {
+                    let center = c.borrow().arc().borrow().center().borrow().data();
+                    let piece = line_piece_containing(lines, center).unwrap_or(first);
+                    c.borrow_mut().set_line(piece);
+                }
+                ConstraintCell::PointOnLineSegment(c) => {
+                    let piece = line_piece_containing(lines, c.borrow().point().borrow().data())?;
+                    c.borrow_mut().set_line(piece);
+                }
+                // Only the axis of a symmetry is an unbounded line, the mirrored lines are not
+                ConstraintCell::SymmetricPointsAboutLine(c) => c.borrow_mut().set_axis(first),
+                ConstraintCell::SymmetricArcsAboutLine(c) => c.borrow_mut().set_axis(first),
+                ConstraintCell::SymmetricLinesAboutLine(c) => {
+                    if is_original(&c.borrow().line1()) || is_original(&c.borrow().line2()) {
+                        return None;
+                    }
+                    c.borrow_mut().set_axis(first);
+                }
+                _ => return None,
+            }
+            Some(constraint.clone())
+        }
+        (EditablePrimitive::Arc(arc), Pieces::Arcs(arcs)) => {
+            // The pieces stay on the circle of the arc
+            let first = arcs.first()?.clone();
+            let is_original = |other: &Rc<RefCell<Arc>>| Rc::ptr_eq(other, arc);
+            match constraint {
+                ConstraintCell::FixedRadius(_)
+                | ConstraintCell::FixedDiameter(_)
+                | ConstraintCell::RadiusBounds(_)
+                | ConstraintCell::EqualRadius(_) => {
+                    migrate_radius_constraint(constraint, &original.to_primitive_cell(), &first)
+                }
+                ConstraintCell::LineArcTangent(c) => c.borrow_mut().set_arc(first),
+                ConstraintCell::ArcCircleTangent(c) => c.borrow_mut().set_arc(first),
+                ConstraintCell::ArcArcTangent(c) => {
+                    if is_original(&c.borrow().arc1()) {
+                        c.borrow_mut().set_arc1(first.clone());
+                    }
+                    if is_original(&c.borrow().arc2()) {
+                        c.borrow_mut().set_arc2(first);
+                    }
+                }
+                ConstraintCell::ArcStartPointCoincident(c) => {
+                    let start = arc.borrow().start_point();
+                    let piece = arcs
+                        .iter()
+                        .find(|piece| (piece.borrow().start_point() - start).norm() < TOLERANCE)?;
+                    c.borrow_mut().set_arc(piece.clone());
+                }
+                ConstraintCell::ArcEndPointCoincident(c) => {
+                    let end = arc.borrow().end_point();
+                    let piece = arcs
+                        .iter()
+                        .find(|piece| (piece.borrow().end_point() - end).norm() < TOLERANCE)?;
+                    c.borrow_mut().set_arc(piece.clone());
+                }
+                ConstraintCell::PointOnArc(c) => {
+                    let piece = piece_containing(arcs, c.borrow().point().borrow().data())?;
+                    c.borrow_mut().set_arc(piece);
+                }
+                _ => return None,
+            }
+            Some(constraint.clone())
+        }
+        (EditablePrimitive::Circle(circle), Pieces::Arcs(arcs)) => {
+            // The circle becomes arcs, so constraints on it are replaced by the ones for arcs
+            let first = arcs.first()?.clone();
+            let is_original = |other: &Rc<RefCell<Circle>>| Rc::ptr_eq(other, circle);
+            match constraint {
+                ConstraintCell::FixedRadius(_)
+                | ConstraintCell::FixedDiameter(_)
+                | ConstraintCell::RadiusBounds(_)
+                | ConstraintCell::EqualRadius(_) => {
+                    migrate_radius_constraint(constraint, &original.to_primitive_cell(), &first);
+                    Some(constraint.clone())
+                }
+                ConstraintCell::LineCircleTangent(c) => Some(ConstraintCell::LineArcTangent(
+                    Rc::new(RefCell::new(LineArcTangent::new(c.borrow().line(), first))),
+                )),
+                ConstraintCell::ArcCircleTangent(c) => {
+                    Some(ConstraintCell::ArcArcTangent(Rc::new(RefCell::new(
+                        ArcArcTangent::new(c.borrow().arc(), first, c.borrow().internal()),
+                    ))))
+                }
+                ConstraintCell::CircleCircleTangent(c) => {
+                    let other = if is_original(&c.borrow().circle1()) {
+                        c.borrow().circle2()
+                    } else {
+                        c.borrow().circle1()
+                    };
+                    Some(ConstraintCell::ArcCircleTangent(Rc::new(RefCell::new(
+                        ArcCircleTangent::new(first, other, c.borrow().internal()),
+                    ))))
+                }
+                ConstraintCell::PointOnCircle(c) => {
+                    let point = c.borrow().point();
+                    let piece = piece_containing(arcs, point.borrow().data())?;
+                    Some(ConstraintCell::PointOnArc(Rc::new(RefCell::new(
+                        PointOnArc::new(point.clone(), piece),
+                    ))))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+// Points the radius constraint at the arc instead of the original circle or arc
+fn migrate_radius_constraint(
+    constraint: &ConstraintCell,
+    original: &PrimitiveCell,
+    arc: &Rc<RefCell<Arc>>,
+) {
+    let replace = |primitive: RadiusPrimitive| {
+        if &primitive.to_primitive_cell() == original {
+            RadiusPrimitive::Arc(arc.clone())
+        } else {
+            primitive
+        }
+    };
+    match constraint {
+        ConstraintCell::FixedRadius(c) => {
+            let primitive = replace(c.borrow().primitive());
+            c.borrow_mut().set_primitive(primitive);
+        }
+        ConstraintCell::FixedDiameter(c) => {
+            let primitive = replace(c.borrow().primitive());
+            c.borrow_mut().set_primitive(primitive);
+        }
+        ConstraintCell::RadiusBounds(c) => {
+            let primitive = replace(c.borrow().primitive());
+            c.borrow_mut().set_primitive(primitive);
+        }
+        ConstraintCell::EqualRadius(c) => {
+            let primitive1 = replace(c.borrow().primitive1());
+            let primitive2 = replace(c.borrow().primitive2());
+            c.borrow_mut().set_primitive1(primitive1);
+            c.borrow_mut().set_primitive2(primitive2);
+        }
+        _ => {}
+    }
+}
+
+fn piece_containing(arcs: &[Rc<RefCell<Arc>>], position: Vector2<f64>) -> Option<Rc<RefCell<Arc>>> {
+    arcs.iter()
+        .find(|arc| {
+            let arc = arc.borrow();
+            let d = position - arc.center().borrow().data();
+            arc.contains_angle(d.y.atan2(d.x))
+        })
+        .cloned()
+}
+
+// The line piece onto which the position projects
+fn line_piece_containing(
+    lines: &[Rc<RefCell<Line>>],
+    position: Vector2<f64>,
+) -> Option<Rc<RefCell<Line>>> {
+    lines
+        .iter()
+        .find(|line| {
+            let line = line.borrow();
+            let start = line.start().borrow().data();
+            let d = line.end().borrow().data() - start;
+            let t = d.dot(&(position - start)) / d.norm_squared();
+            (-TOLERANCE..=1.0 + TOLERANCE).contains(&t)
+        })
+        .cloned()
+}
+
+// Run some tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, f64::consts::PI, rc::Rc};
+
+    use nalgebra::Vector2;
+
+    use crate::{
+        constraints::{
+            coincident::point_on_circle::PointOnCircle, lines::horizontal_line::HorizontalLine,
+            lines::line_length::LineLength, radius::fixed_radius::FixedRadius,
+            radius::RadiusPrimitive, tangent::line_circle_tangent::LineCircleTangent, Constraint,
+            ConstraintCell,
+        },
+        error::ISOTopeError,
+        primitives::{arc::Arc, circle::Circle, ellipse::Ellipse, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{bfgs_solver::BFGSSolver, Solver},
+    };
+
+    fn add_line(sketch: &mut Sketch, start: Vector2<f64>, end: Vector2<f64>) -> u64 {
+        let polyline = sketch.add_polyline(&[start, end], false).unwrap();
+        let line = PrimitiveCell::Line(polyline.lines[0].clone());
+        sketch.get_primitive_id(&line).unwrap()
+    }
+
+    fn line_ends(sketch: &Sketch, id: u64) -> (Vector2<f64>, Vector2<f64>) {
+        match sketch.get_primitive_by_id(id).unwrap() {
+            PrimitiveCell::Line(line) => (
+                line.borrow().start().borrow().data(),
+                line.borrow().end().borrow().data(),
+            ),
+            _ => panic!("expected a line"),
+        }
+    }
+
+    fn assert_solved(sketch: &Sketch, tolerance: f64) {
+        for constraint in sketch.constraints() {
+            assert!(constraint.borrow().loss_value() < tolerance);
+        }
+    }
+
+    #[test]
+    fn test_split_line() {
+        let mut sketch = Sketch::new();
+        let id = add_line(&mut sketch, Vector2::new(0.0, 0.0), Vector2::new(3.0, 0.0));
+        let line = match sketch.get_primitive_by_id(id).unwrap() {
+            PrimitiveCell::Line(line) => line.clone(),
+            _ => panic!("expected a line"),
+        };
+        sketch
+            .add_constraint(ConstraintCell::HorizontalLine(Rc::new(RefCell::new(
+                HorizontalLine::new(line),
+            ))))
+            .unwrap();
+
+        let ids = sketch
+            .split(id, &[Vector2::new(2.0, 0.0), Vector2::new(1.0, 0.0)])
+            .unwrap();
+        assert_eq!(ids.len(), 3);
+        assert!(sketch.get_primitive_by_id(id).is_none());
+        assert!((line_ends(&sketch, ids[0]).1 - Vector2::new(1.0, 0.0)).norm() < 1e-12);
+        assert!((line_ends(&sketch, ids[2]).0 - Vector2::new(2.0, 0.0)).norm() < 1e-12);
+
+        // The horizontal constraint moves to the first piece and the pieces stay collinear
+        assert_eq!(sketch.constraints().len(), 3);
+        assert_solved(&sketch, 1e-12);
+
+        assert!(matches!(
+            sketch.split(ids[0], &[Vector2::new(5.0, 0.0)]),
+            Err(ISOTopeError::PositionNotOnPrimitive)
+        ));
+    }
+
+    #[test]
+    fn test_trim_line() {
+        let mut sketch = Sketch::new();
+        let id = add_line(&mut sketch, Vector2::new(0.0, 0.0), Vector2::new(4.0, 0.0));
+        add_line(&mut sketch, Vector2::new(1.0, -1.0), Vector2::new(1.0, 1.0));
+        add_line(&mut sketch, Vector2::new(3.0, -1.0), Vector2::new(3.0, 1.0));
+
+        let ids = sketch.trim(id, Vector2::new(2.0, 0.1)).unwrap();
+        assert_eq!(ids.len(), 2);
+        let (start, end) = line_ends(&sketch, ids[0]);
+        assert!((start - Vector2::new(0.0, 0.0)).norm() < 1e-12);
+        assert!((end - Vector2::new(1.0, 0.0)).norm() < 1e-12);
+        let (start, end) = line_ends(&sketch, ids[1]);
+        assert!((start - Vector2::new(3.0, 0.0)).norm() < 1e-12);
+        assert!((end - Vector2::new(4.0, 0.0)).norm() < 1e-12);
+
+        // Trimming the end piece removes it up to the crossing line
+        let ids = sketch.trim(ids[1], Vector2::new(3.5, 0.0)).unwrap();
+        assert!(ids.is_empty());
+    }
+
+    #[test]
+    fn test_trim_circle() {
+        let mut sketch = Sketch::new();
+        let center = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let circle = Rc::new(RefCell::new(Circle::new(center.clone(), 1.0)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(center.clone()))
+            .unwrap();
+        let id = sketch
+            .add_primitive(PrimitiveCell::Circle(circle.clone()))
+            .unwrap();
+        sketch
+            .add_constraint(ConstraintCell::FixedRadius(Rc::new(RefCell::new(
+                FixedRadius::new(RadiusPrimitive::Circle(circle), 1.0),
+            ))))
+            .unwrap();
+        add_line(&mut sketch, Vector2::new(-2.0, 0.0), Vector2::new(2.0, 0.0));
+
+        // The upper half is removed and the lower half stays as an arc
+        let ids = sketch.trim(id, Vector2::new(0.1, 0.9)).unwrap();
+        assert_eq!(ids.len(), 1);
+        let arc = match sketch.get_primitive_by_id(ids[0]).unwrap() {
+            PrimitiveCell::Arc(arc) => arc.clone(),
+            _ => panic!("expected an arc"),
+        };
+        assert!((arc.borrow().sweep_angle() - PI).abs() < 1e-12);
+        assert!(arc.borrow().mid_point().y < -0.99);
+
+        // The radius constraint applies to the arc now
+        assert_eq!(sketch.constraints().len(), 1);
+        arc.borrow_mut().set_radius(1.5);
+        BFGSSolver::new().solve(&mut sketch).unwrap();
+        assert!((arc.borrow().radius() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_split_circle() {
+        let mut sketch = Sketch::new();
+        let center = Rc::new(RefCell::new(Point2::new(1.0, 1.0)));
+        let circle = Rc::new(RefCell::new(Circle::new(center.clone(), 2.0)));
+        let point = Rc::new(RefCell::new(Point2::new(1.0, -1.0)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(center.clone()))
+            .unwrap();
+        let id = sketch
+            .add_primitive(PrimitiveCell::Circle(circle.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Point2(point.clone()))
+            .unwrap();
+        sketch
+            .add_constraint(ConstraintCell::PointOnCircle(Rc::new(RefCell::new(
+                PointOnCircle::new(point, circle),
+            ))))
+            .unwrap();
+
+        assert!(matches!(
+            sketch.split(id, &[Vector2::new(3.0, 1.0)]),
+            Err(ISOTopeError::PositionNotOnPrimitive)
+        ));
+        let ids = sketch
+            .split(id, &[Vector2::new(3.0, 1.0), Vector2::new(-1.0, 1.0)])
+            .unwrap();
+        assert_eq!(ids.len(), 2);
+
+        // The point on the circle ends up on the lower arc, and the arcs are joined at both ends
+        let point_on_arc = sketch
+            .constraints()
+            .iter()
+            .find_map(|constraint| match constraint {
+                ConstraintCell::PointOnArc(c) => Some(c.clone()),
+                _ => None,
+            })
+            .unwrap();
+        assert!(point_on_arc.borrow().arc().borrow().mid_point().y < 0.0);
+        assert_eq!(sketch.constraints().len(), 5);
+        assert_solved(&sketch, 1e-12);
+    }
+
+    #[test]
+    fn test_extend_line() {
+        let mut sketch = Sketch::new();
+        let id = add_line(&mut sketch, Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0));
+        assert!(matches!(
+            sketch.extend(id, Vector2::new(1.0, 0.0)),
+            Err(ISOTopeError::NoIntersection)
+        ));
+
+        add_line(&mut sketch, Vector2::new(3.0, -1.0), Vector2::new(3.0, 1.0));
+        add_line(&mut sketch, Vector2::new(5.0, -1.0), Vector2::new(5.0, 1.0));
+        let old_end = match sketch.get_primitive_by_id(id).unwrap() {
+            PrimitiveCell::Line(line) => PrimitiveCell::Point2(line.borrow().end()),
+            _ => panic!("expected a line"),
+        };
+        let extended = sketch.extend(id, Vector2::new(0.9, 0.0)).unwrap();
+        let (start, end) = line_ends(&sketch, extended);
+        assert!((start - Vector2::new(0.0, 0.0)).norm() < 1e-12);
+        assert!((end - Vector2::new(3.0, 0.0)).norm() < 1e-12);
+
+        // The replaced end point is not left behind in the sketch
+        assert!(sketch.get_primitive_id(&old_end).is_none());
+        assert_eq!(sketch.primitives().len(), 9);
+    }
+
+    #[test]
+    fn test_extend_without_target() {
+        let mut sketch = Sketch::new();
+
+        // A parallel line is never hit
+        let id = add_line(&mut sketch, Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0));
+        add_line(&mut sketch, Vector2::new(0.0, 1.0), Vector2::new(1.0, 1.0));
+        assert!(matches!(
+            sketch.extend(id, Vector2::new(1.0, 0.0)),
+            Err(ISOTopeError::NoIntersection)
+        ));
+
+        // A line of zero length has no direction to extend in
+        add_line(&mut sketch, Vector2::new(3.0, -2.0), Vector2::new(3.0, 2.0));
+        let degenerate = add_line(&mut sketch, Vector2::new(2.0, 0.0), Vector2::new(2.0, 0.0));
+        assert!(matches!(
+            sketch.extend(degenerate, Vector2::new(2.0, 0.0)),
+            Err(ISOTopeError::NoIntersection)
+        ));
+
+        // An arc with nothing in the gap between its ends
+        let center = Rc::new(RefCell::new(Point2::new(10.0, 0.0)));
+        let arc = Rc::new(RefCell::new(Arc::new(center.clone(), 1.0, false, 0.0, PI)));
+        sketch.add_primitive(PrimitiveCell::Point2(center)).unwrap();
+        let arc_id = sketch.add_primitive(PrimitiveCell::Arc(arc)).unwrap();
+        assert!(matches!(
+            sketch.extend(arc_id, Vector2::new(11.0, 0.0)),
+            Err(ISOTopeError::NoIntersection)
+        ));
+
+        // Nothing is replaced when there is nothing to extend to
+        assert!(sketch.get_primitive_by_id(id).is_some());
+        assert!(sketch.get_primitive_by_id(degenerate).is_some());
+        assert!(sketch.get_primitive_by_id(arc_id).is_some());
+    }
+
+    #[test]
+    fn test_trim_tangent_line() {
+        let mut sketch = Sketch::new();
+        let id = add_line(&mut sketch, Vector2::new(-3.0, 1.0), Vector2::new(3.0, 1.0));
+        let line = match sketch.get_primitive_by_id(id).unwrap() {
+            PrimitiveCell::Line(line) => line.clone(),
+            _ => panic!("expected a line"),
+        };
+        let center = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let circle = Rc::new(RefCell::new(Circle::new(center.clone(), 1.0)));
+        sketch.add_primitive(PrimitiveCell::Point2(center)).unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Circle(circle.clone()))
+            .unwrap();
+        sketch
+            .add_constraint(ConstraintCell::LineCircleTangent(Rc::new(RefCell::new(
+                LineCircleTangent::new(line.clone(), circle),
+            ))))
+            .unwrap();
+        sketch
+            .add_constraint(ConstraintCell::LineLength(Rc::new(RefCell::new(
+                LineLength::new(line, 6.0),
+            ))))
+            .unwrap();
+        add_line(&mut sketch, Vector2::new(2.0, -2.0), Vector2::new(2.0, 2.0));
+
+        let ids = sketch.trim(id, Vector2::new(2.5, 1.0)).unwrap();
+        assert_eq!(ids.len(), 1);
+        assert!((line_ends(&sketch, ids[0]).1 - Vector2::new(2.0, 1.0)).norm() < 1e-12);
+
+        // The tangency moves to the remaining piece, the length of the original is dropped
+        let piece = sketch.get_primitive_by_id(ids[0]).unwrap();
+        let constraints = sketch.constraints();
+        assert_eq!(constraints.len(), 1);
+        let constraint = constraints[0].borrow();
+        assert!(matches!(
+            constraint.get_type(),
+            Constraint::LineCircleTangent(_)
+        ));
+        assert!(constraint.references().contains(piece));
+        drop(constraint);
+        assert_solved(&sketch, 1e-12);
+    }
+
+    #[test]
+    fn test_degenerate_line() {
+        let mut sketch = Sketch::new();
+        let id = add_line(&mut sketch, Vector2::new(1.0, 1.0), Vector2::new(1.0, 1.0));
+        assert!(matches!(
+            sketch.split(id, &[Vector2::new(1.0, 1.0)]),
+            Err(ISOTopeError::DegeneratePrimitive)
+        ));
+        assert!(matches!(
+            sketch.trim(id, Vector2::new(1.0, 1.0)),
+            Err(ISOTopeError::DegeneratePrimitive)
+        ));
+        assert!(sketch.get_primitive_by_id(id).is_some());
+    }
+
+    #[test]
+    fn test_unsupported_cutting_edge() {
+        let mut sketch = Sketch::new();
+        let id = add_line(&mut sketch, Vector2::new(-3.0, 0.0), Vector2::new(3.0, 0.0));
+        let center = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(center.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Ellipse(Rc::new(RefCell::new(Ellipse::new(
+                center, 2.0, 1.0, 0.0,
+            )))))
+            .unwrap();
+
+        // The ellipse crosses the line, which cannot be computed yet
+        assert!(matches!(
+            sketch.trim(id, Vector2::new(0.0, 0.0)),
+            Err(ISOTopeError::UnsupportedPrimitive)
+        ));
+        assert!(matches!(
+            sketch.extend(id, Vector2::new(3.0, 0.0)),
+            Err(ISOTopeError::UnsupportedPrimitive)
+        ));
+    }
+}
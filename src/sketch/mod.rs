@@ -1,23 +1,77 @@
 use std::cell::RefCell;
-use std::collections::{BTreeMap, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
 use std::rc::Rc;
 
 use nalgebra::{DMatrix, DVector};
+use nalgebra_sparse::{CooMatrix, CsrMatrix};
 use serde::{Deserialize, Serialize};
 
+use crate::constraints::arcs::endpoint_arc_equal_radius::EndpointArcEqualRadius;
 use crate::constraints::ConstraintCell;
 use crate::decompose::face::Face;
 use crate::decompose::{decompose_sketch, merge_faces};
 use crate::error::ISOTopeError;
-use crate::primitives::{point2, PrimitiveCell};
+use crate::primitives::infinite_line::InfiniteLine;
+use crate::primitives::point2::Point2;
+use crate::primitives::{point2, PrimitiveCell, PrimitiveLike};
 
 use super::constraints::ConstraintLike;
 
+pub mod builders;
+pub mod corners;
+pub mod editing;
+
+// The origin and the X and Y axes every sketch has. They can be referenced by primitives and
+// constraints, but they are not part of the primitives of the sketch, so they have no degrees of
+// freedom and cannot be deleted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Datums {
+    origin: Rc<RefCell<Point2>>,
+    x_axis: Rc<RefCell<InfiniteLine>>,
+    y_axis: Rc<RefCell<InfiniteLine>>,
+}
+
+impl Default for Datums {
+    fn default() -> Self {
+        let origin = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let x_axis = Rc::new(RefCell::new(InfiniteLine::new(origin.clone(), 0.0)));
+        let y_axis = Rc::new(RefCell::new(InfiniteLine::new(
+            origin.clone(),
+            std::f64::consts::FRAC_PI_2,
+        )));
+        Self {
+            origin,
+            x_axis,
+            y_axis,
+        }
+    }
+}
+
+impl Datums {
+    fn contains(&self, primitive: &PrimitiveCell) -> bool {
+        primitive == &PrimitiveCell::Point2(self.origin.clone())
+            || primitive == &PrimitiveCell::InfiniteLine(self.x_axis.clone())
+            || primitive == &PrimitiveCell::InfiniteLine(self.y_axis.clone())
+    }
+
+    // Constraints still add to the gradients of the datums, so they are reset with the sketch
+    fn zero_gradient(&self) {
+        self.origin.borrow_mut().zero_gradient();
+        self.x_axis.borrow_mut().zero_gradient();
+        self.y_axis.borrow_mut().zero_gradient();
+    }
+}
+
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct Sketch {
     primitives: BTreeMap<u64, PrimitiveCell>,
     primitives_next_id: u64,
     constraints: VecDeque<ConstraintCell>,
+    // Ids of construction primitives, they take part in solving but not in decomposing or exporting
+    #[serde(default)]
+    construction: BTreeSet<u64>,
+    #[serde(default)]
+    datums: Datums,
 }
 
 impl Sketch {
@@ -26,9 +80,17 @@ impl Sketch {
     }
 
     pub fn add_primitive(&mut self, primitive: PrimitiveCell) -> Result<u64, ISOTopeError> {
+        self.add_primitive_with_construction(primitive, false)
+    }
+
+    pub fn add_primitive_with_construction(
+        &mut self,
+        primitive: PrimitiveCell,
+        construction: bool,
+    ) -> Result<u64, ISOTopeError> {
         // Make sure all referenced primitives are added to the sketch before the primitive
         for reference in primitive.borrow().references().iter() {
-            if !self.primitives.iter().any(|(_, p)| reference == p) {
+            if !self.contains_primitive(reference) {
                 return Err(ISOTopeError::MissingSketchReferences);
             }
         }
@@ -36,13 +98,40 @@ impl Sketch {
         if self.primitives.iter().any(|(_, p)| p == &primitive) {
             return Err(ISOTopeError::PrimitiveAlreadyInSketch);
         }
+        // Endpoint arcs need their start and end point at the same distance from the center
+        if let PrimitiveCell::EndpointArc(arc) = &primitive {
+            self.constraints
+                .push_back(ConstraintCell::EndpointArcEqualRadius(Rc::new(
+                    RefCell::new(EndpointArcEqualRadius::new(arc.clone())),
+                )));
+        }
         // Add the primitive to the sketch
         self.primitives.insert(self.primitives_next_id, primitive);
+        if construction {
+            self.construction.insert(self.primitives_next_id);
+        }
         self.primitives_next_id += 1;
 
         Ok(self.primitives_next_id - 1)
     }
 
+    // Checks whether the primitive was added to the sketch or is one of its datums
+    fn contains_primitive(&self, primitive: &PrimitiveCell) -> bool {
+        self.datums.contains(primitive) || self.primitives.iter().any(|(_, p)| p == primitive)
+    }
+
+    pub fn origin(&self) -> Rc<RefCell<Point2>> {
+        self.datums.origin.clone()
+    }
+
+    pub fn x_axis(&self) -> Rc<RefCell<InfiniteLine>> {
+        self.datums.x_axis.clone()
+    }
+
+    pub fn y_axis(&self) -> Rc<RefCell<InfiniteLine>> {
+        self.datums.y_axis.clone()
+    }
+
     pub fn get_num_primitives(&self) -> usize {
         self.primitives.len()
     }
@@ -50,7 +139,7 @@ impl Sketch {
     pub fn add_constraint(&mut self, constraint: ConstraintCell) -> Result<(), ISOTopeError> {
         // Make sure all referenced primitives are added to the sketch before the constraint
         for reference in constraint.borrow().references().iter() {
-            if !self.primitives.iter().any(|(_, p)| p == reference) {
+            if !self.contains_primitive(reference) {
                 return Err(ISOTopeError::MissingSketchReferences);
             }
         }
@@ -69,8 +158,19 @@ impl Sketch {
     }
 
     pub fn delete_primitive(&mut self, id: u64) -> Result<(), ISOTopeError> {
-        if self.primitives.remove(&id).is_none() {
-            return Err(ISOTopeError::PrimitiveNotFound(id));
+        let primitive = self
+            .primitives
+            .remove(&id)
+            .ok_or(ISOTopeError::PrimitiveNotFound(id))?;
+        self.construction.remove(&id);
+        // Remove the implicit constraint that was added together with an endpoint arc
+        if let PrimitiveCell::EndpointArc(arc) = &primitive {
+            self.constraints.retain(|c| match c {
+                ConstraintCell::EndpointArcEqualRadius(constraint) => {
+                    !Rc::ptr_eq(&constraint.borrow().arc(), arc)
+                }
+                _ => true,
+            });
         }
 
         Ok(())
@@ -91,6 +191,34 @@ impl Sketch {
         self.primitives.clone()
     }
 
+    // All primitives except the construction ones, this is the geometry that gets decomposed and exported
+    pub fn non_construction_primitives(&self) -> BTreeMap<u64, PrimitiveCell> {
+        self.primitives
+            .iter()
+            .filter(|(id, _)| !self.construction.contains(id))
+            .map(|(id, p)| (*id, p.clone()))
+            .collect()
+    }
+
+    pub fn is_construction(&self, id: u64) -> Result<bool, ISOTopeError> {
+        if !self.primitives.contains_key(&id) {
+            return Err(ISOTopeError::PrimitiveNotFound(id));
+        }
+        Ok(self.construction.contains(&id))
+    }
+
+    pub fn set_construction(&mut self, id: u64, construction: bool) -> Result<(), ISOTopeError> {
+        if !self.primitives.contains_key(&id) {
+            return Err(ISOTopeError::PrimitiveNotFound(id));
+        }
+        if construction {
+            self.construction.insert(id);
+        } else {
+            self.construction.remove(&id);
+        }
+        Ok(())
+    }
+
     pub fn constraints(&self) -> VecDeque<ConstraintCell> {
         self.constraints.clone()
     }
@@ -128,6 +256,7 @@ impl Sketch {
         for primitive in self.primitives.iter_mut() {
             primitive.1.borrow_mut().zero_gradient();
         }
+        self.datums.zero_gradient();
 
         for constraint in self.constraints.iter_mut() {
             constraint.borrow_mut().update_gradient();
@@ -158,28 +287,93 @@ impl Sketch {
         loss_per_constraint
     }
 
+    // The residuals of all constraints, stacked in the order of the constraints
+    pub fn get_residuals(&self) -> DVector<f64> {
+        let residuals: Vec<DVector<f64>> = self
+            .constraints
+            .iter()
+            .map(|constraint| constraint.borrow().residuals())
+            .collect();
+        let mut stacked = DVector::zeros(residuals.iter().map(|r| r.len()).sum());
+        let mut i = 0;
+        for r in residuals.iter() {
+            stacked.rows_mut(i, r.len()).copy_from(r);
+            i += r.len();
+        }
+        stacked
+    }
+
+    // The jacobian of the residuals returned by get_residuals as a dense matrix
     pub fn get_jacobian(&self) -> DMatrix<f64> {
-        let mut jacobian = DMatrix::zeros(self.constraints.len(), self.get_n_dofs());
-        for (i, constraint) in self.constraints.iter().enumerate() {
-            // Zero the gradients of all primitives
-            for primitive in self.primitives.iter() {
-                primitive.1.borrow_mut().zero_gradient();
-            }
-            // Update the gradient of the constraint
-            constraint.borrow_mut().update_gradient();
-            // Copy the gradient of the constraint to the jacobian
-            let mut j = 0;
-            for primitive in self.primitives.iter() {
-                let p = primitive.1.borrow();
-                let primitive_gradient = p.get_gradient();
-                jacobian
-                    .row_mut(i)
-                    .columns_mut(j, primitive_gradient.len())
-                    .copy_from(&primitive_gradient.transpose());
-                j += primitive_gradient.len();
+        DMatrix::from(&self.get_sparse_jacobian())
+    }
+
+    // The jacobian of the residuals returned by get_residuals. Every constraint only touches the
+    // primitives it references, so only their gradients are evaluated and stored.
+    pub fn get_sparse_jacobian(&self) -> CsrMatrix<f64> {
+        let offsets = self.data_offsets();
+        let num_residuals: Vec<usize> = self
+            .constraints
+            .iter()
+            .map(|constraint| constraint.borrow().residuals().len())
+            .collect();
+        let mut jacobian = CooMatrix::new(num_residuals.iter().sum(), self.get_n_dofs());
+        let mut row = 0;
+        for (constraint, n) in self.constraints.iter().zip(num_residuals) {
+            // The primitives whose data receives the gradient of the constraint, together with the
+            // column of their first value
+            let columns: Vec<(PrimitiveCell, usize)> = gradient_primitives(constraint)
+                .into_iter()
+                .filter_map(|primitive| {
+                    let offset = offsets.get(&(primitive.as_ptr() as *const ()))?;
+                    Some((primitive, *offset))
+                })
+                .collect();
+
+            for k in 0..n {
+                // Zero the gradients of the referenced primitives
+                for (primitive, _) in columns.iter() {
+                    primitive.borrow_mut().zero_gradient();
+                }
+                self.datums.zero_gradient();
+                // Update the gradient of a single residual of the constraint
+                let mut weights = DVector::zeros(n);
+                weights[k] = 1.0;
+                constraint
+                    .borrow_mut()
+                    .update_residual_gradient(weights.as_view());
+                // Copy the non-zero entries of the gradient to the jacobian
+                for (primitive, offset) in columns.iter() {
+                    for (j, value) in primitive.borrow().get_gradient().iter().enumerate() {
+                        if *value != 0.0 {
+                            jacobian.push(row, offset + j, *value);
+                        }
+                    }
+                }
+                row += 1;
             }
         }
-        jacobian
+        CsrMatrix::from(&jacobian)
+    }
+
+    // The index of the first value of every primitive in get_data, keyed by its address
+    fn data_offsets(&self) -> HashMap<*const (), usize> {
+        let mut offsets = HashMap::new();
+        let mut i = 0;
+        for primitive in self.primitives.values() {
+            offsets.insert(primitive.as_ptr() as *const (), i);
+            i += primitive.borrow().get_data().len();
+        }
+        offsets
+    }
+
+    // The number of inequality constraints violated by more than the tolerance
+    pub fn count_violated_inequalities(&self, tolerance: f64) -> usize {
+        self.constraints
+            .iter()
+            .filter(|c| c.borrow().is_inequality())
+            .filter(|c| (2.0 * c.borrow().loss_value()).sqrt() > tolerance)
+            .count()
     }
 
     pub fn set_data(&mut self, data: DVector<f64>) {
@@ -232,6 +426,38 @@ impl Sketch {
             println!("Error: {}", error);
             assert!(error < check_epsilon);
         }
+
+        // Compare the gradient of every single residual to its numerical gradient as well
+        let residuals = constraint.borrow().residuals();
+        for k in 0..residuals.len() {
+            for primitive in self.primitives.iter() {
+                primitive.1.borrow_mut().zero_gradient();
+            }
+            self.datums.zero_gradient();
+            let mut weights = DVector::zeros(residuals.len());
+            weights[k] = 1.0;
+            constraint
+                .borrow_mut()
+                .update_residual_gradient(weights.as_view());
+
+            for primitive in self.primitives.iter() {
+                let original_value = primitive.1.borrow().get_data().clone_owned();
+                let analytical_gradient = primitive.1.borrow().get_gradient().clone_owned();
+                let mut numerical_gradient = DVector::zeros(original_value.len());
+                for i in 0..original_value.len() {
+                    let mut new_value = original_value.clone_owned();
+                    new_value[i] += epsilon;
+                    primitive.1.borrow_mut().set_data(new_value.as_view());
+                    let new_residual = constraint.borrow().residuals()[k];
+                    primitive.1.borrow_mut().set_data(original_value.as_view());
+                    numerical_gradient[i] = (new_residual - residuals[k]) / epsilon;
+                }
+
+                let error = (numerical_gradient - analytical_gradient).norm();
+                println!("Residual {} error: {}", k, error);
+                assert!(error < check_epsilon);
+            }
+        }
     }
 
     // Helper functions
@@ -268,16 +494,111 @@ impl Sketch {
     }
 }
 
+// The references of a constraint and everything they reference in turn, as the gradient of a
+// primitive is passed on to the primitives it references
+fn gradient_primitives(constraint: &ConstraintCell) -> Vec<PrimitiveCell> {
+    let mut primitives: Vec<PrimitiveCell> = vec![];
+    let mut stack = constraint.borrow().references();
+    while let Some(primitive) = stack.pop() {
+        if !primitives.contains(&primitive) {
+            stack.extend(primitive.borrow().references());
+            primitives.push(primitive);
+        }
+    }
+    primitives
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
         constraints::coincident::arc_end_point_coincident::ArcEndPointCoincident,
+        constraints::distance::euclidian_distance_between_points::EuclidianDistanceBetweenPoints,
+        constraints::lines::parallel_lines::ParallelLines,
         examples::test_rectangle_rotated::RotatedRectangleDemo,
-        primitives::{arc::Arc, point2::Point2},
+        primitives::{arc::Arc, line::Line, point2::Point2},
+        solvers::{bfgs_solver::BFGSSolver, Solver},
     };
+    use nalgebra::Vector2;
 
     use super::*;
 
+    #[test]
+    fn test_datums() {
+        let mut sketch = Sketch::new();
+
+        // The datums are fixed and have no degrees of freedom
+        assert_eq!(sketch.get_num_primitives(), 0);
+        assert_eq!(sketch.get_n_dofs(), 0);
+        assert!(sketch
+            .get_primitive_id(&PrimitiveCell::Point2(sketch.origin()))
+            .is_none());
+
+        let point = Rc::new(RefCell::new(Point2::new(3.0, 4.0)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(point.clone()))
+            .unwrap();
+        let line = Rc::new(RefCell::new(Line::new(sketch.origin(), point.clone())));
+        sketch
+            .add_primitive(PrimitiveCell::Line(line.clone()))
+            .unwrap();
+        assert_eq!(sketch.get_n_dofs(), 2);
+
+        sketch
+            .add_constraint(ConstraintCell::EuclideanDistance(Rc::new(RefCell::new(
+                EuclidianDistanceBetweenPoints::new(sketch.origin(), point.clone(), 2.0),
+            ))))
+            .unwrap();
+        sketch
+            .add_constraint(ConstraintCell::ParallelLines(Rc::new(RefCell::new(
+                ParallelLines::new(line.clone(), sketch.y_axis()),
+            ))))
+            .unwrap();
+
+        BFGSSolver::new().solve(&mut sketch).unwrap();
+
+        // Only the point moved, so the line ends up on the y axis
+        assert!(point.borrow().data().x.abs() < 1e-4);
+        assert!((point.borrow().data().y - 2.0).abs() < 1e-4);
+        assert_eq!(sketch.origin().borrow().data(), Vector2::new(0.0, 0.0));
+        assert_eq!(
+            sketch.y_axis().borrow().angle(),
+            std::f64::consts::FRAC_PI_2
+        );
+    }
+
+    #[test]
+    fn test_construction_flag() {
+        let mut sketch = Sketch::new();
+
+        let point_a = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let point_b = Rc::new(RefCell::new(Point2::new(1.0, 0.0)));
+        let id_a = sketch
+            .add_primitive(PrimitiveCell::Point2(point_a.clone()))
+            .unwrap();
+        let id_b = sketch
+            .add_primitive_with_construction(PrimitiveCell::Point2(point_b.clone()), true)
+            .unwrap();
+
+        assert!(!sketch.is_construction(id_a).unwrap());
+        assert!(sketch.is_construction(id_b).unwrap());
+        assert_eq!(sketch.non_construction_primitives().len(), 1);
+        // Construction primitives are still solved for
+        assert_eq!(sketch.get_n_dofs(), 4);
+
+        sketch.set_construction(id_a, true).unwrap();
+        sketch.set_construction(id_b, false).unwrap();
+        assert!(sketch.is_construction(id_a).unwrap());
+        assert!(!sketch.is_construction(id_b).unwrap());
+
+        sketch.delete_primitive(id_a).unwrap();
+        assert!(matches!(
+            sketch.is_construction(id_a),
+            Err(ISOTopeError::PrimitiveNotFound(_))
+        ));
+        assert!(sketch.set_construction(id_a, false).is_err());
+        assert_eq!(sketch.non_construction_primitives().len(), 1);
+    }
+
     #[test]
     fn test_references_have_to_be_added_beforehand() {
         assert!(std::panic::catch_unwind(|| {
@@ -364,4 +685,40 @@ mod tests {
         sketch.get_loss_per_constraint();
         sketch.get_jacobian();
     }
+
+    #[test]
+    fn test_sparse_jacobian() {
+        let rect = RotatedRectangleDemo::new();
+        let sketch = rect.sketch.borrow();
+
+        let jacobian = sketch.get_jacobian();
+        let sparse_jacobian = sketch.get_sparse_jacobian();
+        assert_eq!(sparse_jacobian.nrows(), sketch.get_residuals().len());
+        assert_eq!(sparse_jacobian.ncols(), sketch.get_n_dofs());
+        assert!(sparse_jacobian.nnz() < jacobian.len());
+
+        // Compare to the jacobian assembled from the gradients of all primitives
+        let mut row = 0;
+        for constraint in sketch.constraints().iter() {
+            let n = constraint.borrow().residuals().len();
+            for k in 0..n {
+                let mut weights = DVector::zeros(n);
+                weights[k] = 1.0;
+                for primitive in sketch.primitives().values() {
+                    primitive.borrow_mut().zero_gradient();
+                }
+                constraint
+                    .borrow_mut()
+                    .update_residual_gradient(weights.as_view());
+                let mut j = 0;
+                for primitive in sketch.primitives().values() {
+                    for value in primitive.borrow().get_gradient().iter() {
+                        assert_eq!(jacobian[(row, j)], *value);
+                        j += 1;
+                    }
+                }
+                row += 1;
+            }
+        }
+    }
 }
@@ -1,11 +1,12 @@
-use std::error::Error;
+use std::time::Instant;
 
 use nalgebra::{DMatrix, UniformNorm};
 
+use crate::error::SolverError;
 use crate::sketch::Sketch;
-use crate::solvers::line_search::{line_search_wolfe, LineSearchError};
+use crate::solvers::line_search::line_search_wolfe;
 
-use super::Solver;
+use super::{SolveReport, SolveStatus, Solver};
 
 pub struct BFGSSolver {
     max_iterations: usize,
@@ -38,7 +39,10 @@ impl BFGSSolver {
 }
 
 impl Solver for BFGSSolver {
-    fn solve(&self, sketch: &mut Sketch) -> Result<(), Box<dyn Error>> {
+    fn solve(&self, sketch: &mut Sketch) -> Result<SolveReport, SolverError> {
+        let start = Instant::now();
+        let initial_loss = sketch.get_loss();
+        let mut status = SolveStatus::MaxIterations;
         let mut iterations = 0;
         let mut data = sketch.get_data();
         let n = data.len();
@@ -50,38 +54,43 @@ impl Solver for BFGSSolver {
         while iterations < self.max_iterations {
             let loss = sketch.get_loss();
             if loss < self.min_loss {
+                status = SolveStatus::Converged;
                 break;
             }
 
             let gradient = sketch.get_gradient();
             if !gradient.iter().all(|x| x.is_finite()) {
-                return Err("gradient contains non-finite values".into());
+                return Err(SolverError::NonFiniteGradient);
             }
 
             if gradient.apply_norm(&UniformNorm) < self.gradient_threshold {
+                status = SolveStatus::Stalled;
                 break;
             }
 
             let p = -(&h) * &gradient;
             if !p.iter().all(|x| x.is_finite()) {
-                return Err("search direction contains non-finite values".into());
+                return Err(SolverError::NonFiniteSearchDirection);
             }
 
             let alpha = match line_search_wolfe(sketch, &p, &gradient) {
                 Ok(alpha) => alpha,
-                Err(LineSearchError::SearchFailed) => {
+                Err(SolverError::LineSearchFailed) => {
                     // If the line search could not find a suitable step size, the Hessian
                     // approximation may be inaccurate. Resetting the Hessian to the identity matrix
                     // will restart with a steepest descent step and hopefully build a better
-                    // approximation.
+                    // approximation. If even that fails, no more progress can be made. Either way
+                    // the sketch is moved back from the last rejected trial point.
+                    sketch.set_data(data.clone());
                     if recently_reset {
-                        return Err("bfgs: line search failed twice in a row".into());
+                        status = SolveStatus::Stalled;
+                        break;
                     }
                     h = DMatrix::identity(n, n);
                     recently_reset = true;
                     continue;
                 }
-                Err(e) => return Err(e.into()),
+                Err(e) => return Err(e),
             };
 
             recently_reset = false;
@@ -115,7 +124,13 @@ impl Solver for BFGSSolver {
             iterations += 1;
         }
 
-        Ok(())
+        Ok(SolveReport::new(
+            sketch,
+            status,
+            iterations,
+            initial_loss,
+            start.elapsed(),
+        ))
     }
 }
 
@@ -126,7 +141,11 @@ mod tests {
 
     use crate::{
         examples::test_rectangle_rotated::RotatedRectangleDemo,
-        solvers::{bfgs_solver::BFGSSolver, Solver},
+        solvers::{
+            bfgs_solver::BFGSSolver,
+            tests::{check_line_search_failure, distance_sketch},
+            SolveStatus, Solver,
+        },
     };
 
     #[test]
@@ -151,4 +170,39 @@ mod tests {
 
         rectangle.check(1e-5)
     }
+
+    #[test]
+    pub fn test_bfgs_solve_report() {
+        let mut sketch = distance_sketch(&[2.0]);
+        let report = BFGSSolver::new().solve(&mut sketch).unwrap();
+        assert_eq!(report.status, SolveStatus::Converged);
+        assert!(report.iterations > 0);
+        assert!(report.final_loss < 1e-16);
+        assert!(report.max_residual < 1e-8);
+        assert_eq!(report.constraint_losses.len(), 1);
+
+        // Nothing left to do
+        let report = BFGSSolver::new().solve(&mut sketch).unwrap();
+        assert_eq!(report.status, SolveStatus::Converged);
+        assert_eq!(report.iterations, 0);
+
+        let mut sketch = distance_sketch(&[2.0]);
+        let report = BFGSSolver::new_with_params(1, 1e-16, 1e-8)
+            .solve(&mut sketch)
+            .unwrap();
+        assert_eq!(report.status, SolveStatus::MaxIterations);
+        assert_eq!(report.iterations, 1);
+
+        // The constraints cannot be satisfied at the same time
+        let mut sketch = distance_sketch(&[1.0, 2.0]);
+        let report = BFGSSolver::new().solve(&mut sketch).unwrap();
+        assert_eq!(report.status, SolveStatus::Stalled);
+        assert!((report.final_loss - 0.25).abs() < 1e-6);
+        assert!((report.max_residual - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    pub fn test_bfgs_line_search_failure() {
+        check_line_search_failure(&BFGSSolver::new());
+    }
 }
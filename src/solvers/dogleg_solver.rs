@@ -0,0 +1,229 @@
+use std::time::Instant;
+
+use nalgebra::DVector;
+
+use crate::error::SolverError;
+use crate::sketch::Sketch;
+
+use super::{NormalEquations, SolveReport, SolveStatus, Solver};
+
+// A trust region solver with the dogleg step of Powell. Every step combines the gauss-newton step
+// and the steepest descent step of the linearized residuals, so that it stays inside of a region
+// in which the linearization is trusted. The region grows and shrinks with how well the
+// linearization predicted the decrease of the loss, which replaces the line search.
+pub struct DoglegSolver {
+    max_iterations: usize,
+    min_loss: f64,
+    // The initial radius of the trust region relative to the norm of the data of the sketch. Like
+    // in MINPACK it is large by default, so the first steps are plain gauss-newton steps.
+    initial_radius_factor: f64,
+    gradient_threshold: f64,
+    step_threshold: f64,
+    // The damping of the normal equations relative to the largest diagonal entry of J^T * J
+    regularization: f64,
+}
+
+impl Default for DoglegSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DoglegSolver {
+    pub fn new() -> Self {
+        Self {
+            max_iterations: 1000,
+            min_loss: 1e-16,
+            initial_radius_factor: 100.0,
+            gradient_threshold: 1e-12,
+            step_threshold: 1e-14,
+            regularization: 1e-10,
+        }
+    }
+
+    pub fn new_with_params(
+        max_iterations: usize,
+        min_loss: f64,
+        initial_radius_factor: f64,
+    ) -> Self {
+        Self {
+            max_iterations,
+            min_loss,
+            initial_radius_factor,
+            gradient_threshold: 1e-12,
+            step_threshold: 1e-14,
+            regularization: 1e-10,
+        }
+    }
+}
+
+// The dogleg step inside of a trust region with the given radius. It follows the steepest descent
+// step to its minimum along the gradient and then turns towards the gauss-newton step.
+fn dogleg_step(
+    gauss_newton_step: Option<&DVector<f64>>,
+    steepest_descent_step: &DVector<f64>,
+    radius: f64,
+) -> DVector<f64> {
+    let steepest_descent_norm = steepest_descent_step.norm();
+    let Some(gauss_newton_step) = gauss_newton_step else {
+        // Without a gauss-newton step only the steepest descent remains
+        return steepest_descent_step * (radius / steepest_descent_norm).min(1.0);
+    };
+
+    if gauss_newton_step.norm() <= radius {
+        return gauss_newton_step.clone();
+    }
+    if steepest_descent_norm >= radius {
+        return steepest_descent_step * (radius / steepest_descent_norm);
+    }
+
+    // Find beta in [0, 1] with |a + beta * (b - a)| = radius
+    let a = steepest_descent_step;
+    let b_minus_a = gauss_newton_step - a;
+    let c = a.dot(&b_minus_a);
+    let b_minus_a_squared = b_minus_a.norm_squared();
+    let radius_gap = radius * radius - a.norm_squared();
+    let root = (c * c + b_minus_a_squared * radius_gap).sqrt();
+    // Two algebraically equal forms, chosen to avoid cancellation
+    let beta = if c <= 0.0 {
+        (root - c) / b_minus_a_squared
+    } else {
+        radius_gap / (c + root)
+    };
+    a + beta * b_minus_a
+}
+
+impl Solver for DoglegSolver {
+    fn solve(&self, sketch: &mut Sketch) -> Result<SolveReport, SolverError> {
+        let start = Instant::now();
+        let initial_loss = sketch.get_loss();
+        let mut status = SolveStatus::MaxIterations;
+        let mut iterations = 0;
+
+        let mut data = sketch.get_data();
+        let mut residuals = sketch.get_residuals();
+        let mut jacobian = sketch.get_sparse_jacobian();
+        let mut loss = 0.5 * residuals.norm_squared();
+        let mut radius = self.initial_radius_factor * data.norm().max(1.0);
+
+        while iterations < self.max_iterations {
+            if loss < self.min_loss {
+                status = SolveStatus::Converged;
+                break;
+            }
+
+            let normal_equations = NormalEquations::new(&jacobian, &residuals);
+            let gradient = &normal_equations.gradient;
+            if gradient.amax() < self.gradient_threshold {
+                status = SolveStatus::Stalled;
+                break;
+            }
+
+            // The minimum of the linearized loss along the negative gradient
+            let jacobian_gradient = &jacobian * gradient;
+            let alpha = gradient.norm_squared() / jacobian_gradient.norm_squared();
+            let steepest_descent_step = -alpha * gradient;
+
+            // J^T * J is singular as soon as the constraints leave degrees of freedom open or
+            // contradict each other. The regularization picks the gauss-newton step with (almost)
+            // the smallest norm, and if even that fails the steepest descent step is used alone.
+            let gauss_newton_step = normal_equations
+                .solve_damped(self.regularization * normal_equations.max_diagonal().max(1.0))
+                .ok()
+                .filter(|step| step.iter().all(|x| x.is_finite()));
+
+            let step = dogleg_step(gauss_newton_step.as_ref(), &steepest_descent_step, radius);
+            if step.norm() <= self.step_threshold * (data.norm() + self.step_threshold) {
+                status = SolveStatus::Stalled;
+                break;
+            }
+
+            let new_data = &data + &step;
+            sketch.set_data(new_data.clone());
+            let new_residuals = sketch.get_residuals();
+            let new_loss = 0.5 * new_residuals.norm_squared();
+
+            // Ratio of the actual decrease of the loss to the one predicted by the linearization
+            let predicted_decrease =
+                -gradient.dot(&step) - 0.5 * (&jacobian * &step).norm_squared();
+            let rho = (loss - new_loss) / predicted_decrease;
+
+            if rho > 0.75 {
+                radius = radius.max(3.0 * step.norm());
+            } else if rho < 0.25 || !rho.is_finite() {
+                radius = 0.5 * step.norm();
+            }
+
+            if rho > 0.0 && new_loss.is_finite() {
+                data = new_data;
+                residuals = new_residuals;
+                jacobian = sketch.get_sparse_jacobian();
+                loss = new_loss;
+            } else {
+                sketch.set_data(data.clone());
+            }
+
+            iterations += 1;
+        }
+
+        Ok(SolveReport::new(
+            sketch,
+            status,
+            iterations,
+            initial_loss,
+            start.elapsed(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::ops::DerefMut;
+
+    use crate::{
+        examples::test_rectangle_rotated::RotatedRectangleDemo,
+        solvers::{dogleg_solver::DoglegSolver, tests::distance_sketch, SolveStatus, Solver},
+    };
+
+    #[test]
+    pub fn test_dogleg_solver() -> Result<(), Box<dyn Error>> {
+        let rectangle = RotatedRectangleDemo::new();
+
+        // Now solve the sketch
+        let solver = DoglegSolver::new();
+        let report = solver
+            .solve(rectangle.sketch.borrow_mut().deref_mut())
+            .unwrap();
+
+        println!("loss: {:?}", rectangle.sketch.borrow_mut().get_loss());
+        println!("point_a: {:?}", rectangle.point_a.as_ref().borrow());
+        println!("point_b: {:?}", rectangle.point_b.as_ref().borrow());
+        println!("point_c: {:?}", rectangle.point_c.as_ref().borrow());
+        println!("point_d: {:?}", rectangle.point_d.as_ref().borrow());
+        println!(
+            "point_reference: {:?}",
+            rectangle.point_reference.as_ref().borrow()
+        );
+
+        assert_eq!(report.status, SolveStatus::Converged);
+        rectangle.check(1e-5)
+    }
+
+    #[test]
+    pub fn test_dogleg_solver_rank_deficient() {
+        // The same constraint twice leaves the jacobian with a single independent row for four
+        // degrees of freedom
+        let mut sketch = distance_sketch(&[2.0, 2.0]);
+        let report = DoglegSolver::new().solve(&mut sketch).unwrap();
+        assert_eq!(report.status, SolveStatus::Converged);
+        assert!(report.max_residual < 1e-8);
+
+        // The constraints cannot be satisfied at the same time
+        let mut sketch = distance_sketch(&[1.0, 2.0]);
+        let report = DoglegSolver::new().solve(&mut sketch).unwrap();
+        assert_eq!(report.status, SolveStatus::Stalled);
+        assert!((report.final_loss - 0.25).abs() < 1e-6);
+        assert!((report.max_residual - 0.5).abs() < 1e-6);
+    }
+}
@@ -1,14 +1,18 @@
-use std::error::Error;
+use std::time::Instant;
 
+use crate::error::SolverError;
 use crate::sketch::Sketch;
 
-use super::Solver;
+use super::{NormalEquations, SolveReport, SolveStatus, Solver};
+
+// How often the step is halved before giving up on decreasing the loss
+const MAX_STEP_HALVINGS: usize = 30;
 
 pub struct GaussNewtonSolver {
     max_iterations: usize,
     min_loss: f64,
-    step_size: f64,
-    pseudo_inverse_eps: f64,
+    // The damping of the normal equations relative to the largest diagonal entry of J^T * J
+    regularization: f64,
 }
 
 impl Default for GaussNewtonSolver {
@@ -20,46 +24,72 @@ impl Default for GaussNewtonSolver {
 impl GaussNewtonSolver {
     pub fn new() -> Self {
         Self {
-            max_iterations: 10000,
-            min_loss: 1e-6,
-            step_size: 1e-3,
-            pseudo_inverse_eps: 1e-6,
+            max_iterations: 100,
+            min_loss: 1e-16,
+            regularization: 1e-10,
         }
     }
 
-    pub fn new_with_params(max_iterations: usize, min_loss: f64, step_size: f64) -> Self {
+    pub fn new_with_params(max_iterations: usize, min_loss: f64) -> Self {
         Self {
             max_iterations,
             min_loss,
-            step_size,
-            pseudo_inverse_eps: 1e-6,
+            regularization: 1e-10,
         }
     }
 }
 
 impl Solver for GaussNewtonSolver {
-    fn solve(&self, sketch: &mut Sketch) -> Result<(), Box<dyn Error>> {
+    fn solve(&self, sketch: &mut Sketch) -> Result<SolveReport, SolverError> {
+        let start = Instant::now();
+        let initial_loss = sketch.get_loss();
+        let mut status = SolveStatus::MaxIterations;
         let mut iterations = 0;
-        let mut loss_sum = f64::INFINITY;
-
-        while iterations < self.max_iterations && loss_sum > self.min_loss {
-            let mut data = sketch.get_data();
-            let losses = sketch.get_loss_per_constraint();
-            loss_sum = losses.sum();
-            let jacobian = sketch.get_jacobian();
 
-            data -= (jacobian.transpose() * jacobian.clone())
-                .clone()
-                .pseudo_inverse(self.pseudo_inverse_eps)?
-                * &jacobian.transpose()
-                * &losses
-                * self.step_size;
-
-            sketch.set_data(data);
+        while iterations < self.max_iterations {
+            let residuals = sketch.get_residuals();
+            let loss = 0.5 * residuals.norm_squared();
+            if loss < self.min_loss {
+                status = SolveStatus::Converged;
+                break;
+            }
+
+            // J^T * J is singular as soon as the constraints leave degrees of freedom open. A tiny
+            // regularization picks the solution of J * step = -r with (almost) the smallest norm, so
+            // these degrees of freedom stay where they are.
+            let normal_equations = NormalEquations::new(&sketch.get_sparse_jacobian(), &residuals);
+            let step = normal_equations
+                .solve_damped(self.regularization * normal_equations.max_diagonal().max(1.0))?;
+
+            // The linearization may overshoot far away from the solution, so shorten the step until
+            // it actually decreases the loss
+            let data = sketch.get_data();
+            let mut alpha = 1.0;
+            let mut decreased = false;
+            for _ in 0..MAX_STEP_HALVINGS {
+                sketch.set_data(&data + alpha * &step);
+                if sketch.get_loss() < loss {
+                    decreased = true;
+                    break;
+                }
+                alpha *= 0.5;
+            }
+            if !decreased {
+                sketch.set_data(data);
+                status = SolveStatus::Stalled;
+                break;
+            }
 
             iterations += 1;
         }
-        Ok(())
+
+        Ok(SolveReport::new(
+            sketch,
+            status,
+            iterations,
+            initial_loss,
+            start.elapsed(),
+        ))
     }
 }
 
@@ -78,7 +108,7 @@ mod tests {
         let rectangle = RotatedRectangleDemo::new();
 
         // Now solve the sketch
-        let solver = GaussNewtonSolver::new_with_params(500, 1e-8, 1e0);
+        let solver = GaussNewtonSolver::new();
         solver
             .solve(rectangle.sketch.borrow_mut().deref_mut())
             .unwrap();
@@ -90,6 +120,6 @@ mod tests {
         println!("point_d: {:?}", rectangle.point_d.as_ref());
         println!("point_reference: {:?}", rectangle.point_reference.as_ref());
 
-        rectangle.check(1e-1)
+        rectangle.check(1e-5)
     }
 }
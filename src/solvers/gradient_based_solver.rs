@@ -1,9 +1,10 @@
-use std::error::Error;
+use std::time::Instant;
 
+use crate::error::SolverError;
 use crate::sketch::Sketch;
 use crate::solvers::line_search::line_search_wolfe;
 
-use super::Solver;
+use super::{SolveReport, SolveStatus, Solver};
 
 pub struct GradientBasedSolver {
     max_iterations: usize,
@@ -36,23 +37,37 @@ impl GradientBasedSolver {
 }
 
 impl Solver for GradientBasedSolver {
-    fn solve(&self, sketch: &mut Sketch) -> Result<(), Box<dyn Error>> {
+    fn solve(&self, sketch: &mut Sketch) -> Result<SolveReport, SolverError> {
+        let start = Instant::now();
+        let mut status = SolveStatus::MaxIterations;
         let mut iterations = 0;
 
         let mut gradient = sketch.get_gradient();
         let mut grad_norm = gradient.norm();
         let mut loss = sketch.get_loss();
+        let initial_loss = loss;
         while iterations < self.max_iterations {
-            if grad_norm < self.min_grad {
+            if loss < self.min_loss {
+                status = SolveStatus::Converged;
                 break;
             }
-            if loss < self.min_loss {
+            if grad_norm < self.min_grad {
+                status = SolveStatus::Stalled;
                 break;
             }
             let mut data = sketch.get_data();
 
             let direction = -&gradient;
-            let alpha = line_search_wolfe(sketch, &direction, &gradient)?;
+            let alpha = match line_search_wolfe(sketch, &direction, &gradient) {
+                Ok(alpha) => alpha,
+                Err(SolverError::LineSearchFailed) => {
+                    // The line search leaves the sketch at its last rejected trial point
+                    sketch.set_data(data);
+                    status = SolveStatus::Stalled;
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
             // data = data + alpha * direction
             data.axpy(alpha, &direction, 1.0);
             sketch.set_data(data);
@@ -65,6 +80,24 @@ impl Solver for GradientBasedSolver {
             iterations += 1;
         }
 
-        Ok(())
+        Ok(SolveReport::new(
+            sketch,
+            status,
+            iterations,
+            initial_loss,
+            start.elapsed(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{
+        gradient_based_solver::GradientBasedSolver, tests::check_line_search_failure,
+    };
+
+    #[test]
+    pub fn test_gradient_based_line_search_failure() {
+        check_line_search_failure(&GradientBasedSolver::new());
     }
 }
@@ -0,0 +1,226 @@
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use nalgebra::{DVector, UniformNorm};
+
+use crate::error::SolverError;
+use crate::sketch::Sketch;
+use crate::solvers::line_search::line_search_wolfe;
+
+use super::{SolveReport, SolveStatus, Solver};
+
+// The limited memory variant of the BFGS solver. Instead of a dense approximation of the inverse
+// hessian it only keeps the last few steps and gradient changes, so memory and time per iteration
+// grow linearly with the number of degrees of freedom.
+pub struct LBFGSSolver {
+    max_iterations: usize,
+    min_loss: f64,
+    gradient_threshold: f64,
+    // The number of past steps used to approximate the inverse hessian
+    history_length: usize,
+}
+
+impl Default for LBFGSSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LBFGSSolver {
+    pub fn new() -> Self {
+        Self {
+            max_iterations: 1000,
+            min_loss: 1e-16,
+            gradient_threshold: 1e-8,
+            history_length: 10,
+        }
+    }
+
+    pub fn new_with_params(
+        max_iterations: usize,
+        min_loss: f64,
+        gradient_threshold: f64,
+        history_length: usize,
+    ) -> Self {
+        Self {
+            max_iterations,
+            min_loss,
+            gradient_threshold,
+            history_length,
+        }
+    }
+}
+
+// A past step s, the change y of the gradient along it and 1 / (s * y)
+struct Correction {
+    s: DVector<f64>,
+    y: DVector<f64>,
+    rho: f64,
+}
+
+// The two loop recursion, which multiplies the gradient with the inverse hessian approximated from
+// the corrections without ever forming it
+fn search_direction(gradient: &DVector<f64>, history: &VecDeque<Correction>) -> DVector<f64> {
+    let mut q = gradient.clone();
+    let mut alphas = Vec::with_capacity(history.len());
+    for correction in history.iter().rev() {
+        let alpha = correction.rho * correction.s.dot(&q);
+        q.axpy(-alpha, &correction.y, 1.0);
+        alphas.push(alpha);
+    }
+
+    // Scale the initial hessian like the most recent curvature
+    if let Some(last) = history.back() {
+        q *= 1.0 / (last.rho * last.y.norm_squared());
+    }
+
+    for (correction, alpha) in history.iter().zip(alphas.iter().rev()) {
+        let beta = correction.rho * correction.y.dot(&q);
+        q.axpy(alpha - beta, &correction.s, 1.0);
+    }
+    -q
+}
+
+impl Solver for LBFGSSolver {
+    fn solve(&self, sketch: &mut Sketch) -> Result<SolveReport, SolverError> {
+        let start = Instant::now();
+        let initial_loss = sketch.get_loss();
+        let mut status = SolveStatus::MaxIterations;
+        let mut iterations = 0;
+        let mut data = sketch.get_data();
+
+        let mut history: VecDeque<Correction> = VecDeque::with_capacity(self.history_length);
+
+        let mut recently_reset = false;
+
+        while iterations < self.max_iterations {
+            let loss = sketch.get_loss();
+            if loss < self.min_loss {
+                status = SolveStatus::Converged;
+                break;
+            }
+
+            let gradient = sketch.get_gradient();
+            if !gradient.iter().all(|x| x.is_finite()) {
+                return Err(SolverError::NonFiniteGradient);
+            }
+
+            if gradient.apply_norm(&UniformNorm) < self.gradient_threshold {
+                status = SolveStatus::Stalled;
+                break;
+            }
+
+            let p = search_direction(&gradient, &history);
+            if !p.iter().all(|x| x.is_finite()) {
+                return Err(SolverError::NonFiniteSearchDirection);
+            }
+
+            let alpha = match line_search_wolfe(sketch, &p, &gradient) {
+                Ok(alpha) => alpha,
+                Err(SolverError::LineSearchFailed) => {
+                    // Just like the BFGS solver, forget the curvature information and restart with
+                    // a steepest descent step. If even that fails, no more progress can be made.
+                    sketch.set_data(data.clone());
+                    if recently_reset {
+                        status = SolveStatus::Stalled;
+                        break;
+                    }
+                    history.clear();
+                    recently_reset = true;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+
+            recently_reset = false;
+
+            let s = alpha * &p;
+
+            let new_data = &data + &s;
+            sketch.set_data(new_data.clone());
+            data = new_data;
+
+            let new_gradient = sketch.get_gradient();
+            let y = &new_gradient - &gradient;
+
+            // Only keep corrections with positive curvature, which keeps the approximated inverse
+            // hessian positive definite
+            let s_dot_y = s.dot(&y);
+            if s_dot_y > 1e-16 && self.history_length > 0 {
+                if history.len() == self.history_length {
+                    history.pop_front();
+                }
+                history.push_back(Correction {
+                    s,
+                    y,
+                    rho: 1.0 / s_dot_y,
+                });
+            }
+
+            iterations += 1;
+        }
+
+        Ok(SolveReport::new(
+            sketch,
+            status,
+            iterations,
+            initial_loss,
+            start.elapsed(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::ops::DerefMut;
+
+    use crate::{
+        examples::test_rectangle_rotated::RotatedRectangleDemo,
+        solvers::{
+            lbfgs_solver::LBFGSSolver, tests::check_line_search_failure, SolveStatus, Solver,
+        },
+    };
+
+    #[test]
+    pub fn test_lbfgs_solver() -> Result<(), Box<dyn Error>> {
+        let rectangle = RotatedRectangleDemo::new();
+
+        // Now solve the sketch
+        let solver = LBFGSSolver::new();
+        let report = solver
+            .solve(rectangle.sketch.borrow_mut().deref_mut())
+            .unwrap();
+
+        println!("loss: {:?}", rectangle.sketch.borrow_mut().get_loss());
+        println!("point_a: {:?}", rectangle.point_a.as_ref().borrow());
+        println!("point_b: {:?}", rectangle.point_b.as_ref().borrow());
+        println!("point_c: {:?}", rectangle.point_c.as_ref().borrow());
+        println!("point_d: {:?}", rectangle.point_d.as_ref().borrow());
+        println!(
+            "point_reference: {:?}",
+            rectangle.point_reference.as_ref().borrow()
+        );
+
+        assert_eq!(report.status, SolveStatus::Converged);
+        rectangle.check(1e-5)
+    }
+
+    #[test]
+    pub fn test_lbfgs_solver_short_history() -> Result<(), Box<dyn Error>> {
+        let rectangle = RotatedRectangleDemo::new();
+
+        // Even the last two steps carry enough curvature information
+        let solver = LBFGSSolver::new_with_params(1000, 1e-16, 1e-8, 2);
+        solver
+            .solve(rectangle.sketch.borrow_mut().deref_mut())
+            .unwrap();
+
+        rectangle.check(1e-5)
+    }
+
+    #[test]
+    pub fn test_lbfgs_line_search_failure() {
+        check_line_search_failure(&LBFGSSolver::new());
+    }
+}
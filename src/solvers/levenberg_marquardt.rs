@@ -1,17 +1,17 @@
-use std::error::Error;
-
-use nalgebra::DMatrix;
+use std::time::Instant;
 
+use crate::error::SolverError;
 use crate::sketch::Sketch;
 
-use super::Solver;
+use super::{NormalEquations, SolveReport, SolveStatus, Solver};
 
 pub struct LevenbergMarquardtSolver {
     max_iterations: usize,
     min_loss: f64,
-    step_size: f64,
-    pseudo_inverse_eps: f64,
-    beta: f64,
+    // The initial damping relative to the largest diagonal entry of J^T * J
+    initial_damping: f64,
+    gradient_threshold: f64,
+    step_threshold: f64,
 }
 
 impl Default for LevenbergMarquardtSolver {
@@ -24,53 +24,94 @@ impl LevenbergMarquardtSolver {
     pub fn new() -> Self {
         Self {
             max_iterations: 1000,
-            min_loss: 1e-10,
-            step_size: 1e-1,
-            pseudo_inverse_eps: 1e-6,
-            beta: 1e-5,
+            min_loss: 1e-16,
+            initial_damping: 1e-3,
+            gradient_threshold: 1e-12,
+            step_threshold: 1e-14,
         }
     }
 
-    pub fn new_with_params(
-        max_iterations: usize,
-        min_loss: f64,
-        step_size: f64,
-        beta: f64,
-    ) -> Self {
+    pub fn new_with_params(max_iterations: usize, min_loss: f64, initial_damping: f64) -> Self {
         Self {
             max_iterations,
             min_loss,
-            step_size,
-            pseudo_inverse_eps: 1e-6,
-            beta,
+            initial_damping,
+            gradient_threshold: 1e-12,
+            step_threshold: 1e-14,
         }
     }
 }
 
 impl Solver for LevenbergMarquardtSolver {
-    fn solve(&self, sketch: &mut Sketch) -> Result<(), Box<dyn Error>> {
+    fn solve(&self, sketch: &mut Sketch) -> Result<SolveReport, SolverError> {
+        let start = Instant::now();
+        let initial_loss = sketch.get_loss();
+        let mut status = SolveStatus::MaxIterations;
         let mut iterations = 0;
-        let mut loss_sum = f64::INFINITY;
-
-        while iterations < self.max_iterations && loss_sum > self.min_loss {
-            let mut data = sketch.get_data();
-            let losses = sketch.get_loss_per_constraint();
-            loss_sum = losses.sum();
-            let jacobian = sketch.get_jacobian();
 
-            data -= (jacobian.transpose() * jacobian.clone()
-                + self.beta * DMatrix::identity(jacobian.ncols(), jacobian.ncols()))
-            .clone()
-            .pseudo_inverse(self.pseudo_inverse_eps)?
-                * &jacobian.transpose()
-                * &losses
-                * self.step_size;
-
-            sketch.set_data(data);
+        let mut data = sketch.get_data();
+        let mut residuals = sketch.get_residuals();
+        let mut jacobian = sketch.get_sparse_jacobian();
+        let mut loss = 0.5 * residuals.norm_squared();
+
+        // The damping is adapted after every step with the updates of Nielsen: it shrinks when the
+        // linear model predicts the decrease of the loss well and grows ever faster on failed steps
+        let mut damping: Option<f64> = None;
+        let mut growth = 2.0;
+
+        while iterations < self.max_iterations {
+            if loss < self.min_loss {
+                status = SolveStatus::Converged;
+                break;
+            }
+
+            let normal_equations = NormalEquations::new(&jacobian, &residuals);
+            let gradient = &normal_equations.gradient;
+            if gradient.amax() < self.gradient_threshold {
+                status = SolveStatus::Stalled;
+                break;
+            }
+
+            let mu = *damping
+                .get_or_insert_with(|| self.initial_damping * normal_equations.max_diagonal());
+            let step = normal_equations.solve_damped(mu)?;
+            if step.norm() <= self.step_threshold * (data.norm() + self.step_threshold) {
+                status = SolveStatus::Stalled;
+                break;
+            }
+
+            let new_data = &data + &step;
+            sketch.set_data(new_data.clone());
+            let new_residuals = sketch.get_residuals();
+            let new_loss = 0.5 * new_residuals.norm_squared();
+
+            // Ratio of the actual decrease of the loss to the one predicted by the linear model
+            let predicted_decrease = 0.5 * step.dot(&(mu * &step - gradient));
+            let rho = (loss - new_loss) / predicted_decrease;
+
+            if rho > 0.0 && new_loss.is_finite() {
+                data = new_data;
+                residuals = new_residuals;
+                jacobian = sketch.get_sparse_jacobian();
+                loss = new_loss;
+                damping = Some(mu * (1.0 / 3.0f64).max(1.0 - (2.0 * rho - 1.0).powi(3)));
+                growth = 2.0;
+            } else {
+                sketch.set_data(data.clone());
+                damping = Some(mu * growth);
+                growth *= 2.0;
+            }
 
             iterations += 1;
         }
-        Ok(())
+
+        Ok(SolveReport::new(
+            sketch,
+            status,
+            iterations,
+            initial_loss,
+            start.elapsed(),
+        ))
     }
 }
 
@@ -89,7 +130,7 @@ mod tests {
         let rectangle = RotatedRectangleDemo::new();
 
         // Now solve the sketch
-        let solver = LevenbergMarquardtSolver::new_with_params(1000, 1e-10, 1e-1, 1e-5);
+        let solver = LevenbergMarquardtSolver::new();
         solver
             .solve(rectangle.sketch.borrow_mut().deref_mut())
             .unwrap();
@@ -101,6 +142,6 @@ mod tests {
         println!("point_d: {:?}", rectangle.point_d.as_ref());
         println!("point_reference: {:?}", rectangle.point_reference.as_ref());
 
-        rectangle.check(1e-1)
+        rectangle.check(1e-5)
     }
 }
@@ -1,14 +1,6 @@
+use crate::error::SolverError;
 use crate::sketch::Sketch;
 use nalgebra::DVector;
-use thiserror::Error;
-
-#[derive(Debug, Error)]
-pub enum LineSearchError {
-    #[error("line search failed: search direction is not a descent direction")]
-    NotDescentDirection,
-    #[error("line search failed: could not find a suitable step size")]
-    SearchFailed,
-}
 
 const WOLFE_C1: f64 = 1e-4;
 const WOLFE_C2: f64 = 0.9;
@@ -18,11 +10,11 @@ pub(crate) fn line_search_wolfe(
     sketch: &mut Sketch,
     direction: &DVector<f64>,
     gradient: &DVector<f64>,
-) -> Result<f64, LineSearchError> {
+) -> Result<f64, SolverError> {
     let mut alpha = 1.0;
     let m = gradient.dot(direction);
     if m >= 0.0 {
-        return Err(LineSearchError::NotDescentDirection);
+        return Err(SolverError::NotDescentDirection);
     }
     let curvature_condition = WOLFE_C2 * m;
     let loss = sketch.get_loss();
@@ -44,5 +36,5 @@ pub(crate) fn line_search_wolfe(
             alpha *= 0.5;
         }
     }
-    Err(LineSearchError::SearchFailed)
+    Err(SolverError::LineSearchFailed)
 }
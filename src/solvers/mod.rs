@@ -1,14 +1,213 @@
-use std::error::Error;
+use std::time::Duration;
 
+use nalgebra::{DMatrix, DVector};
+use nalgebra_sparse::factorization::CscCholesky;
+use nalgebra_sparse::{CscMatrix, CsrMatrix};
+use serde::{Deserialize, Serialize};
+
+use crate::error::SolverError;
 use crate::sketch::Sketch;
 
 mod line_search;
 
 pub mod bfgs_solver;
+pub mod dogleg_solver;
 pub mod gauss_newton_solver;
 pub mod gradient_based_solver;
+pub mod lbfgs_solver;
 pub mod levenberg_marquardt;
 
+// The largest violation of an inequality constraint that is accepted after solving
+pub(crate) const INEQUALITY_TOLERANCE: f64 = 1e-6;
+
+// The reason a solver stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SolveStatus {
+    // The loss fell below the minimum loss of the solver
+    Converged,
+    // The solver could not make any more progress before reaching the minimum loss, e.g. because
+    // the gradient vanished in a local minimum of conflicting constraints
+    Stalled,
+    // The solver ran out of iterations before reaching the minimum loss
+    MaxIterations,
+    // The loss ended up larger than before solving or is not finite
+    Diverged,
+    // Some inequality constraints are still violated after solving. They are only penalized while
+    // violated, so a low loss alone does not mean the solution lies inside of their feasible region.
+    Infeasible,
+}
+
+// What happened while solving a sketch and how well the constraints are satisfied afterwards
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SolveReport {
+    pub status: SolveStatus,
+    pub iterations: usize,
+    pub final_loss: f64,
+    // The largest residual sqrt(2 * loss) of a single constraint
+    pub max_residual: f64,
+    // The losses of the constraints in the order of the constraints of the sketch
+    pub constraint_losses: DVector<f64>,
+    // The number of inequality constraints violated by more than INEQUALITY_TOLERANCE
+    pub violated_inequalities: usize,
+    pub wall_time: Duration,
+}
+
+impl SolveReport {
+    // Collects the losses of the solved sketch. The status of the solver is replaced by diverged if
+    // the loss got worse than the initial loss, or else by infeasible if inequality constraints are
+    // violated.
+    pub(crate) fn new(
+        sketch: &Sketch,
+        status: SolveStatus,
+        iterations: usize,
+        initial_loss: f64,
+        wall_time: Duration,
+    ) -> Self {
+        let constraint_losses = sketch.get_loss_per_constraint();
+        let final_loss = constraint_losses.sum();
+        let max_residual = constraint_losses
+            .iter()
+            .map(|loss| (2.0 * loss).sqrt())
+            .fold(0.0, f64::max);
+        let violated_inequalities = sketch.count_violated_inequalities(INEQUALITY_TOLERANCE);
+        let status = if !final_loss.is_finite() || final_loss > initial_loss {
+            SolveStatus::Diverged
+        } else if violated_inequalities > 0 {
+            SolveStatus::Infeasible
+        } else {
+            status
+        };
+
+        Self {
+            status,
+            iterations,
+            final_loss,
+            max_residual,
+            constraint_losses,
+            violated_inequalities,
+            wall_time,
+        }
+    }
+}
+
+// The normal equations J^T * J * step = -J^T * r of the linearized least squares problem
+// min |r + J * step|. J^T * J stays sparse, as every constraint only couples the primitives it
+// references.
+pub(crate) struct NormalEquations {
+    hessian: CsrMatrix<f64>,
+    // The gradient J^T * r of the loss
+    pub gradient: DVector<f64>,
+}
+
+impl NormalEquations {
+    pub fn new(jacobian: &CsrMatrix<f64>, residuals: &DVector<f64>) -> Self {
+        let jacobian_transposed = jacobian.transpose();
+        Self {
+            gradient: &jacobian_transposed * residuals,
+            hessian: &jacobian_transposed * jacobian,
+        }
+    }
+
+    pub fn max_diagonal(&self) -> f64 {
+        self.hessian
+            .diagonal_as_csr()
+            .values()
+            .iter()
+            .fold(0.0, |a, b| a.max(*b))
+    }
+
+    // Solves (J^T * J + damping * I) * step = -J^T * r with a sparse cholesky factorization. Any
+    // positive damping makes the system positive definite, even if the constraints do not
+    // determine all degrees of freedom.
+    pub fn solve_damped(&self, damping: f64) -> Result<DVector<f64>, SolverError> {
+        let n = self.gradient.len();
+        let damped = &self.hessian + CsrMatrix::identity(n) * damping;
+        let cholesky = CscCholesky::factor(&CscMatrix::from(&damped)).map_err(|_| {
+            SolverError::LinearSystemFailed("the normal equations are not positive definite")
+        })?;
+        let rhs = DMatrix::from_column_slice(n, 1, (-&self.gradient).as_slice());
+        Ok(cholesky.solve(&rhs).column(0).into_owned())
+    }
+}
+
 pub trait Solver {
-    fn solve(&self, sketch: &mut Sketch) -> Result<(), Box<dyn Error>>;
+    fn solve(&self, sketch: &mut Sketch) -> Result<SolveReport, SolverError>;
+}
+
+// Sketches shared by the tests of the solvers
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::{
+        constraints::{
+            coincident::point_on_line::PointOnLine,
+            distance::euclidian_distance_between_points::EuclidianDistanceBetweenPoints,
+            ConstraintCell,
+        },
+        primitives::{line::Line, point2::Point2, PrimitiveCell},
+        sketch::Sketch,
+        solvers::{SolveStatus, Solver},
+    };
+
+    // Two points with the given target distances between them
+    pub(crate) fn distance_sketch(distances: &[f64]) -> Sketch {
+        let mut sketch = Sketch::new();
+        let point1 = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let point2 = Rc::new(RefCell::new(Point2::new(3.0, 1.0)));
+        sketch
+            .add_primitive(PrimitiveCell::Point2(point1.clone()))
+            .unwrap();
+        sketch
+            .add_primitive(PrimitiveCell::Point2(point2.clone()))
+            .unwrap();
+        for distance in distances {
+            sketch
+                .add_constraint(ConstraintCell::EuclideanDistance(Rc::new(RefCell::new(
+                    EuclidianDistanceBetweenPoints::new(point1.clone(), point2.clone(), *distance),
+                ))))
+                .unwrap();
+        }
+        sketch
+    }
+
+    // The point is far away along a short line, so turning the line moves it off the line by a
+    // lever of 1e5. Every step along the gradient overshoots, no matter how often the line search
+    // halves it.
+    pub(crate) fn lever_sketch() -> Sketch {
+        let mut sketch = Sketch::new();
+        let start = Rc::new(RefCell::new(Point2::new(0.0, 0.0)));
+        let end = Rc::new(RefCell::new(Point2::new(1.0, 0.0)));
+        let point = Rc::new(RefCell::new(Point2::new(1e5, 1.0)));
+        let line = Rc::new(RefCell::new(Line::new(start.clone(), end.clone())));
+        for p in [&start, &end, &point] {
+            sketch
+                .add_primitive(PrimitiveCell::Point2(p.clone()))
+                .unwrap();
+        }
+        sketch
+            .add_primitive(PrimitiveCell::Line(line.clone()))
+            .unwrap();
+        sketch
+            .add_constraint(ConstraintCell::PointOnLine(Rc::new(RefCell::new(
+                PointOnLine::new(point, line),
+            ))))
+            .unwrap();
+        sketch
+    }
+
+    // A failed line search must leave the sketch at the last accepted point, which is the initial
+    // one for the lever sketch
+    pub(crate) fn check_line_search_failure(solver: &impl Solver) {
+        let mut sketch = lever_sketch();
+        let initial_data = sketch.get_data();
+        let initial_loss = sketch.get_loss();
+        let report = solver.solve(&mut sketch).unwrap();
+
+        assert_eq!(report.status, SolveStatus::Stalled);
+        assert_eq!(report.iterations, 0);
+        assert_eq!(sketch.get_data(), initial_data);
+        assert_eq!(report.final_loss, initial_loss);
+    }
 }